@@ -1,25 +1,31 @@
-use geodesy::ellps::Ellipsoid as GeodesyEllipsoid;
-use healpix_geo_core::ellipsoid::{
-    Ellipsoid as RustEllipsoid, ReferenceEllipsoid, ReferenceSphere,
-};
+use healpix_geo_core::ellipsoid::{Ellipsoid as RustEllipsoid, ReferenceBody};
 use serde::Deserialize;
 use serde_wasm_bindgen::from_value;
+use std::collections::HashMap;
 use wasm_bindgen::prelude::*;
 
 #[derive(Deserialize, Debug, PartialEq)]
 #[wasm_bindgen]
 pub enum EllipsoidLike {
+    // needs to come before the other variants, which ignore the semimedian axis
+    #[serde(untagged)]
+    Triaxial(Triaxial),
     #[serde(untagged)]
     EllipsoidInverseFlattening(EllipsoidInverseFlattening),
     #[serde(untagged)]
     EllipsoidSemiMinorAxis(EllipsoidSemiMinorAxis),
     #[serde(untagged)]
+    EllipsoidFlattening(EllipsoidFlattening),
+    #[serde(untagged)]
+    EllipsoidEccentricity(EllipsoidEccentricity),
+    #[serde(untagged)]
     Sphere(Sphere),
 }
 
 #[derive(Deserialize, Debug, PartialEq)]
 #[wasm_bindgen]
 pub struct EllipsoidInverseFlattening {
+    #[serde(alias = "semimajor_axis")]
     pub semi_major_axis: f64,
     pub inverse_flattening: f64,
 }
@@ -27,7 +33,36 @@ pub struct EllipsoidInverseFlattening {
 #[derive(Deserialize, Debug, PartialEq)]
 #[wasm_bindgen]
 pub struct EllipsoidSemiMinorAxis {
+    #[serde(alias = "semimajor_axis")]
     pub semi_major_axis: f64,
+    #[serde(alias = "semiminor_axis")]
+    pub semi_minor_axis: f64,
+}
+
+#[derive(Deserialize, Debug, PartialEq)]
+#[wasm_bindgen]
+pub struct EllipsoidFlattening {
+    #[serde(alias = "semimajor_axis")]
+    pub semi_major_axis: f64,
+    pub flattening: f64,
+}
+
+#[derive(Deserialize, Debug, PartialEq)]
+#[wasm_bindgen]
+pub struct EllipsoidEccentricity {
+    #[serde(alias = "semimajor_axis")]
+    pub semi_major_axis: f64,
+    pub eccentricity: f64,
+}
+
+#[derive(Deserialize, Debug, PartialEq)]
+#[wasm_bindgen]
+pub struct Triaxial {
+    #[serde(alias = "semimajor_axis")]
+    pub semi_major_axis: f64,
+    #[serde(alias = "semimedian_axis")]
+    pub semi_median_axis: f64,
+    #[serde(alias = "semiminor_axis")]
     pub semi_minor_axis: f64,
 }
 
@@ -62,32 +97,55 @@ pub fn parse_ellipsoid(obj: JsValue) -> Result<EllipsoidLike, JsValue> {
     } else {
         from_value(obj)?
     };
+
+    // reject invalid parameters early
+    RustEllipsoid::from_mapping(&ellipsoid_like.to_mapping())
+        .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
     Ok(ellipsoid_like)
 }
 
 impl EllipsoidLike {
-    pub fn into_ellipsoid(self) -> RustEllipsoid {
-        match self {
-            Self::EllipsoidInverseFlattening(ell) => {
-                let ellipsoid =
-                    GeodesyEllipsoid::new(ell.semi_major_axis, 1.0f64 / ell.inverse_flattening);
-
-                RustEllipsoid::Ellipsoid(ReferenceEllipsoid::new(ellipsoid))
-            }
-            Self::EllipsoidSemiMinorAxis(ell) => {
-                let a = ell.semi_major_axis;
-                let b = ell.semi_minor_axis;
-                let flattening = (a - b) / a;
-                let ellipsoid = GeodesyEllipsoid::new(a, flattening);
-
-                RustEllipsoid::Ellipsoid(ReferenceEllipsoid::new(ellipsoid))
-            }
-            Self::Sphere(sphere) => {
-                let ellipsoid = GeodesyEllipsoid::new(sphere.radius, 0.0f64);
+    /// The parameters, using the names understood by `ReferenceBody::from_mapping`
+    pub fn to_mapping(&self) -> HashMap<String, f64> {
+        let parameters = match self {
+            Self::EllipsoidInverseFlattening(ell) => [
+                ("semi_major_axis", ell.semi_major_axis),
+                ("inverse_flattening", ell.inverse_flattening),
+            ]
+            .to_vec(),
+            Self::EllipsoidSemiMinorAxis(ell) => [
+                ("semi_major_axis", ell.semi_major_axis),
+                ("semi_minor_axis", ell.semi_minor_axis),
+            ]
+            .to_vec(),
+            Self::EllipsoidFlattening(ell) => [
+                ("semi_major_axis", ell.semi_major_axis),
+                ("flattening", ell.flattening),
+            ]
+            .to_vec(),
+            Self::EllipsoidEccentricity(ell) => [
+                ("semi_major_axis", ell.semi_major_axis),
+                ("eccentricity", ell.eccentricity),
+            ]
+            .to_vec(),
+            Self::Sphere(sphere) => [("radius", sphere.radius)].to_vec(),
+            Self::Triaxial(ell) => [
+                ("semi_major_axis", ell.semi_major_axis),
+                ("semi_median_axis", ell.semi_median_axis),
+                ("semi_minor_axis", ell.semi_minor_axis),
+            ]
+            .to_vec(),
+        };
+
+        parameters
+            .into_iter()
+            .map(|(name, value)| (name.to_string(), value))
+            .collect()
+    }
 
-                RustEllipsoid::Sphere(ReferenceSphere::new(ellipsoid))
-            }
-        }
+    pub fn into_ellipsoid(self) -> Result<RustEllipsoid, JsError> {
+        RustEllipsoid::from_mapping(&self.to_mapping()).map_err(|e| JsError::new(&e.to_string()))
     }
 }
 
@@ -108,7 +166,7 @@ mod tests {
             inverse_flattening: if_,
         });
 
-        let actual = obj.into_ellipsoid();
+        let actual = obj.into_ellipsoid().unwrap();
         match actual {
             RustEllipsoid::Ellipsoid(ell) => {
                 let unpacked = ell.ellipsoid();
@@ -152,6 +210,78 @@ mod tests {
         assert_eq!(actual, expected);
     }
 
+    #[test]
+    fn test_ellipsoidlike_alternatives() {
+        let a: f64 = 6378137.0;
+        let f: f64 = 1.0 / 298.257223563;
+
+        let alternatives = [
+            EllipsoidLike::EllipsoidSemiMinorAxis(EllipsoidSemiMinorAxis {
+                semi_major_axis: a,
+                semi_minor_axis: a * (1.0 - f),
+            }),
+            EllipsoidLike::EllipsoidFlattening(EllipsoidFlattening {
+                semi_major_axis: a,
+                flattening: f,
+            }),
+            EllipsoidLike::EllipsoidEccentricity(EllipsoidEccentricity {
+                semi_major_axis: a,
+                eccentricity: (f * (2.0 - f)).sqrt(),
+            }),
+        ];
+
+        for obj in alternatives {
+            let unpacked = *obj.into_ellipsoid().unwrap().ellipsoid();
+
+            assert_eq!(unpacked.semimajor_axis(), a);
+            assert!((unpacked.flattening() - f).abs() < 1e-15);
+        }
+    }
+
+    #[test]
+    fn test_deserialize_ellipsoid_alternative_spelling() {
+        let json = r#"{"semimajor_axis": 6378137.0, "semiminor_axis": 6356752.314245179}"#;
+
+        let actual: EllipsoidLike = serde_json::from_str(json).unwrap();
+        let expected = EllipsoidLike::EllipsoidSemiMinorAxis(EllipsoidSemiMinorAxis {
+            semi_major_axis: 6378137.0,
+            semi_minor_axis: 6356752.314245179,
+        });
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_deserialize_ellipsoid_eccentricity() {
+        let json = r#"{"semi_major_axis": 6378137.0, "eccentricity": 0.0818191908426215}"#;
+
+        let actual: EllipsoidLike = serde_json::from_str(json).unwrap();
+        let expected = EllipsoidLike::EllipsoidEccentricity(EllipsoidEccentricity {
+            semi_major_axis: 6378137.0,
+            eccentricity: 0.0818191908426215,
+        });
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_deserialize_triaxial() {
+        let json = r#"{"semi_major_axis": 6378171.36, "semi_median_axis": 6378102.0, "semi_minor_axis": 6356752.0}"#;
+
+        let actual: EllipsoidLike = serde_json::from_str(json).unwrap();
+        let expected = EllipsoidLike::Triaxial(Triaxial {
+            semi_major_axis: 6378171.36,
+            semi_median_axis: 6378102.0,
+            semi_minor_axis: 6356752.0,
+        });
+
+        assert_eq!(actual, expected);
+        assert!(matches!(
+            actual.into_ellipsoid().unwrap(),
+            RustEllipsoid::Triaxial(_)
+        ));
+    }
+
     #[test]
     fn test_deserialize_sphere() {
         let json = r#"{"name": "sphere", "radius": 6370997.0}"#;
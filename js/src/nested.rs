@@ -25,14 +25,21 @@ impl Nested {
 
     /// Center coordinates for the given cell
     #[wasm_bindgen(js_name = healpixToLonLat)]
-    pub fn healpix_to_lonlat(ipix: u64, depth: u8, ellipsoid: Option<EllipsoidLike>) -> Coordinate {
+    pub fn healpix_to_lonlat(
+        ipix: u64,
+        depth: u8,
+        ellipsoid: Option<EllipsoidLike>,
+    ) -> Result<Coordinate, JsError> {
         let layer = healpix::nested::get(depth);
 
-        let ellipsoid_ = ellipsoid.map(|e| e.into_ellipsoid()).unwrap_or_default();
+        let ellipsoid_ = ellipsoid
+            .map(EllipsoidLike::into_ellipsoid)
+            .transpose()?
+            .unwrap_or_default();
 
         let (lon, lat) = scalar::healpix_to_lonlat(&ipix, layer, &ellipsoid_);
 
-        Coordinate { lon, lat }
+        Ok(Coordinate { lon, lat })
     }
 
     /// Project the given coordinate to the healpix grid
@@ -42,11 +49,14 @@ impl Nested {
         lat: f64,
         depth: u8,
         ellipsoid: Option<EllipsoidLike>,
-    ) -> u64 {
+    ) -> Result<u64, JsError> {
         let layer = healpix::nested::get(depth);
-        let ellipsoid_ = ellipsoid.map(|e| e.into_ellipsoid()).unwrap_or_default();
+        let ellipsoid_ = ellipsoid
+            .map(EllipsoidLike::into_ellipsoid)
+            .transpose()?
+            .unwrap_or_default();
 
-        scalar::lonlat_to_healpix(&lon, &lat, layer, &ellipsoid_)
+        Ok(scalar::lonlat_to_healpix(&lon, &lat, layer, &ellipsoid_))
     }
 
     /// Single vertex of the given cell
@@ -59,17 +69,21 @@ impl Nested {
         u: f64,
         v: f64,
         ellipsoid: Option<EllipsoidLike>,
-    ) -> Coordinate {
+    ) -> Result<Coordinate, JsError> {
         let layer = healpix::nested::get(depth);
-        let ellipsoid_ = ellipsoid.map(|e| e.into_ellipsoid()).unwrap_or_default();
+        let ellipsoid_ = ellipsoid
+            .map(EllipsoidLike::into_ellipsoid)
+            .transpose()?
+            .unwrap_or_default();
 
         let center = layer.center_of_projected_cell(hash);
-        let (lon, lat) = spherical_vertex(center, depth, (u, v));
+        let vertex = spherical_vertex(center, depth, (u, v));
+        let (lon, lat) = ellipsoid_.authalic_to_geographic(&vertex);
 
-        Coordinate {
+        Ok(Coordinate {
             lon: lon.to_degrees().rem_euclid(360.0),
-            lat: ellipsoid_.latitude_authalic_to_geographic(lat).to_degrees(),
-        }
+            lat: lat.to_degrees(),
+        })
     }
 }
 
@@ -95,7 +109,7 @@ mod tests {
 
         let values = uv
             .into_iter()
-            .map(|(u, v)| Nested::vertex(hash, depth, u, v, None))
+            .map(|(u, v)| Nested::vertex(hash, depth, u, v, None).unwrap())
             .collect::<Vec<_>>();
         let expected: Vec<Coordinate> = vec![
             (45.0, 0.0),
@@ -137,7 +151,7 @@ mod tests {
         // authalic -> geographic conversion produces a measurable difference
         let ipix: u64 = 0;
 
-        let sphere_default = Nested::healpix_to_lonlat(ipix, depth, None);
+        let sphere_default = Nested::healpix_to_lonlat(ipix, depth, None).unwrap();
 
         // WGS84: the geographic latitude differs measurably from the authalic
         // (spherical) latitude, while the longitude is unaffected
@@ -145,7 +159,7 @@ mod tests {
             semi_major_axis: 6378137.0,
             inverse_flattening: 298.257223563,
         });
-        let ellipsoidal = Nested::healpix_to_lonlat(ipix, depth, Some(wgs84));
+        let ellipsoidal = Nested::healpix_to_lonlat(ipix, depth, Some(wgs84)).unwrap();
 
         assert!((sphere_default.lon - ellipsoidal.lon).abs() < 1e-9);
         assert!((sphere_default.lat - ellipsoidal.lat).abs() > 1e-3);
@@ -154,7 +168,7 @@ mod tests {
         let sphere = EllipsoidLike::Sphere(Sphere {
             radius: 6_371_000.0,
         });
-        let explicit_sphere = Nested::healpix_to_lonlat(ipix, depth, Some(sphere));
+        let explicit_sphere = Nested::healpix_to_lonlat(ipix, depth, Some(sphere)).unwrap();
 
         assert!((sphere_default.lon - explicit_sphere.lon).abs() < 1e-9);
         assert!((sphere_default.lat - explicit_sphere.lat).abs() < 1e-9);
@@ -28,14 +28,21 @@ impl Ring {
 
     /// Center coordinates for the given cell
     #[wasm_bindgen(js_name = healpixToLonLat)]
-    pub fn healpix_to_lonlat(hash: u64, depth: u8, ellipsoid: Option<EllipsoidLike>) -> Coordinate {
+    pub fn healpix_to_lonlat(
+        hash: u64,
+        depth: u8,
+        ellipsoid: Option<EllipsoidLike>,
+    ) -> Result<Coordinate, JsError> {
         let layer = healpix::nested::get(depth);
-        let ellipsoid_ = ellipsoid.map(|e| e.into_ellipsoid()).unwrap_or_default();
+        let ellipsoid_ = ellipsoid
+            .map(EllipsoidLike::into_ellipsoid)
+            .transpose()?
+            .unwrap_or_default();
         let hash_ = layer.from_ring(hash);
 
         let (lon, lat) = scalar::healpix_to_lonlat(&hash_, layer, &ellipsoid_);
 
-        Coordinate { lon, lat }
+        Ok(Coordinate { lon, lat })
     }
 
     /// Project the given coordinate to the healpix grid
@@ -45,11 +52,14 @@ impl Ring {
         lat: f64,
         depth: u8,
         ellipsoid: Option<EllipsoidLike>,
-    ) -> u64 {
+    ) -> Result<u64, JsError> {
         let layer = healpix::nested::get(depth);
-        let ellipsoid_ = ellipsoid.map(|e| e.into_ellipsoid()).unwrap_or_default();
+        let ellipsoid_ = ellipsoid
+            .map(EllipsoidLike::into_ellipsoid)
+            .transpose()?
+            .unwrap_or_default();
 
-        layer.to_ring(scalar::lonlat_to_healpix(&lon, &lat, layer, &ellipsoid_))
+        Ok(layer.to_ring(scalar::lonlat_to_healpix(&lon, &lat, layer, &ellipsoid_)))
     }
 
     /// Single vertex of the given cell
@@ -62,17 +72,21 @@ impl Ring {
         u: f64,
         v: f64,
         ellipsoid: Option<EllipsoidLike>,
-    ) -> Coordinate {
+    ) -> Result<Coordinate, JsError> {
         let layer = healpix::nested::get(depth);
-        let ellipsoid_ = ellipsoid.map(|e| e.into_ellipsoid()).unwrap_or_default();
+        let ellipsoid_ = ellipsoid
+            .map(EllipsoidLike::into_ellipsoid)
+            .transpose()?
+            .unwrap_or_default();
 
         let center = layer.center_of_projected_cell(layer.from_ring(hash));
-        let (lon, lat) = spherical_vertex(center, depth, (u, v));
+        let vertex = spherical_vertex(center, depth, (u, v));
+        let (lon, lat) = ellipsoid_.authalic_to_geographic(&vertex);
 
-        Coordinate {
+        Ok(Coordinate {
             lon: lon.to_degrees().rem_euclid(360.0),
-            lat: ellipsoid_.latitude_authalic_to_geographic(lat).to_degrees(),
-        }
+            lat: lat.to_degrees(),
+        })
     }
 }
 
@@ -98,7 +112,7 @@ mod tests {
 
         let values = uv
             .into_iter()
-            .map(|(u, v)| Ring::vertex(hash, depth, u, v, None))
+            .map(|(u, v)| Ring::vertex(hash, depth, u, v, None).unwrap())
             .collect::<Vec<_>>();
         let expected: Vec<Coordinate> = vec![
             (45.0, 0.0),
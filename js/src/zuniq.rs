@@ -28,12 +28,18 @@ impl Zuniq {
 
     /// Center coordinates for the given cell
     #[wasm_bindgen(js_name = healpixToLonLat)]
-    pub fn healpix_to_lonlat(hash: u64, ellipsoid: Option<EllipsoidLike>) -> Coordinate {
-        let ellipsoid_ = ellipsoid.map(|e| e.into_ellipsoid()).unwrap_or_default();
+    pub fn healpix_to_lonlat(
+        hash: u64,
+        ellipsoid: Option<EllipsoidLike>,
+    ) -> Result<Coordinate, JsError> {
+        let ellipsoid_ = ellipsoid
+            .map(EllipsoidLike::into_ellipsoid)
+            .transpose()?
+            .unwrap_or_default();
 
         let (lon, lat) = scalar::healpix_to_lonlat(&hash, &ellipsoid_);
 
-        Coordinate { lon, lat }
+        Ok(Coordinate { lon, lat })
     }
 
     /// Project the given coordinate to the healpix grid
@@ -43,29 +49,41 @@ impl Zuniq {
         lat: f64,
         depth: u8,
         ellipsoid: Option<EllipsoidLike>,
-    ) -> u64 {
+    ) -> Result<u64, JsError> {
         let layer = healpix::nested::get(depth);
-        let ellipsoid_ = ellipsoid.map(|e| e.into_ellipsoid()).unwrap_or_default();
+        let ellipsoid_ = ellipsoid
+            .map(EllipsoidLike::into_ellipsoid)
+            .transpose()?
+            .unwrap_or_default();
 
-        scalar::lonlat_to_healpix(&lon, &lat, layer, &ellipsoid_)
+        Ok(scalar::lonlat_to_healpix(&lon, &lat, layer, &ellipsoid_))
     }
 
     /// Single vertex of the given cell
     ///
     /// The parameters `u` and `v` represent offsets from the southern vertex of the given cell.
     #[wasm_bindgen(js_name = vertex)]
-    pub fn vertex(hash: u64, u: f64, v: f64, ellipsoid: Option<EllipsoidLike>) -> Coordinate {
+    pub fn vertex(
+        hash: u64,
+        u: f64,
+        v: f64,
+        ellipsoid: Option<EllipsoidLike>,
+    ) -> Result<Coordinate, JsError> {
         let (depth, nested) = healpix::nested::from_zuniq(hash);
         let layer = healpix::nested::get(depth);
-        let ellipsoid_ = ellipsoid.map(|e| e.into_ellipsoid()).unwrap_or_default();
+        let ellipsoid_ = ellipsoid
+            .map(EllipsoidLike::into_ellipsoid)
+            .transpose()?
+            .unwrap_or_default();
 
         let center = layer.center_of_projected_cell(nested);
-        let (lon, lat) = spherical_vertex(center, depth, (u, v));
+        let vertex = spherical_vertex(center, depth, (u, v));
+        let (lon, lat) = ellipsoid_.authalic_to_geographic(&vertex);
 
-        Coordinate {
+        Ok(Coordinate {
             lon: lon.to_degrees().rem_euclid(360.0),
-            lat: ellipsoid_.latitude_authalic_to_geographic(lat).to_degrees(),
-        }
+            lat: lat.to_degrees(),
+        })
     }
 }
 
@@ -90,7 +108,7 @@ mod tests {
 
         let values = uv
             .into_iter()
-            .map(|(u, v)| Zuniq::vertex(hash, u, v, None))
+            .map(|(u, v)| Zuniq::vertex(hash, u, v, None).unwrap())
             .collect::<Vec<_>>();
         let expected: Vec<Coordinate> = vec![
             (45.0, 0.0),
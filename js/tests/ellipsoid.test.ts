@@ -45,4 +45,26 @@ describe("parseEllipsoid", () => {
       ellipsoid.semi_minor_axis,
     );
   });
+  test("ellipsoid from semi-major axis and flattening", () => {
+    const ellipsoid = {
+      semi_major_axis: 6378137.0,
+      flattening: 1 / 298.257223563,
+    };
+    const result = healpixGeo.parseEllipsoid(ellipsoid);
+    expect(result).to.have.property("flattening", ellipsoid.flattening);
+  });
+  test("ellipsoid from semi-major axis and eccentricity", () => {
+    const ellipsoid = {
+      semi_major_axis: 6378137.0,
+      eccentricity: 0.0818191908426215,
+    };
+    const result = healpixGeo.parseEllipsoid(ellipsoid);
+    expect(result).to.have.property("eccentricity", ellipsoid.eccentricity);
+  });
+  test("invalid parameters", () => {
+    const ellipsoid = { semi_major_axis: 6378137.0, inverse_flattening: 1.5 };
+    expect(() => healpixGeo.parseEllipsoid(ellipsoid)).toThrow(
+      "The inverse flattening must be greater than or equal to 2",
+    );
+  });
 });
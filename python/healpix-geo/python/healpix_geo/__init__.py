@@ -3,6 +3,9 @@ import numpy as np
 from healpix_geo import geometry, healpix_geo, nested, ring, zuniq
 from healpix_geo.geometry import Bbox
 
+ellipsoid_names = healpix_geo.ellipsoid_names
+InputTransform = healpix_geo.InputTransform
+
 
 def cartesian_to_lonlat(x, y, z, ellipsoid="sphere", num_threads=0):
     """Convert cartesian coordinates to geographic coordinates on the surface of the ellipsoid.
@@ -98,4 +101,6 @@ __all__ = [
     "slices",
     "geometry",
     "Bbox",
+    "ellipsoid_names",
+    "InputTransform",
 ]
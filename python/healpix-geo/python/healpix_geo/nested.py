@@ -2,9 +2,17 @@ import marray
 import numpy as np
 
 from healpix_geo import healpix_geo
-from healpix_geo.utils import _check_depth, _check_ipixels, _check_ring
+from healpix_geo.utils import (
+    _as_float,
+    _check_depth,
+    _check_ipixels,
+    _check_ring,
+    _float_dtype,
+    _transform_points,
+)
 
 RangeMOCIndex = healpix_geo.nested.RangeMOCIndex
+RangeMOCIndexBuilder = healpix_geo.nested.RangeMOCIndexBuilder
 internal_boundary = healpix_geo.nested.internal_boundary
 
 
@@ -12,7 +20,7 @@ def create_empty(depth):
     return RangeMOCIndex.empty(depth)
 
 
-def healpix_to_lonlat(ipix, depth, ellipsoid="sphere", num_threads=0):
+def healpix_to_lonlat(ipix, depth, ellipsoid="sphere", num_threads=0, dtype="float64"):
     r"""Get the longitudes and latitudes of the center of some HEALPix cells.
 
     Parameters
@@ -29,6 +37,9 @@ def healpix_to_lonlat(ipix, depth, ellipsoid="sphere", num_threads=0):
         Specifies the number of threads to use for the computation. Default to 0 means
         it will choose the number of threads based on the RAYON_NUM_THREADS environment variable (if set),
         or the number of logical CPUs (otherwise)
+    dtype : str or numpy.dtype, default: "float64"
+        The floating point type of the returned coordinates, either ``float32``
+        or ``float64``.
 
     Returns
     -------
@@ -41,6 +52,8 @@ def healpix_to_lonlat(ipix, depth, ellipsoid="sphere", num_threads=0):
         When the HEALPix cell indexes given have values out of :math:`[0, 4^{29 - depth}[`.
     ValueError
         When the name of the ellipsoid is unknown.
+    ValueError
+        When the dtype is neither ``float32`` nor ``float64``.
 
     Examples
     --------
@@ -60,11 +73,16 @@ def healpix_to_lonlat(ipix, depth, ellipsoid="sphere", num_threads=0):
     ipix = ipix.astype(np.uint64)
 
     num_threads = np.uint16(num_threads)
+    dtype = _float_dtype(dtype)
 
-    return healpix_geo.nested.healpix_to_lonlat(depth, ipix, ellipsoid, num_threads)
+    return healpix_geo.nested.healpix_to_lonlat(
+        depth, ipix, ellipsoid, num_threads, dtype
+    )
 
 
-def lonlat_to_healpix(longitude, latitude, depth, ellipsoid="sphere", num_threads=0):
+def lonlat_to_healpix(
+    longitude, latitude, depth, ellipsoid="sphere", num_threads=0, transform=None
+):
     r"""Get the HEALPix indexes that contains specific points.
 
     Parameters
@@ -83,6 +101,11 @@ def lonlat_to_healpix(longitude, latitude, depth, ellipsoid="sphere", num_thread
         Specifies the number of threads to use for the computation. Default to 0 means
         it will choose the number of threads based on the RAYON_NUM_THREADS environment variable (if set),
         or the number of logical CPUs (otherwise)
+    transform : healpix_geo.InputTransform, optional
+        A geodesy operator applied to the input coordinates before computing
+        the cells, for example to convert from a different datum or from
+        projected coordinates. The longitudes and latitudes are then the
+        coordinates expected by the operator.
 
     Returns
     -------
@@ -96,6 +119,11 @@ def lonlat_to_healpix(longitude, latitude, depth, ellipsoid="sphere", num_thread
     ValueError
         When the name of the ellipsoid is unknown.
 
+    Notes
+    -----
+    ``float32`` coordinates are passed on without conversion, all other dtypes
+    are cast to ``float64``.
+
     Examples
     --------
     >>> from healpix_geo.nested import lonlat_to_healpix
@@ -108,8 +136,14 @@ def lonlat_to_healpix(longitude, latitude, depth, ellipsoid="sphere", num_thread
     array([304, 573,  38], dtype=uint64)
     """
     _check_depth(depth)
-    longitude = np.atleast_1d(longitude).astype("float64")
-    latitude = np.atleast_1d(latitude).astype("float64")
+    longitude, latitude = _as_float(longitude, latitude)
+
+    if transform is not None:
+        longitude, latitude = transform.apply(
+            longitude.astype("float64"),
+            latitude.astype("float64"),
+            num_threads=num_threads,
+        )
 
     num_threads = np.uint16(num_threads)
 
@@ -122,7 +156,9 @@ def lonlat_to_healpix(longitude, latitude, depth, ellipsoid="sphere", num_thread
     )
 
 
-def healpix_to_cartesian(ipix, depth, ellipsoid="sphere", num_threads=0):
+def healpix_to_cartesian(
+    ipix, depth, ellipsoid="sphere", num_threads=0, dtype="float64"
+):
     r"""Get the cartesian coordinates of the center of the given HEALPix cells.
 
     Parameters
@@ -139,6 +175,9 @@ def healpix_to_cartesian(ipix, depth, ellipsoid="sphere", num_threads=0):
         Specifies the number of threads to use for the computation. Default to 0 means
         it will choose the number of threads based on the RAYON_NUM_THREADS environment variable (if set),
         or the number of logical CPUs (otherwise)
+    dtype : str or numpy.dtype, default: "float64"
+        The floating point type of the returned coordinates, either ``float32``
+        or ``float64``.
 
     Returns
     -------
@@ -151,6 +190,8 @@ def healpix_to_cartesian(ipix, depth, ellipsoid="sphere", num_threads=0):
         When the HEALPix cell indexes given have values out of :math:`[0, 4^{29 - depth}[`.
     ValueError
         When the name of the ellipsoid is unknown.
+    ValueError
+        When the dtype is neither ``float32`` nor ``float64``.
 
     Examples
     --------
@@ -172,11 +213,16 @@ def healpix_to_cartesian(ipix, depth, ellipsoid="sphere", num_threads=0):
     ipix = ipix.astype(np.uint64)
 
     num_threads = np.uint16(num_threads)
+    dtype = _float_dtype(dtype)
 
-    return healpix_geo.nested.healpix_to_cartesian(depth, ipix, ellipsoid, num_threads)
+    return healpix_geo.nested.healpix_to_cartesian(
+        depth, ipix, ellipsoid, num_threads, dtype
+    )
 
 
-def cartesian_to_healpix(x, y, z, depth, ellipsoid="sphere", num_threads=0):
+def cartesian_to_healpix(
+    x, y, z, depth, ellipsoid="sphere", num_threads=0, transform=None
+):
     r"""Get the HEALPix indexes that contain specific points.
 
     Parameters
@@ -197,6 +243,10 @@ def cartesian_to_healpix(x, y, z, depth, ellipsoid="sphere", num_threads=0):
         Specifies the number of threads to use for the computation. Default to 0 means
         it will choose the number of threads based on the RAYON_NUM_THREADS environment variable (if set),
         or the number of logical CPUs (otherwise)
+    transform : healpix_geo.InputTransform, optional
+        A geodesy operator applied to the cartesian coordinates before computing
+        the cells, for example a Helmert transformation from the cartesian frame
+        of a different datum.
 
     Returns
     -------
@@ -210,6 +260,11 @@ def cartesian_to_healpix(x, y, z, depth, ellipsoid="sphere", num_threads=0):
     ValueError
         When the name of the ellipsoid is unknown.
 
+    Notes
+    -----
+    ``float32`` coordinates are passed on without conversion, all other dtypes
+    are cast to ``float64``.
+
     Examples
     --------
     >>> from healpix_geo.nested import cartesian_to_healpix
@@ -227,9 +282,15 @@ def cartesian_to_healpix(x, y, z, depth, ellipsoid="sphere", num_threads=0):
     array([304, 573,  38], dtype=uint64)
     """
     _check_depth(depth)
-    x = np.atleast_1d(x).astype("float64")
-    y = np.atleast_1d(y).astype("float64")
-    z = np.atleast_1d(z).astype("float64")
+    x, y, z = _as_float(x, y, z)
+
+    if transform is not None:
+        x, y, z = transform.apply_cartesian(
+            x.astype("float64"),
+            y.astype("float64"),
+            z.astype("float64"),
+            num_threads=num_threads,
+        )
 
     num_threads = np.uint16(num_threads)
 
@@ -308,7 +369,7 @@ def vertices(ipix, depth, ellipsoid="sphere", step=1, num_threads=0):
 
 
 def bilinear_interpolation(
-    longitude, latitude, depth, *, ellipsoid="sphere", num_threads=0
+    longitude, latitude, depth, *, ellipsoid="sphere", num_threads=0, transform=None
 ):
     """Get the cell ids and weights necessary to bilinearly interpolate the given values.
 
@@ -328,6 +389,11 @@ def bilinear_interpolation(
         Specifies the number of threads to use for the computation. Default to 0 means
         it will choose the number of threads based on the RAYON_NUM_THREADS environment variable (if set),
         or the number of logical CPUs (otherwise)
+    transform : healpix_geo.InputTransform, optional
+        A geodesy operator applied to the input coordinates before computing
+        the cells, for example to convert from a different datum or from
+        projected coordinates. The longitudes and latitudes are then the
+        coordinates expected by the operator.
 
     Returns
     -------
@@ -385,6 +451,11 @@ def bilinear_interpolation(
     longitude = np.atleast_1d(longitude).astype("float64")
     latitude = np.atleast_1d(latitude).astype("float64")
 
+    if transform is not None:
+        longitude, latitude = transform.apply(
+            longitude, latitude, num_threads=num_threads
+        )
+
     num_threads = np.uint16(num_threads)
 
     ipix, weights = healpix_geo.nested.bilinear_interpolation(
@@ -658,6 +729,145 @@ def angular_distances(from_, to_, depth, num_threads=0):
     return np.where(mask, np.reshape(distances, to_.shape), np.nan)
 
 
+def geodesic_distances(from_, to_, depth, ellipsoid="sphere", num_threads=0):
+    """Compute the geodesic distances between cell centers
+
+    Contrary to :py:func:`angular_distances`, the distances are measured along
+    the geodesics of the reference ellipsoid, using Karney's algorithm.
+
+    Parameters
+    ----------
+    from_ : numpy.ndarray
+        The source Healpix cell indexes given as a ``np.uint64`` numpy array. Should be 1D.
+    to_ : numpy.ndarray
+        The destination Healpix cell indexes given as a ``np.uint64`` numpy array.
+        Should be 2D. Cells set to ``-1`` are ignored.
+    depth : int
+        The depth of the Healpix cells.
+    ellipsoid : ellipsoid-like, default: "sphere"
+        Reference ellipsoid to evaluate healpix on. Triaxial ellipsoids are not
+        supported.
+    num_threads : int, default: 0
+        Specifies the number of threads to use for the computation. Default to 0 means
+        it will choose the number of threads based on the RAYON_NUM_THREADS environment variable (if set),
+        or the number of logical CPUs (otherwise)
+
+    Returns
+    -------
+    distances : numpy.ndarray
+        The geodesic distances in meters.
+
+    Raises
+    ------
+    ValueError
+        When the Healpix cell indexes given have values out of :math:`[0, 4^{depth}[`.
+    """
+    _check_depth(depth)
+
+    from_ = np.atleast_1d(from_)
+    _check_ipixels(data=from_, depth=depth)
+    from_ = from_.astype("uint64")
+
+    mask = to_ != -1
+    masked_to = np.where(mask, to_, 0)
+
+    to_ = np.atleast_1d(masked_to)
+    _check_ipixels(data=to_, depth=depth)
+    to_ = to_.astype("uint64")
+
+    if from_.shape != to_.shape and from_.shape != to_.shape[:-1]:
+        raise ValueError(
+            "The shape of `from_` must be compatible with the shape of `to_`:\n"
+            f"{to_.shape} or {to_.shape[:-1]} must be equal to {from_.shape}."
+        )
+
+    if from_.shape == to_.shape:
+        intermediate_shape = to_.shape + (1,)
+    else:
+        intermediate_shape = to_.shape
+
+    num_threads = np.uint16(num_threads)
+
+    distances = healpix_geo.nested.geodesic_distances(
+        depth, from_, np.reshape(to_, intermediate_shape), ellipsoid, num_threads
+    )
+
+    return np.where(mask, np.reshape(distances, to_.shape), np.nan)
+
+
+def point_geodesic_distances(
+    longitude, latitude, to_, depth, ellipsoid="sphere", num_threads=0
+):
+    """Compute the geodesic distances between points and cell centers
+
+    Parameters
+    ----------
+    longitude : numpy.ndarray
+        The longitudes of the points in degrees. Should be 1D.
+    latitude : numpy.ndarray
+        The latitudes of the points in degrees. Should be 1D.
+    to_ : numpy.ndarray
+        The destination Healpix cell indexes given as a ``np.uint64`` numpy array.
+        Should be 2D. Cells set to ``-1`` are ignored.
+    depth : int
+        The depth of the Healpix cells.
+    ellipsoid : ellipsoid-like, default: "sphere"
+        Reference ellipsoid to evaluate healpix on. Triaxial ellipsoids are not
+        supported.
+    num_threads : int, default: 0
+        Specifies the number of threads to use for the computation. Default to 0 means
+        it will choose the number of threads based on the RAYON_NUM_THREADS environment variable (if set),
+        or the number of logical CPUs (otherwise)
+
+    Returns
+    -------
+    distances : numpy.ndarray
+        The geodesic distances in meters.
+
+    Raises
+    ------
+    ValueError
+        When the Healpix cell indexes given have values out of :math:`[0, 4^{depth}[`.
+    """
+    _check_depth(depth)
+
+    longitude = np.atleast_1d(longitude).astype("float64")
+    latitude = np.atleast_1d(latitude).astype("float64")
+
+    mask = to_ != -1
+    masked_to = np.where(mask, to_, 0)
+
+    to_ = np.atleast_1d(masked_to)
+    _check_ipixels(data=to_, depth=depth)
+    to_ = to_.astype("uint64")
+
+    if longitude.shape != latitude.shape:
+        raise ValueError("`longitude` and `latitude` must have the same shape.")
+
+    if longitude.shape != to_.shape and longitude.shape != to_.shape[:-1]:
+        raise ValueError(
+            "The shape of the points must be compatible with the shape of `to_`:\n"
+            f"{to_.shape} or {to_.shape[:-1]} must be equal to {longitude.shape}."
+        )
+
+    if longitude.shape == to_.shape:
+        intermediate_shape = to_.shape + (1,)
+    else:
+        intermediate_shape = to_.shape
+
+    num_threads = np.uint16(num_threads)
+
+    distances = healpix_geo.nested.point_geodesic_distances(
+        depth, longitude,
+        latitude,
+        np.reshape(to_, intermediate_shape),
+        ellipsoid,
+        num_threads,
+    )
+
+    return np.where(mask, np.reshape(distances, to_.shape), np.nan)
+
+
 def zone_coverage(bbox, depth, *, ellipsoid="sphere", flat=True):
     """Search the cells covering the given bounding box
 
@@ -725,7 +935,7 @@ def box_coverage(center, size, angle, depth, *, ellipsoid="sphere", flat=True):
     )
 
 
-def polygon_coverage(vertices, depth, *, ellipsoid="sphere", flat=True):
+def polygon_coverage(vertices, depth, *, ellipsoid="sphere", flat=True, transform=None):
     """Search the cells covering the given polygon.
 
     Parameters
@@ -740,6 +950,11 @@ def polygon_coverage(vertices, depth, *, ellipsoid="sphere", flat=True):
         :py:func:`cdshealpix.nested.polygon_search`.
     flat : bool, default: True
         If ``True``, the cells returned will all be at the passed depth.
+    transform : healpix_geo.InputTransform, optional
+        A geodesy operator applied to the input coordinates before computing
+        the cells, for example to convert from a different datum or from
+        projected coordinates. The vertices are then the coordinates expected
+        by the operator.
 
     Returns
     -------
@@ -752,13 +967,22 @@ def polygon_coverage(vertices, depth, *, ellipsoid="sphere", flat=True):
     """
     _check_depth(depth)
 
+    vertices = _transform_points(transform, vertices)
+
     return healpix_geo.nested.polygon_coverage(
         depth, vertices, ellipsoid=ellipsoid, flat=flat
     )
 
 
 def cone_coverage(
-    center, radius, depth, *, delta_depth=0, ellipsoid="sphere", flat=True
+    center,
+    radius,
+    depth,
+    *,
+    delta_depth=0,
+    ellipsoid="sphere",
+    flat=True,
+    transform=None,
 ):
     """Search the cells covering the given cone
 
@@ -778,6 +1002,11 @@ def cone_coverage(
         :py:func:`cdshealpix.nested.cone_search`.
     flat : bool, default: True
         If ``True``, the cells returned will all be at the passed depth.
+    transform : healpix_geo.InputTransform, optional
+        A geodesy operator applied to the input coordinates before computing
+        the cells, for example to convert from a different datum or from
+        projected coordinates. The coordinates of the center are then the
+        coordinates expected by the operator.
 
     Returns
     -------
@@ -790,8 +1019,7 @@ def cone_coverage(
     """
     _check_depth(depth)
 
-    if not isinstance(center, tuple):
-        center = tuple(center)
+    center = tuple(_transform_points(transform, center))
 
     return healpix_geo.nested.cone_coverage(
         depth, center, radius, delta_depth=delta_depth, ellipsoid=ellipsoid, flat=flat
@@ -807,6 +1035,7 @@ def elliptical_cone_coverage(
     delta_depth=0,
     ellipsoid="sphere",
     flat=True,
+    transform=None,
 ):
     """Search the cells covering the given elliptical cone.
 
@@ -828,6 +1057,11 @@ def elliptical_cone_coverage(
         :py:func:`cdshealpix.nested.elliptical_cone_search`.
     flat : bool, default: True
         If ``True``, the cells returned will all be at the passed depth.
+    transform : healpix_geo.InputTransform, optional
+        A geodesy operator applied to the input coordinates before computing
+        the cells, for example to convert from a different datum or from
+        projected coordinates. The coordinates of the center are then the
+        coordinates expected by the operator.
 
     Returns
     -------
@@ -840,8 +1074,7 @@ def elliptical_cone_coverage(
     """
     _check_depth(depth)
 
-    if not isinstance(center, tuple):
-        center = tuple(center)
+    center = tuple(_transform_points(transform, center))
     if not isinstance(ellipse_geometry, tuple):
         ellipse_geometry = tuple(ellipse_geometry)
 
@@ -2,10 +2,17 @@ import marray
 import numpy as np
 
 from healpix_geo import healpix_geo
-from healpix_geo.utils import _check_depth, _check_ipixels, _check_ring
+from healpix_geo.utils import (
+    _as_float,
+    _check_depth,
+    _check_ipixels,
+    _check_ring,
+    _float_dtype,
+    _transform_points,
+)
 
 
-def healpix_to_lonlat(ipix, depth, ellipsoid="sphere", num_threads=0):
+def healpix_to_lonlat(ipix, depth, ellipsoid="sphere", num_threads=0, dtype="float64"):
     r"""Get the longitudes and latitudes of the center of some HEALPix cells.
 
     Parameters
@@ -21,6 +28,9 @@ def healpix_to_lonlat(ipix, depth, ellipsoid="sphere", num_threads=0):
         Specifies the number of threads to use for the computation. Default to 0 means
         it will choose the number of threads based on the RAYON_NUM_THREADS environment variable (if set),
         or the number of logical CPUs (otherwise)
+    dtype : str or numpy.dtype, default: "float64"
+        The floating point type of the returned coordinates, either ``float32``
+        or ``float64``.
 
     Returns
     -------
@@ -33,6 +43,8 @@ def healpix_to_lonlat(ipix, depth, ellipsoid="sphere", num_threads=0):
         When the HEALPix cell indexes given have values out of :math:`[0, 4^{29 - depth}[`.
     ValueError
         When the name of the ellipsoid is unknown.
+    ValueError
+        When the dtype is neither ``float32`` nor ``float64``.
 
     Examples
     --------
@@ -52,11 +64,16 @@ def healpix_to_lonlat(ipix, depth, ellipsoid="sphere", num_threads=0):
     ipix = ipix.astype(np.uint64)
 
     num_threads = np.uint16(num_threads)
+    dtype = _float_dtype(dtype)
 
-    return healpix_geo.ring.healpix_to_lonlat(depth, ipix, ellipsoid, num_threads)
+    return healpix_geo.ring.healpix_to_lonlat(
+        depth, ipix, ellipsoid, num_threads, dtype
+    )
 
 
-def lonlat_to_healpix(longitude, latitude, depth, ellipsoid="sphere", num_threads=0):
+def lonlat_to_healpix(
+    longitude, latitude, depth, ellipsoid="sphere", num_threads=0, transform=None
+):
     r"""Get the HEALPix indexes that contains specific points.
 
     Parameters
@@ -74,6 +91,11 @@ def lonlat_to_healpix(longitude, latitude, depth, ellipsoid="sphere", num_thread
         Specifies the number of threads to use for the computation. Default to 0 means
         it will choose the number of threads based on the RAYON_NUM_THREADS environment variable (if set),
         or the number of logical CPUs (otherwise)
+    transform : healpix_geo.InputTransform, optional
+        A geodesy operator applied to the input coordinates before computing
+        the cells, for example to convert from a different datum or from
+        projected coordinates. The longitudes and latitudes are then the
+        coordinates expected by the operator.
 
     Returns
     -------
@@ -87,6 +109,11 @@ def lonlat_to_healpix(longitude, latitude, depth, ellipsoid="sphere", num_thread
     ValueError
         When the name of the ellipsoid is unknown.
 
+    Notes
+    -----
+    ``float32`` coordinates are passed on without conversion, all other dtypes
+    are cast to ``float64``.
+
     Examples
     --------
     >>> from healpix_geo.ring import lonlat_to_healpix
@@ -99,8 +126,14 @@ def lonlat_to_healpix(longitude, latitude, depth, ellipsoid="sphere", num_thread
     array([336, 436, 114], dtype=uint64)
     """
     _check_depth(depth)
-    longitude = np.atleast_1d(longitude).astype("float64")
-    latitude = np.atleast_1d(latitude).astype("float64")
+    longitude, latitude = _as_float(longitude, latitude)
+
+    if transform is not None:
+        longitude, latitude = transform.apply(
+            longitude.astype("float64"),
+            latitude.astype("float64"),
+            num_threads=num_threads,
+        )
 
     num_threads = np.uint16(num_threads)
 
@@ -109,7 +142,9 @@ def lonlat_to_healpix(longitude, latitude, depth, ellipsoid="sphere", num_thread
     )
 
 
-def healpix_to_cartesian(ipix, depth, ellipsoid="sphere", num_threads=0):
+def healpix_to_cartesian(
+    ipix, depth, ellipsoid="sphere", num_threads=0, dtype="float64"
+):
     r"""Get the cartesian coordinates of the center of the given HEALPix cells.
 
     Parameters
@@ -126,6 +161,9 @@ def healpix_to_cartesian(ipix, depth, ellipsoid="sphere", num_threads=0):
         Specifies the number of threads to use for the computation. Default to 0 means
         it will choose the number of threads based on the RAYON_NUM_THREADS environment variable (if set),
         or the number of logical CPUs (otherwise)
+    dtype : str or numpy.dtype, default: "float64"
+        The floating point type of the returned coordinates, either ``float32``
+        or ``float64``.
 
     Returns
     -------
@@ -138,6 +176,8 @@ def healpix_to_cartesian(ipix, depth, ellipsoid="sphere", num_threads=0):
         When the HEALPix cell indexes given have values out of :math:`[0, 4^{29 - depth}[`.
     ValueError
         When the name of the ellipsoid is unknown.
+    ValueError
+        When the dtype is neither ``float32`` nor ``float64``.
 
     Examples
     --------
@@ -159,11 +199,16 @@ def healpix_to_cartesian(ipix, depth, ellipsoid="sphere", num_threads=0):
     ipix = ipix.astype(np.uint64)
 
     num_threads = np.uint16(num_threads)
+    dtype = _float_dtype(dtype)
 
-    return healpix_geo.ring.healpix_to_cartesian(depth, ipix, ellipsoid, num_threads)
+    return healpix_geo.ring.healpix_to_cartesian(
+        depth, ipix, ellipsoid, num_threads, dtype
+    )
 
 
-def cartesian_to_healpix(x, y, z, depth, ellipsoid="sphere", num_threads=0):
+def cartesian_to_healpix(
+    x, y, z, depth, ellipsoid="sphere", num_threads=0, transform=None
+):
     r"""Get the HEALPix indexes that contain specific points.
 
     Parameters
@@ -184,6 +229,10 @@ def cartesian_to_healpix(x, y, z, depth, ellipsoid="sphere", num_threads=0):
         Specifies the number of threads to use for the computation. Default to 0 means
         it will choose the number of threads based on the RAYON_NUM_THREADS environment variable (if set),
         or the number of logical CPUs (otherwise)
+    transform : healpix_geo.InputTransform, optional
+        A geodesy operator applied to the cartesian coordinates before computing
+        the cells, for example a Helmert transformation from the cartesian frame
+        of a different datum.
 
     Returns
     -------
@@ -197,6 +246,11 @@ def cartesian_to_healpix(x, y, z, depth, ellipsoid="sphere", num_threads=0):
     ValueError
         When the name of the ellipsoid is unknown.
 
+    Notes
+    -----
+    ``float32`` coordinates are passed on without conversion, all other dtypes
+    are cast to ``float64``.
+
     Examples
     --------
     >>> from healpix_geo.ring import cartesian_to_healpix
@@ -214,9 +268,15 @@ def cartesian_to_healpix(x, y, z, depth, ellipsoid="sphere", num_threads=0):
     array([336, 436, 114], dtype=uint64)
     """
     _check_depth(depth)
-    x = np.atleast_1d(x).astype("float64")
-    y = np.atleast_1d(y).astype("float64")
-    z = np.atleast_1d(z).astype("float64")
+    x, y, z = _as_float(x, y, z)
+
+    if transform is not None:
+        x, y, z = transform.apply_cartesian(
+            x.astype("float64"),
+            y.astype("float64"),
+            z.astype("float64"),
+            num_threads=num_threads,
+        )
 
     num_threads = np.uint16(num_threads)
 
@@ -292,7 +352,7 @@ def vertices(ipix, depth, ellipsoid, step=1, num_threads=0):
 
 
 def bilinear_interpolation(
-    longitude, latitude, depth, *, ellipsoid="sphere", num_threads=0
+    longitude, latitude, depth, *, ellipsoid="sphere", num_threads=0, transform=None
 ):
     """Get the cell ids and weights necessary to bilinearly interpolate the given values.
 
@@ -312,6 +372,11 @@ def bilinear_interpolation(
         Specifies the number of threads to use for the computation. Default to 0 means
         it will choose the number of threads based on the RAYON_NUM_THREADS environment variable (if set),
         or the number of logical CPUs (otherwise)
+    transform : healpix_geo.InputTransform, optional
+        A geodesy operator applied to the input coordinates before computing
+        the cells, for example to convert from a different datum or from
+        projected coordinates. The longitudes and latitudes are then the
+        coordinates expected by the operator.
 
     Returns
     -------
@@ -369,6 +434,11 @@ def bilinear_interpolation(
     longitude = np.atleast_1d(longitude).astype("float64")
     latitude = np.atleast_1d(latitude).astype("float64")
 
+    if transform is not None:
+        longitude, latitude = transform.apply(
+            longitude, latitude, num_threads=num_threads
+        )
+
     num_threads = np.uint16(num_threads)
 
     ipix, weights = healpix_geo.ring.bilinear_interpolation(
@@ -563,6 +633,145 @@ def angular_distances(from_, to_, depth, num_threads=0):
     return np.where(mask, np.reshape(distances, to_.shape), np.nan)
 
 
+def geodesic_distances(from_, to_, depth, ellipsoid="sphere", num_threads=0):
+    """Compute the geodesic distances between cell centers
+
+    Contrary to :py:func:`angular_distances`, the distances are measured along
+    the geodesics of the reference ellipsoid, using Karney's algorithm.
+
+    Parameters
+    ----------
+    from_ : numpy.ndarray
+        The source Healpix cell indexes given as a ``np.uint64`` numpy array. Should be 1D.
+    to_ : numpy.ndarray
+        The destination Healpix cell indexes given as a ``np.uint64`` numpy array.
+        Should be 2D. Cells set to ``-1`` are ignored.
+    depth : int
+        The depth of the Healpix cells.
+    ellipsoid : ellipsoid-like, default: "sphere"
+        Reference ellipsoid to evaluate healpix on. Triaxial ellipsoids are not
+        supported.
+    num_threads : int, default: 0
+        Specifies the number of threads to use for the computation. Default to 0 means
+        it will choose the number of threads based on the RAYON_NUM_THREADS environment variable (if set),
+        or the number of logical CPUs (otherwise)
+
+    Returns
+    -------
+    distances : numpy.ndarray
+        The geodesic distances in meters.
+
+    Raises
+    ------
+    ValueError
+        When the Healpix cell indexes given have values out of :math:`[0, 4^{depth}[`.
+    """
+    _check_depth(depth)
+
+    from_ = np.atleast_1d(from_)
+    _check_ipixels(data=from_, depth=depth)
+    from_ = from_.astype("uint64")
+
+    mask = to_ != -1
+    masked_to = np.where(mask, to_, 0)
+
+    to_ = np.atleast_1d(masked_to)
+    _check_ipixels(data=to_, depth=depth)
+    to_ = to_.astype("uint64")
+
+    if from_.shape != to_.shape and from_.shape != to_.shape[:-1]:
+        raise ValueError(
+            "The shape of `from_` must be compatible with the shape of `to_`:\n"
+            f"{to_.shape} or {to_.shape[:-1]} must be equal to {from_.shape}."
+        )
+
+    if from_.shape == to_.shape:
+        intermediate_shape = to_.shape + (1,)
+    else:
+        intermediate_shape = to_.shape
+
+    num_threads = np.uint16(num_threads)
+
+    distances = healpix_geo.ring.geodesic_distances(
+        depth, from_, np.reshape(to_, intermediate_shape), ellipsoid, num_threads
+    )
+
+    return np.where(mask, np.reshape(distances, to_.shape), np.nan)
+
+
+def point_geodesic_distances(
+    longitude, latitude, to_, depth, ellipsoid="sphere", num_threads=0
+):
+    """Compute the geodesic distances between points and cell centers
+
+    Parameters
+    ----------
+    longitude : numpy.ndarray
+        The longitudes of the points in degrees. Should be 1D.
+    latitude : numpy.ndarray
+        The latitudes of the points in degrees. Should be 1D.
+    to_ : numpy.ndarray
+        The destination Healpix cell indexes given as a ``np.uint64`` numpy array.
+        Should be 2D. Cells set to ``-1`` are ignored.
+    depth : int
+        The depth of the Healpix cells.
+    ellipsoid : ellipsoid-like, default: "sphere"
+        Reference ellipsoid to evaluate healpix on. Triaxial ellipsoids are not
+        supported.
+    num_threads : int, default: 0
+        Specifies the number of threads to use for the computation. Default to 0 means
+        it will choose the number of threads based on the RAYON_NUM_THREADS environment variable (if set),
+        or the number of logical CPUs (otherwise)
+
+    Returns
+    -------
+    distances : numpy.ndarray
+        The geodesic distances in meters.
+
+    Raises
+    ------
+    ValueError
+        When the Healpix cell indexes given have values out of :math:`[0, 4^{depth}[`.
+    """
+    _check_depth(depth)
+
+    longitude = np.atleast_1d(longitude).astype("float64")
+    latitude = np.atleast_1d(latitude).astype("float64")
+
+    mask = to_ != -1
+    masked_to = np.where(mask, to_, 0)
+
+    to_ = np.atleast_1d(masked_to)
+    _check_ipixels(data=to_, depth=depth)
+    to_ = to_.astype("uint64")
+
+    if longitude.shape != latitude.shape:
+        raise ValueError("`longitude` and `latitude` must have the same shape.")
+
+    if longitude.shape != to_.shape and longitude.shape != to_.shape[:-1]:
+        raise ValueError(
+            "The shape of the points must be compatible with the shape of `to_`:\n"
+            f"{to_.shape} or {to_.shape[:-1]} must be equal to {longitude.shape}."
+        )
+
+    if longitude.shape == to_.shape:
+        intermediate_shape = to_.shape + (1,)
+    else:
+        intermediate_shape = to_.shape
+
+    num_threads = np.uint16(num_threads)
+
+    distances = healpix_geo.ring.point_geodesic_distances(
+        depth, longitude,
+        latitude,
+        np.reshape(to_, intermediate_shape),
+        ellipsoid,
+        num_threads,
+    )
+
+    return np.where(mask, np.reshape(distances, to_.shape), np.nan)
+
+
 def zone_coverage(bbox, depth, *, ellipsoid="sphere", flat=True):
     """Search the cells covering the given bounding box
 
@@ -632,7 +841,7 @@ def box_coverage(center, size, angle, depth, *, ellipsoid="sphere", flat=True):
     )
 
 
-def polygon_coverage(vertices, depth, *, ellipsoid="sphere", flat=True):
+def polygon_coverage(vertices, depth, *, ellipsoid="sphere", flat=True, transform=None):
     """Search the cells covering the given polygon.
 
     Parameters
@@ -648,6 +857,11 @@ def polygon_coverage(vertices, depth, *, ellipsoid="sphere", flat=True):
         scheme.
     flat : bool, default: True
         If ``True``, the cells returned will all be at the passed depth.
+    transform : healpix_geo.InputTransform, optional
+        A geodesy operator applied to the input coordinates before computing
+        the cells, for example to convert from a different datum or from
+        projected coordinates. The vertices are then the coordinates expected
+        by the operator.
 
     Returns
     -------
@@ -661,13 +875,22 @@ def polygon_coverage(vertices, depth, *, ellipsoid="sphere", flat=True):
     """
     _check_depth(depth)
 
+    vertices = _transform_points(transform, vertices)
+
     return healpix_geo.ring.polygon_coverage(
         depth, vertices, ellipsoid=ellipsoid, flat=flat
     )
 
 
 def cone_coverage(
-    center, radius, depth, *, delta_depth=0, ellipsoid="sphere", flat=True
+    center,
+    radius,
+    depth,
+    *,
+    delta_depth=0,
+    ellipsoid="sphere",
+    flat=True,
+    transform=None,
 ):
     """Search the cells covering the given cone
 
@@ -688,6 +911,11 @@ def cone_coverage(
         scheme.
     flat : bool, default: True
         If ``True``, the cells returned will all be at the passed depth.
+    transform : healpix_geo.InputTransform, optional
+        A geodesy operator applied to the input coordinates before computing
+        the cells, for example to convert from a different datum or from
+        projected coordinates. The coordinates of the center are then the
+        coordinates expected by the operator.
 
     Returns
     -------
@@ -701,8 +929,7 @@ def cone_coverage(
     """
     _check_depth(depth)
 
-    if not isinstance(center, tuple):
-        center = tuple(center)
+    center = tuple(_transform_points(transform, center))
 
     return healpix_geo.ring.cone_coverage(
         depth, center, radius, delta_depth=delta_depth, ellipsoid=ellipsoid, flat=flat
@@ -718,6 +945,7 @@ def elliptical_cone_coverage(
     delta_depth=0,
     ellipsoid="sphere",
     flat=True,
+    transform=None,
 ):
     """Search the cells covering the given elliptical cone.
 
@@ -740,6 +968,11 @@ def elliptical_cone_coverage(
         scheme.
     flat : bool, default: True
         If ``True``, the cells returned will all be at the passed depth.
+    transform : healpix_geo.InputTransform, optional
+        A geodesy operator applied to the input coordinates before computing
+        the cells, for example to convert from a different datum or from
+        projected coordinates. The coordinates of the center are then the
+        coordinates expected by the operator.
 
     Returns
     -------
@@ -752,8 +985,7 @@ def elliptical_cone_coverage(
     """
     _check_depth(depth)
 
-    if not isinstance(center, tuple):
-        center = tuple(center)
+    center = tuple(_transform_points(transform, center))
     if not isinstance(ellipse_geometry, tuple):
         ellipse_geometry = tuple(ellipse_geometry)
 
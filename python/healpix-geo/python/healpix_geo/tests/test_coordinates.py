@@ -0,0 +1,78 @@
+import numpy as np
+import pytest
+
+import healpix_geo
+
+
+@pytest.mark.parametrize("module", ["nested", "ring", "zuniq"])
+@pytest.mark.parametrize("ellipsoid", ["sphere", "WGS84"])
+def test_single_precision(module, ellipsoid):
+    module = getattr(healpix_geo, module)
+    depth = 8
+
+    lon = np.array([0.5, 45.3, 120.1, 300.7], dtype="float32")
+    lat = np.array([-60.2, 0.1, 30.4, 75.9], dtype="float32")
+
+    actual = module.lonlat_to_healpix(lon, lat, depth, ellipsoid=ellipsoid)
+    expected = module.lonlat_to_healpix(
+        lon.astype("float64"), lat.astype("float64"), depth, ellipsoid=ellipsoid
+    )
+    np.testing.assert_equal(actual, expected)
+
+    x = np.array([6378137.0, 0.0, 3000000.0], dtype="float32")
+    y = np.array([0.0, 6378137.0, -2000000.0], dtype="float32")
+    z = np.array([0.0, 0.0, 5000000.0], dtype="float32")
+    actual = module.cartesian_to_healpix(x, y, z, depth, ellipsoid=ellipsoid)
+    expected = module.cartesian_to_healpix(
+        x.astype("float64"),
+        y.astype("float64"),
+        z.astype("float64"),
+        depth,
+        ellipsoid=ellipsoid,
+    )
+    np.testing.assert_equal(actual, expected)
+
+
+
+@pytest.mark.parametrize("module", ["nested", "ring", "zuniq"])
+def test_single_precision_output(module):
+    depth = 8
+    if module == "zuniq":
+        ipix = healpix_geo.zuniq.from_nested(np.array([0, 1000, 786431]), depth)
+        args = ()
+    else:
+        ipix = np.array([0, 1000, 786431])
+        args = (depth,)
+    module = getattr(healpix_geo, module)
+
+    actual = module.healpix_to_lonlat(ipix, *args, ellipsoid="WGS84", dtype="float32")
+    expected = module.healpix_to_lonlat(ipix, *args, ellipsoid="WGS84")
+    for actual_, expected_ in zip(actual, expected):
+        assert actual_.dtype == np.float32
+        np.testing.assert_equal(actual_, expected_.astype("float32"))
+
+    actual = module.healpix_to_cartesian(
+        ipix, *args, ellipsoid="WGS84", dtype=np.float32
+    )
+    expected = module.healpix_to_cartesian(ipix, *args, ellipsoid="WGS84")
+    for actual_, expected_ in zip(actual, expected):
+        assert actual_.dtype == np.float32
+        np.testing.assert_equal(actual_, expected_.astype("float32"))
+
+    with pytest.raises(ValueError, match="Unsupported dtype"):
+        module.healpix_to_lonlat(ipix, *args, ellipsoid="WGS84", dtype="int64")
+
+@pytest.mark.parametrize("module", ["nested", "ring", "zuniq"])
+def test_mismatching_shapes(module):
+    module = getattr(healpix_geo, module)
+
+    lon = np.array([0.5, 45.3, 120.1])
+    lat = np.array([-60.2, 0.1])
+    with pytest.raises(ValueError, match="must have the same shape"):
+        module.lonlat_to_healpix(lon, lat, 8, ellipsoid="WGS84")
+
+    x = np.array([6378137.0, 0.0])
+    y = np.array([0.0, 6378137.0])
+    z = np.array([0.0])
+    with pytest.raises(ValueError, match="must have the same shape"):
+        module.cartesian_to_healpix(x, y, z, 8, ellipsoid="WGS84")
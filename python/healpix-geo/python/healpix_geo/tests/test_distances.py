@@ -79,3 +79,73 @@ def test_distance_error(indexing_scheme):
 
     with pytest.raises(ValueError, match="The shape of `from_` must be compatible"):
         angular_distances(from_, to_, depth)
+
+
+@pytest.mark.parametrize("indexing_scheme", ["ring", "nested"])
+def test_geodesic_distances_sphere(indexing_scheme):
+    module = getattr(healpix_geo, indexing_scheme)
+
+    from_ = np.array([0, 16, 25, 32, 46])
+    to_ = np.array([[2, 4], [15, 7], [27, 26], [40, -1], [-1, 41]], dtype="int64")
+    depth = 2
+    radius = 6370997.0
+
+    actual = module.geodesic_distances(from_, to_, depth, ellipsoid="sphere")
+    expected = module.angular_distances(from_, to_, depth) * radius
+
+    np.testing.assert_allclose(actual, expected, rtol=1e-9)
+
+
+def test_geodesic_distances_zuniq():
+    from_ = np.array([0, 16, 25, 32, 46], dtype="uint64")
+    to_ = np.array([[2, 4], [15, 7], [27, 26], [40, 3], [11, 41]], dtype="uint64")
+    depth = 2
+
+    actual = healpix_geo.zuniq.geodesic_distances(
+        healpix_geo.zuniq.from_nested(from_, depth),
+        healpix_geo.zuniq.from_nested(to_.ravel(), depth).reshape(to_.shape),
+        ellipsoid="WGS84",
+    )
+    expected = healpix_geo.nested.geodesic_distances(
+        from_, to_, depth, ellipsoid="WGS84"
+    )
+
+    np.testing.assert_allclose(actual, expected)
+
+
+def test_geodesic_distances_triaxial():
+    from_ = np.array([0, 16])
+    to_ = np.array([[2, 4], [15, 7]], dtype="int64")
+    ellipsoid = {
+        "semimajor_axis": 6378171.36,
+        "semimedian_axis": 6378102.0,
+        "semiminor_axis": 6356752.0,
+    }
+
+    with pytest.raises(ValueError, match="not supported on triaxial ellipsoids"):
+        healpix_geo.nested.geodesic_distances(from_, to_, 2, ellipsoid=ellipsoid)
+
+
+@pytest.mark.parametrize("indexing_scheme", ["ring", "nested"])
+def test_point_geodesic_distances(indexing_scheme):
+    module = getattr(healpix_geo, indexing_scheme)
+
+    from_ = np.array([0, 16, 25])
+    to_ = np.array([[2, 4], [15, -1], [27, 26]], dtype="int64")
+    depth = 2
+
+    lon, lat = module.healpix_to_lonlat(from_, depth, ellipsoid="WGS84")
+
+    actual = module.point_geodesic_distances(lon, lat, to_, depth, ellipsoid="WGS84")
+    expected = module.geodesic_distances(from_, to_, depth, ellipsoid="WGS84")
+
+    np.testing.assert_allclose(actual, expected)
+
+
+def test_point_geodesic_distances_error():
+    lon = np.array([0.0, 10.0])
+    lat = np.array([0.0, 10.0])
+    to_ = np.array([[2, 3], [4, 6], [5, 4]])
+
+    with pytest.raises(ValueError, match="The shape of the points must be compatible"):
+        healpix_geo.nested.point_geodesic_distances(lon, lat, to_, 1)
@@ -268,6 +268,133 @@ class TestRangeMOCIndex:
         np.testing.assert_equal(cell_ids[actual_indexer], expected_cell_ids)
         np.testing.assert_equal(actual_moc.cell_ids(), expected_cell_ids)
 
+    @pytest.mark.parametrize("indexing_scheme", ["nested", "ring", "zuniq"])
+    def test_indexing_scheme(self, indexing_scheme):
+        depth = 2
+        module = getattr(healpix_geo, indexing_scheme)
+        cell_ids = module.lonlat_to_healpix(
+            np.array([0.0, 45.0, 120.0]), np.array([10.0, -30.0, 60.0]), depth
+        ).astype("uint64")
+
+        index = healpix_geo.nested.RangeMOCIndex.from_cell_ids(
+            depth, cell_ids, indexing_scheme=indexing_scheme
+        )
+
+        assert index.indexing_scheme == indexing_scheme
+        np.testing.assert_equal(np.sort(index.cell_ids()), np.sort(cell_ids))
+
+        full = healpix_geo.nested.RangeMOCIndex.full_domain(
+            depth, indexing_scheme=indexing_scheme
+        )
+        assert full.indexing_scheme == indexing_scheme
+        np.testing.assert_equal(full.intersection(index).size, cell_ids.size)
+
+    def test_sel_ring(self):
+        cell_ids = np.array([0, 3, 4, 12, 13, 47], dtype="uint64")
+        index = healpix_geo.nested.RangeMOCIndex.from_cell_ids(
+            1, cell_ids, indexing_scheme="ring"
+        )
+        np.testing.assert_equal(index.cell_ids(), cell_ids)
+
+        # data ordered like the ring ids
+        data = cell_ids * 10
+
+        actual_indexer, actual_index = index.sel(np.array([47, 0, 13], dtype="int64"))
+        np.testing.assert_equal(data[actual_indexer], [0, 130, 470])
+        np.testing.assert_equal(actual_index.cell_ids(), [0, 13, 47])
+
+        actual_indexer, actual_index = index.sel(slice(3, 12))
+        np.testing.assert_equal(data[actual_indexer], [30, 40, 120])
+        np.testing.assert_equal(actual_index.cell_ids(), [3, 4, 12])
+
+    def test_zuniq_depth_mismatch(self):
+        labels = healpix_geo.zuniq.from_nested(np.array([3, 5], dtype="uint64"), 2)
+
+        with pytest.raises(ValueError, match="expected depth 3"):
+            healpix_geo.nested.RangeMOCIndex.from_cell_ids(
+                3, labels, indexing_scheme="zuniq"
+            )
+
+        index = healpix_geo.nested.RangeMOCIndex.full_domain(3, indexing_scheme="zuniq")
+        with pytest.raises(ValueError, match="expected depth 3"):
+            index.sel(labels.astype("int64"))
+
+    def test_indexing_scheme_unknown(self):
+        with pytest.raises(ValueError, match="unknown indexing scheme"):
+            healpix_geo.nested.RangeMOCIndex.full_domain(2, indexing_scheme="peano")
+
+    def test_sel_missing(self):
+        cell_ids = np.array([2, 3, 4, 5, 23, 24, 25], dtype="uint64")
+        index = healpix_geo.nested.RangeMOCIndex.from_cell_ids(3, cell_ids)
+        indexer = np.array([3, 10, 24], dtype="int64")
+
+        with pytest.raises(KeyError, match="10"):
+            index.sel(indexer)
+
+        actual_indexer, actual_moc = index.sel(indexer, drop_missing=True)
+
+        np.testing.assert_equal(actual_indexer, [1, 5])
+        np.testing.assert_equal(actual_moc.cell_ids(), [3, 24])
+
+    @pytest.mark.parametrize(
+        ["tolerance", "drop_missing", "expected"],
+        (
+            pytest.param(None, False, [3, 5, 23, 25], id="no tolerance"),
+            pytest.param(4, True, [3, 23], id="tolerance"),
+        ),
+    )
+    def test_sel_nearest(self, tolerance, drop_missing, expected):
+        cell_ids = np.array([2, 3, 4, 5, 23, 24, 25], dtype="uint64")
+        index = healpix_geo.nested.RangeMOCIndex.from_cell_ids(3, cell_ids)
+        indexer = np.array([3, 10, 20, 100], dtype="int64")
+
+        actual_indexer, actual_moc = index.sel(
+            indexer, method="nearest", tolerance=tolerance, drop_missing=drop_missing
+        )
+
+        np.testing.assert_equal(cell_ids[actual_indexer], expected)
+        np.testing.assert_equal(actual_moc.cell_ids(), expected)
+
+    def test_sel_nearest_tolerance_exceeded(self):
+        cell_ids = np.array([2, 3, 4, 5, 23, 24, 25], dtype="uint64")
+        index = healpix_geo.nested.RangeMOCIndex.from_cell_ids(3, cell_ids)
+        indexer = np.array([3, 10], dtype="int64")
+
+        with pytest.raises(KeyError, match="10"):
+            index.sel(indexer, method="nearest", tolerance=4)
+
+    def test_sel_nearest_angular(self):
+        index = healpix_geo.nested.RangeMOCIndex.from_cell_ids(
+            3, np.array([5], dtype="uint64")
+        )
+
+        actual_indexer, actual_moc = index.sel(
+            np.array([6], dtype="int64"), method="nearest", metric="angular"
+        )
+
+        np.testing.assert_equal(actual_indexer, [0])
+        np.testing.assert_equal(actual_moc.cell_ids(), [5])
+
+    def test_repr(self):
+        cell_ids = np.array([1, 2, 3, 4, 5, 18, 40, 41, 42], dtype="uint64")
+        index = healpix_geo.nested.RangeMOCIndex.from_cell_ids(1, cell_ids)
+
+        actual = repr(index)
+
+        assert actual.startswith("<healpix_geo.nested.RangeMOCIndex>")
+        assert "indexing scheme: nested" in actual
+        assert "depth: 1" in actual
+        assert "size: 9 cells" in actual
+        assert "ranges: 3 (largest run: 5 cells, smallest run: 1 cells)" in actual
+        assert "cells per base cell: [3, 2, 0, 0, 1, 0, 0, 0, 0, 0, 3, 0]" in actual
+
+        ring_index = healpix_geo.nested.RangeMOCIndex.full_domain(
+            1, indexing_scheme="ring"
+        )
+        ring_repr = repr(ring_index)
+        assert ring_repr.startswith("<healpix_geo.nested.RangeMOCIndex>")
+        assert "indexing scheme: ring" in ring_repr
+
     @pytest.mark.parametrize(
         ["depth", "cell_ids"],
         (
@@ -345,3 +472,151 @@ class TestRangeMOCIndex:
         if expected is not None:
             np.testing.assert_equal(reconstructed, expected)
         np.testing.assert_equal(actual, reconstructed)
+
+
+class TestRangeMOCIndexChunks:
+    def test_chunk(self):
+        cell_ids = np.array([1, 2, 3, 17, 18, 40], dtype="uint64")
+        index = healpix_geo.nested.RangeMOCIndex.from_cell_ids(2, cell_ids)
+
+        chunks = index.chunk(1)
+
+        assert [parent for parent, _, _ in chunks] == [0, 4, 10]
+        for _, positions, chunk in chunks:
+            np.testing.assert_equal(chunk.cell_ids(), cell_ids[positions])
+
+        with pytest.raises(ValueError, match="chunk depth"):
+            index.chunk(3)
+
+    def test_chunk_ring(self):
+        index = healpix_geo.nested.RangeMOCIndex.full_domain(2, indexing_scheme="ring")
+
+        with pytest.raises(ValueError, match="can't be chunked"):
+            index.chunk(1)
+
+    def test_chunk_to_size(self):
+        index = healpix_geo.nested.RangeMOCIndex.full_domain(3)
+
+        chunks = index.chunk_to_size(20)
+
+        assert len(chunks) == 48
+        assert all(chunk.size == 16 for _, _, chunk in chunks)
+
+    def test_query_chunks(self):
+        index = healpix_geo.nested.RangeMOCIndex.full_domain(6)
+        geom = shapely.Point(0, 2)
+
+        actual = index.query_chunks(2, geom)
+        expected = healpix_geo.nested.lonlat_to_healpix(
+            np.array([0.0]), np.array([2.0]), 2
+        )
+
+        assert actual == expected.tolist()
+
+
+class TestRangeMOCIndexEllipsoid:
+    @pytest.mark.parametrize(
+        ["ellipsoid", "expected"],
+        (
+            ("WGS84", "WGS84"),
+            ("wgs 84", "WGS84"),
+            ("EPSG:7019", "GRS80"),
+            (
+                {"semimajor_axis": 6378137.0, "inverse_flattening": 298.257223563},
+                None,
+            ),
+        ),
+    )
+    def test_ellipsoid_name(self, ellipsoid, expected):
+        index = healpix_geo.nested.RangeMOCIndex.full_domain(2, ellipsoid=ellipsoid)
+
+        assert index.ellipsoid_name == expected
+        assert index.ellipsoid["semimajor_axis"] == pytest.approx(6378137.0)
+
+        roundtripped = pickle.loads(pickle.dumps(index))
+        assert roundtripped.ellipsoid_name == expected
+        assert roundtripped.ellipsoid == index.ellipsoid
+
+    def test_ellipsoid_names(self):
+        names = healpix_geo.ellipsoid_names()
+
+        assert "WGS84" in names
+        assert "sphere" in names
+
+    def test_is_compatible(self):
+        index1 = healpix_geo.nested.RangeMOCIndex.full_domain(3, ellipsoid="WGS84")
+        index2 = healpix_geo.nested.RangeMOCIndex.full_domain(3, ellipsoid="GRS80")
+        index3 = healpix_geo.nested.RangeMOCIndex.full_domain(3)
+
+        assert index1.is_compatible(index2, 0.01)
+        assert not index1.is_compatible(index3, 0.01)
+
+    @pytest.mark.parametrize(
+        ["other", "match"],
+        (
+            pytest.param(
+                {"indexing_scheme": "ring"},
+                "indexing schemes don't match",
+                id="indexing scheme",
+            ),
+            pytest.param(
+                {"ellipsoid": "mars"}, "different bodies", id="different bodies"
+            ),
+            pytest.param(
+                {"ellipsoid": "GRS80"}, "ellipsoids don't match", id="ellipsoid"
+            ),
+        ),
+    )
+    @pytest.mark.parametrize(
+        "operation", ["union", "intersection", "difference", "symmetric_difference"]
+    )
+    def test_set_operations_incompatible(self, operation, other, match):
+        index = healpix_geo.nested.RangeMOCIndex.full_domain(2, ellipsoid="WGS84")
+        other_index = healpix_geo.nested.RangeMOCIndex.full_domain(
+            2, **{"ellipsoid": "WGS84", **other}
+        )
+
+        with pytest.raises(ValueError, match=match):
+            getattr(index, operation)(other_index)
+
+    def test_resample(self):
+        index = healpix_geo.nested.RangeMOCIndex.full_domain(6)
+
+        resampled = index.resample("WGS84")
+
+        assert resampled.depth == index.depth
+        assert resampled.ellipsoid == pytest.approx(
+            {"semimajor_axis": 6378137.0, "flattening": 1 / 298.257223563}
+        )
+        assert resampled.ellipsoid_name == "WGS84"
+        assert resampled.size == index.size
+
+
+class TestRangeMOCIndexBuilder:
+    @pytest.mark.parametrize("buffer_size", [None, 1, 7])
+    def test_batches(self, buffer_size):
+        rng = np.random.default_rng(0)
+        cell_ids = rng.integers(0, 12 * 4**5, size=200).astype("uint64")
+
+        builder = healpix_geo.nested.RangeMOCIndexBuilder(5, buffer_size=buffer_size)
+        for batch in np.array_split(cell_ids, 6):
+            builder.add_cell_ids(batch)
+        # non-contiguous input
+        builder.add_cell_ids(cell_ids[::3])
+        actual = builder.build()
+
+        np.testing.assert_equal(actual.cell_ids(), np.unique(cell_ids))
+
+    def test_zuniq_depth_mismatch(self):
+        labels = healpix_geo.zuniq.from_nested(np.array([3, 5], dtype="uint64"), 2)
+        builder = healpix_geo.nested.RangeMOCIndexBuilder(3, indexing_scheme="zuniq")
+
+        with pytest.raises(ValueError, match="expected depth 3"):
+            builder.add_cell_ids(labels)
+
+    def test_build_twice(self):
+        builder = healpix_geo.nested.RangeMOCIndexBuilder(2)
+        builder.build()
+
+        with pytest.raises(RuntimeError, match="already been built"):
+            builder.build()
@@ -51,9 +51,50 @@ class Ellipsoid:
             nullcontext(),
             id="dict-ellipsoid",
         ),
+        pytest.param(
+            {"semimajor_axis": 1, "flattening": 0.1},
+            nullcontext(),
+            id="dict-ellipsoid-flattening",
+        ),
+        pytest.param(
+            {"semimajor_axis": 1, "semiminor_axis": 0.9},
+            nullcontext(),
+            id="dict-ellipsoid-semiminor_axis",
+        ),
+        pytest.param(
+            {"semimajor_axis": 1, "eccentricity": 0.1},
+            nullcontext(),
+            id="dict-ellipsoid-eccentricity",
+        ),
+        pytest.param(
+            {"semimajor_axis": 1, "eccentricity": 0.9},
+            pytest.raises(
+                ValueError,
+                match=r"The eccentricity must be between 0 and sqrt\(3\) / 2, but got 0.9",
+            ),
+            id="dict-ellipsoid-high_eccentricity",
+        ),
+        pytest.param(
+            {"semimajor_axis": 1, "semimedian_axis": 0.95, "semiminor_axis": 0.9},
+            nullcontext(),
+            id="dict-triaxial",
+        ),
+        pytest.param(
+            {"semimajor_axis": 1, "semimedian_axis": 0.8, "semiminor_axis": 0.9},
+            pytest.raises(
+                ValueError,
+                match="The semiminor axis must be smaller than or equal to the semimedian axis",
+            ),
+            id="dict-triaxial-unsorted_axes",
+        ),
+        pytest.param(
+            {"semi_major_axis": 1, "semi_minor_axis": 0.9},
+            nullcontext(),
+            id="dict-ellipsoid-alternative_spelling",
+        ),
         pytest.param(
             {"abc": 2},
-            pytest.raises(TypeError, match="failed to extract enum"),
+            pytest.raises(ValueError, match="Missing parameter"),
             id="dict-unknown",
         ),
         pytest.param(
@@ -0,0 +1,105 @@
+import numpy as np
+import pytest
+
+import healpix_geo
+
+
+class TestInputTransform:
+    def test_utm_inverse(self):
+        transform = healpix_geo.InputTransform(
+            "utm zone=32", inverse=True, projected=True
+        )
+
+        lon, lat = transform.apply(np.array([500000.0]), np.array([0.0]))
+
+        np.testing.assert_allclose(lon, [9.0])
+        np.testing.assert_allclose(lat, [0.0], atol=1e-9)
+
+    def test_invalid_definition(self):
+        with pytest.raises(ValueError, match="Invalid operator"):
+            healpix_geo.InputTransform("not-an-operator")
+
+    @pytest.mark.parametrize("module", ["nested", "ring", "zuniq"])
+    def test_lonlat_to_healpix(self, module):
+        module = getattr(healpix_geo, module)
+        transform = healpix_geo.InputTransform(
+            "utm zone=32", inverse=True, projected=True
+        )
+
+        easting = np.array([500000.0, 700000.0])
+        northing = np.array([5500000.0, 6000000.0])
+
+        actual = module.lonlat_to_healpix(
+            easting, northing, 10, ellipsoid="WGS84", transform=transform
+        )
+
+        lon, lat = transform.apply(easting, northing)
+        expected = module.lonlat_to_healpix(lon, lat, 10, ellipsoid="WGS84")
+
+        np.testing.assert_equal(actual, expected)
+
+    @pytest.mark.parametrize("module", ["nested", "ring", "zuniq"])
+    def test_bilinear_interpolation(self, module):
+        module = getattr(healpix_geo, module)
+        transform = healpix_geo.InputTransform(
+            "utm zone=32", inverse=True, projected=True
+        )
+
+        easting = np.array([500000.0, 700000.0])
+        northing = np.array([5500000.0, 6000000.0])
+
+        actual_cells, actual_weights = module.bilinear_interpolation(
+            easting, northing, 10, ellipsoid="WGS84", transform=transform
+        )
+
+        lon, lat = transform.apply(easting, northing, num_threads=1)
+        expected_cells, expected_weights = module.bilinear_interpolation(
+            lon, lat, 10, ellipsoid="WGS84"
+        )
+
+        np.testing.assert_equal(actual_cells.data, expected_cells.data)
+        np.testing.assert_equal(actual_weights.data, expected_weights.data)
+
+    @pytest.mark.parametrize("module", ["nested", "ring", "zuniq"])
+    def test_cartesian_to_healpix(self, module):
+        module = getattr(healpix_geo, module)
+        transform = healpix_geo.InputTransform("helmert x=100 y=-50 z=20")
+
+        x = np.array([4728734.69, 3814362.85])
+        y = np.array([465739.72, 4647814.58])
+        z = np.array([4240471.60, 2121029.90])
+
+        actual = module.cartesian_to_healpix(
+            x, y, z, 10, ellipsoid="WGS84", transform=transform
+        )
+        expected = module.cartesian_to_healpix(
+            x + 100, y - 50, z + 20, 10, ellipsoid="WGS84"
+        )
+
+        np.testing.assert_equal(actual, expected)
+
+    @pytest.mark.parametrize("module", ["nested", "ring", "zuniq"])
+    def test_coverage(self, module):
+        module = getattr(healpix_geo, module)
+        transform = healpix_geo.InputTransform(
+            "utm zone=32", inverse=True, projected=True
+        )
+
+        vertices = np.array(
+            [[500000.0, 5500000.0], [700000.0, 5500000.0], [600000.0, 6000000.0]]
+        )
+        lon, lat = transform.apply(vertices[:, 0], vertices[:, 1])
+
+        actual = module.polygon_coverage(
+            vertices, 6, ellipsoid="WGS84", transform=transform
+        )
+        expected = module.polygon_coverage(
+            np.stack([lon, lat], axis=-1), 6, ellipsoid="WGS84"
+        )
+        np.testing.assert_equal(actual, expected)
+
+        actual = module.cone_coverage(
+            vertices[0], 1.0, 6, ellipsoid="WGS84", transform=transform
+        )
+        expected = module.cone_coverage((lon[0], lat[0]), 1.0, 6, ellipsoid="WGS84")
+        np.testing.assert_equal(actual, expected)
@@ -92,3 +92,21 @@ def test_check_ipixels(data, depth, context):
 def test_check_ring(depth, ring, context):
     with context:
         utils._check_ring(depth, ring)
+
+
+@pytest.mark.parametrize(
+    ["dtypes", "expected"],
+    (
+        pytest.param(["float32", "float32"], "float32", id="float32"),
+        pytest.param(["float64", "float64"], "float64", id="float64"),
+        pytest.param(["float32", "float64"], "float64", id="mixed"),
+        pytest.param(["int64", "int64"], "float64", id="int64"),
+        pytest.param(["float16", "float16"], "float64", id="float16"),
+    ),
+)
+def test_as_float(dtypes, expected):
+    arrays = [np.array([1, 2], dtype=dtype) for dtype in dtypes]
+
+    actual = utils._as_float(*arrays)
+
+    assert all(array.dtype == expected for array in actual)
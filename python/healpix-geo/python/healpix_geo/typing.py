@@ -19,7 +19,13 @@ class EllipsoidType(Protocol):
     inverse_flattening: float
 
 
+class TriaxialDict(TypedDict):
+    semimajor_axis: float
+    semimedian_axis: float
+    semiminor_axis: float
+
+
 _SphereLike = SphereDict | SphereType
 _EllipsoidLike = EllipsoidDict | EllipsoidType
 
-EllipsoidLike = str | _SphereLike | _EllipsoidLike
+EllipsoidLike = str | _SphereLike | _EllipsoidLike | TriaxialDict
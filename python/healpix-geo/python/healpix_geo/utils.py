@@ -1,6 +1,46 @@
 import numpy as np
 
 
+def _as_float(*arrays):
+    """Cast coordinates to a floating point dtype supported by the bindings.
+
+    ``float32`` is kept if all arrays have that dtype, everything else is
+    cast to ``float64``.
+    """
+    arrays = [np.atleast_1d(array) for array in arrays]
+
+    if all(array.dtype == np.float32 for array in arrays):
+        dtype = np.float32
+    else:
+        dtype = np.float64
+
+    return tuple(array.astype(dtype) for array in arrays)
+
+
+def _transform_points(transform, points, num_threads=0):
+    """Apply an optional input transform to an array of ``(lon, lat)`` rows."""
+    if transform is None:
+        return points
+
+    points = np.asarray(points, dtype="float64")
+    lon, lat = transform.apply(
+        np.ascontiguousarray(np.atleast_1d(points[..., 0])),
+        np.ascontiguousarray(np.atleast_1d(points[..., 1])),
+        num_threads=num_threads,
+    )
+
+    return np.stack([lon, lat], axis=-1).reshape(points.shape)
+
+
+def _float_dtype(dtype):
+    """Normalize the dtype of output coordinates to its name."""
+    dtype = np.dtype(dtype)
+    if dtype not in (np.float32, np.float64):
+        raise ValueError(f"Unsupported dtype: {dtype}, expected float32 or float64")
+
+    return dtype.name
+
+
 def _check_depth(depth):
     ravel_depth = np.ravel(np.atleast_1d(depth))
     if any(ravel_depth < 0) or any(ravel_depth > 29):
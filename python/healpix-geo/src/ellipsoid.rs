@@ -1,21 +1,17 @@
 use geodesy::ellps::Ellipsoid as GeoEllipsoid;
-use healpix_geo_core::ellipsoid::{Ellipsoid, ReferenceEllipsoid, ReferenceSphere};
+use healpix_geo_core::ellipsoid::{
+    Ellipsoid, ReferenceBody, ReferenceEllipsoid, ReferenceSphere, lookup, lookup_epsg_str,
+    registry,
+};
 use pyo3::exceptions::PyValueError;
 use pyo3::prelude::*;
+use std::collections::HashMap;
 
 #[derive(FromPyObject)]
 pub(crate) enum EllipsoidLike {
     Named(String),
-    EllipsoidParameters {
-        #[pyo3(item("semimajor_axis"))]
-        semimajor_axis: f64,
-        #[pyo3(item("inverse_flattening"))]
-        inverse_flattening: f64,
-    },
-    SphereParameters {
-        #[pyo3(item("radius"))]
-        radius: f64,
-    },
+    /// The parameters as understood by `ReferenceBody::from_mapping`
+    Parameters(HashMap<String, f64>),
     EllipsoidObject {
         #[pyo3(attribute("semimajor_axis"))]
         semimajor_axis: f64,
@@ -28,54 +24,67 @@ pub(crate) enum EllipsoidLike {
     },
 }
 
+fn mapping(parameters: &[(&str, f64)]) -> HashMap<String, f64> {
+    parameters
+        .iter()
+        .map(|(name, value)| (name.to_string(), *value))
+        .collect()
+}
+
 impl EllipsoidLike {
     pub fn into_ellipsoid(self) -> PyResult<Ellipsoid> {
-        match self {
+        let parameters = match self {
             Self::Named(name) => {
+                if let Some(entry) = lookup(&name).or_else(|| lookup_epsg_str(&name)) {
+                    return Ok(entry.to_ellipsoid());
+                }
+
                 let ellipsoid =
                     GeoEllipsoid::named(&name).map_err(|e| PyValueError::new_err(e.to_string()))?;
 
-                if name.contains("sphere") {
+                return if name.contains("sphere") {
                     Ok(Ellipsoid::Sphere(ReferenceSphere::new(ellipsoid)))
                 } else {
                     Ok(Ellipsoid::Ellipsoid(ReferenceEllipsoid::new(ellipsoid)))
-                }
-            }
-            Self::EllipsoidParameters {
-                semimajor_axis,
-                inverse_flattening,
+                };
             }
-            | Self::EllipsoidObject {
+            Self::Parameters(parameters) => parameters,
+            Self::EllipsoidObject {
                 semimajor_axis,
                 inverse_flattening,
-            } => {
-                if inverse_flattening >= 2.0 && semimajor_axis > 0.0 {
-                    let ellipsoid = GeoEllipsoid::new(semimajor_axis, 1.0f64 / inverse_flattening);
+            } => mapping(&[
+                ("semimajor_axis", semimajor_axis),
+                ("inverse_flattening", inverse_flattening),
+            ]),
+            Self::SphereObject { radius } => mapping(&[("radius", radius)]),
+        };
 
-                    Ok(Ellipsoid::Ellipsoid(ReferenceEllipsoid::new(ellipsoid)))
-                } else if inverse_flattening < 2.0 {
-                    Err(PyValueError::new_err(format!(
-                        "The inverse flattening must be greater than or equal to 2, but got {:?}.",
-                        inverse_flattening,
-                    )))
-                } else {
-                    Err(PyValueError::new_err(format!(
-                        "The semimajor axis must be greater than 0, but got {:?}.",
-                        semimajor_axis
-                    )))
-                }
-            }
-            Self::SphereParameters { radius } | EllipsoidLike::SphereObject { radius } => {
-                if radius > 0.0 {
-                    let ellipsoid = GeoEllipsoid::new(radius, 0.0f64);
-                    Ok(Ellipsoid::Sphere(ReferenceSphere::new(ellipsoid)))
-                } else {
-                    Err(PyValueError::new_err(format!(
-                        "The radius must be greater than 0, but got {:?}.",
-                        radius
-                    )))
-                }
-            }
+        Ellipsoid::from_mapping(&parameters).map_err(|e| PyValueError::new_err(e.to_string()))
+    }
+
+    /// Like `into_ellipsoid`, but refuse the bodies geodesics are not supported on
+    pub fn into_geodesic_ellipsoid(self) -> PyResult<Ellipsoid> {
+        let ellipsoid = self.into_ellipsoid()?;
+        if ellipsoid.is_triaxial() {
+            return Err(PyValueError::new_err(
+                "geodesics are not supported on triaxial ellipsoids",
+            ));
         }
+
+        Ok(ellipsoid)
     }
 }
+
+/// The canonical names of the registered ellipsoids
+///
+/// Any of these names, their aliases, or ``"EPSG:<code>"`` strings can be
+/// passed wherever an ellipsoid-like is accepted.
+///
+/// Returns
+/// -------
+/// names : list of str
+///     The names of the registered ellipsoids.
+#[pyfunction]
+pub fn ellipsoid_names() -> Vec<&'static str> {
+    registry().iter().map(|entry| entry.name).collect()
+}
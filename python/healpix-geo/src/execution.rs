@@ -3,11 +3,8 @@ macro_rules! maybe_parallelize {
     ($nthreads:ident, $iterable:expr, $func:expr $(,)?) => {
         #[cfg(not(target_arch = "wasm32"))]
         {
-            let pool = rayon::ThreadPoolBuilder::new()
-                .num_threads($nthreads as usize)
-                .build()
-                .unwrap();
-            pool.install(|| $iterable.par_for_each($func));
+            healpix_geo_core::vectorized::execution::thread_pool($nthreads as usize)
+                .install(|| $iterable.par_for_each($func));
         }
         #[cfg(target_arch = "wasm32")]
         {
@@ -0,0 +1,95 @@
+use numpy::{PyArrayDyn, PyUntypedArrayMethods};
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+
+/// Coordinates in single or double precision
+#[derive(FromPyObject, IntoPyObject)]
+pub(crate) enum FloatArray<'py> {
+    Double(Bound<'py, PyArrayDyn<f64>>),
+    Single(Bound<'py, PyArrayDyn<f32>>),
+}
+
+impl<'py> FloatArray<'py> {
+    /// An array of zeros with the precision named by `dtype`
+    pub(crate) fn zeros(py: Python<'py>, shape: &[usize], dtype: &str) -> PyResult<Self> {
+        match dtype {
+            "float64" => Ok(Self::Double(PyArrayDyn::zeros(py, shape, false))),
+            "float32" => Ok(Self::Single(PyArrayDyn::zeros(py, shape, false))),
+            _ => Err(PyValueError::new_err(format!(
+                "unsupported dtype: {dtype}, expected float32 or float64"
+            ))),
+        }
+    }
+
+    pub(crate) fn shape(&self) -> Vec<usize> {
+        match self {
+            Self::Double(array) => array.shape().to_vec(),
+            Self::Single(array) => array.shape().to_vec(),
+        }
+    }
+}
+
+/// Refuse coordinate arrays that don't have the same shape
+pub(crate) fn check_shapes(arrays: &[&FloatArray<'_>]) -> PyResult<()> {
+    let shapes: Vec<Vec<usize>> = arrays.iter().map(|array| array.shape()).collect();
+
+    if shapes.windows(2).any(|pair| pair[0] != pair[1]) {
+        return Err(PyValueError::new_err(format!(
+            "the coordinate arrays must have the same shape: {shapes:?}"
+        )));
+    }
+
+    Ok(())
+}
+
+/// Evaluate `$body` with the arrays replaced by slices of the same precision
+///
+/// Raises a `TypeError` if the precision of the arrays differs.
+#[macro_export]
+macro_rules! with_float_slices {
+    (($($array:ident),+ $(,)?), $body:expr $(,)?) => {
+        match ($($array,)+) {
+            ($($crate::float::FloatArray::Double($array),)+) => {
+                $(let $array = $array.readonly();)+
+                $(let $array = $array.as_slice()?;)+
+
+                $body
+            }
+            ($($crate::float::FloatArray::Single($array),)+) => {
+                $(let $array = $array.readonly();)+
+                $(let $array = $array.as_slice()?;)+
+
+                $body
+            }
+            #[allow(unreachable_patterns)]
+            _ => Err(pyo3::exceptions::PyTypeError::new_err(
+                "the coordinates must all be either float32 or float64",
+            )),
+        }
+    };
+}
+
+/// Like `with_float_slices`, but with mutable slices to write the output into
+#[macro_export]
+macro_rules! with_float_slices_mut {
+    (($($array:ident),+ $(,)?), $body:expr $(,)?) => {
+        match ($(&$array,)+) {
+            ($($crate::float::FloatArray::Double($array),)+) => {
+                $(let mut $array = $array.readwrite();)+
+                $(let $array = $array.as_slice_mut()?;)+
+
+                $body
+            }
+            ($($crate::float::FloatArray::Single($array),)+) => {
+                $(let mut $array = $array.readwrite();)+
+                $(let $array = $array.as_slice_mut()?;)+
+
+                $body
+            }
+            #[allow(unreachable_patterns)]
+            _ => Err(pyo3::exceptions::PyTypeError::new_err(
+                "the coordinates must all be either float32 or float64",
+            )),
+        }
+    };
+}
@@ -1,4 +1,5 @@
 use numpy::{PyArray1, PyArrayDyn, PyArrayMethods};
+use pyo3::exceptions::{PyKeyError, PyRuntimeError, PyValueError};
 use pyo3::prelude::*;
 use pyo3::types::{PyBytes, PySlice, PyType};
 use std::collections::HashMap;
@@ -10,7 +11,8 @@ use crate::geometry::GeometryTypes;
 
 use healpix_geo_core::ellipsoid::ReferenceBody;
 use healpix_geo_core::index::{
-    Array, CellRegion, ConcreteSlice, LabelIndexer, PositionalIndexer, Slice,
+    Array, CellRegion, CellRegionBuilder, ConcreteSlice, DistanceMetric, IndexingScheme,
+    LabelError, LabelIndexer, PositionalIndexer, RegionChunk, SelectionMethod, Slice,
 };
 use healpix_geo_core::index::{GeometryQuery, Indexing, SetOperations};
 
@@ -101,7 +103,7 @@ impl<'py> IndexKind<'py> {
 ///
 /// The idea is to compress cell ids at depth 29 based on run-length encoding (RLE).
 ///
-/// Only works with cell ids following the "nested" scheme.
+/// The cell ids may follow the "nested", "ring" or "zuniq" scheme.
 #[derive(PartialEq, Debug, Clone)]
 #[pyclass(from_py_object)]
 #[pyo3(module = "healpix_geo.nested")]
@@ -119,15 +121,22 @@ impl RangeMOCIndex {
     /// ----------
     /// depth : int
     ///     The cell depth.
-    #[pyo3(signature = (depth, ellipsoid=EllipsoidLike::Named("sphere".to_string())))]
+    /// indexing_scheme : {"nested", "ring", "zuniq"}, default: "nested"
+    ///     The indexing scheme of the cell ids.
+    #[pyo3(signature = (depth, ellipsoid=EllipsoidLike::Named("sphere".to_string()), indexing_scheme="nested"))]
     #[classmethod]
     fn full_domain(
         _cls: &Bound<'_, PyType>,
         depth: u8,
         ellipsoid: EllipsoidLike,
+        indexing_scheme: &str,
     ) -> PyResult<Self> {
         let index = RangeMOCIndex {
-            region: CellRegion::full_domain(depth, ellipsoid.into_ellipsoid()?),
+            region: CellRegion::full_domain_with_scheme(
+                depth,
+                parse_indexing_scheme(indexing_scheme)?,
+                ellipsoid.into_ellipsoid()?,
+            ),
         };
 
         Ok(index)
@@ -139,11 +148,22 @@ impl RangeMOCIndex {
     /// ----------
     /// depth : int
     ///     The cell depth.
-    #[pyo3(signature = (depth, ellipsoid=EllipsoidLike::Named("sphere".to_string())))]
+    /// indexing_scheme : {"nested", "ring", "zuniq"}, default: "nested"
+    ///     The indexing scheme of the cell ids.
+    #[pyo3(signature = (depth, ellipsoid=EllipsoidLike::Named("sphere".to_string()), indexing_scheme="nested"))]
     #[classmethod]
-    fn empty(_cls: &Bound<'_, PyType>, depth: u8, ellipsoid: EllipsoidLike) -> PyResult<Self> {
+    fn empty(
+        _cls: &Bound<'_, PyType>,
+        depth: u8,
+        ellipsoid: EllipsoidLike,
+        indexing_scheme: &str,
+    ) -> PyResult<Self> {
         let index = RangeMOCIndex {
-            region: CellRegion::empty(depth, ellipsoid.into_ellipsoid()?),
+            region: CellRegion::empty_with_scheme(
+                depth,
+                parse_indexing_scheme(indexing_scheme)?,
+                ellipsoid.into_ellipsoid()?,
+            ),
         };
 
         Ok(index)
@@ -157,7 +177,9 @@ impl RangeMOCIndex {
     ///     The cell depth.
     /// cell_ids : numpy.ndarray
     ///     The cells to construct the the index from.
-    #[pyo3(signature = (depth, cell_ids, ellipsoid=EllipsoidLike::Named("sphere".to_string())))]
+    /// indexing_scheme : {"nested", "ring", "zuniq"}, default: "nested"
+    ///     The indexing scheme of the cell ids.
+    #[pyo3(signature = (depth, cell_ids, ellipsoid=EllipsoidLike::Named("sphere".to_string()), indexing_scheme="nested"))]
     #[classmethod]
     fn from_cell_ids<'a>(
         _cls: &Bound<'a, PyType>,
@@ -165,13 +187,16 @@ impl RangeMOCIndex {
         depth: u8,
         cell_ids: &Bound<'a, PyArray1<u64>>,
         ellipsoid: EllipsoidLike,
+        indexing_scheme: &str,
     ) -> PyResult<Self> {
         let index = RangeMOCIndex {
-            region: CellRegion::from_cell_ids(
+            region: CellRegion::from_cell_ids_with_scheme(
                 depth,
                 cell_ids.to_vec()?,
+                parse_indexing_scheme(indexing_scheme)?,
                 ellipsoid.into_ellipsoid()?,
-            ),
+            )
+            .map_err(invalid_label)?,
         };
 
         Ok(index)
@@ -189,10 +214,12 @@ impl RangeMOCIndex {
     /// -------
     /// result : RangeMOCIndex
     ///     The union of the two indexes.
-    fn union(&self, other: &RangeMOCIndex) -> Self {
-        RangeMOCIndex {
+    fn union(&self, other: &RangeMOCIndex) -> PyResult<Self> {
+        self.check_combinable(other)?;
+
+        Ok(RangeMOCIndex {
             region: self.region.union(&other.region),
-        }
+        })
     }
 
     /// Compute the set intersection of two indexes
@@ -207,10 +234,12 @@ impl RangeMOCIndex {
     /// -------
     /// result : RangeMOCIndex
     ///     The intersection of the two indexes.
-    fn intersection(&self, other: &RangeMOCIndex) -> Self {
-        RangeMOCIndex {
+    fn intersection(&self, other: &RangeMOCIndex) -> PyResult<Self> {
+        self.check_combinable(other)?;
+
+        Ok(RangeMOCIndex {
             region: self.region.intersection(&other.region),
-        }
+        })
     }
 
     /// Compute the set difference of two indexes
@@ -231,10 +260,12 @@ impl RangeMOCIndex {
     /// -------
     /// result : RangeMOCIndex
     ///     The set difference of the two indexes.
-    fn difference(&self, other: &RangeMOCIndex) -> Self {
-        RangeMOCIndex {
+    fn difference(&self, other: &RangeMOCIndex) -> PyResult<Self> {
+        self.check_combinable(other)?;
+
+        Ok(RangeMOCIndex {
             region: self.region.difference(&other.region),
-        }
+        })
     }
 
     /// Compute the symmetric set difference of two indexes
@@ -255,10 +286,57 @@ impl RangeMOCIndex {
     /// -------
     /// result : RangeMOCIndex
     ///     The symmetric set difference of the two indexes.
-    fn symmetric_difference(&self, other: &RangeMOCIndex) -> Self {
-        RangeMOCIndex {
+    fn symmetric_difference(&self, other: &RangeMOCIndex) -> PyResult<Self> {
+        self.check_combinable(other)?;
+
+        Ok(RangeMOCIndex {
             region: self.region.symmetric_difference(&other.region),
-        }
+        })
+    }
+
+    /// Whether the cells of both indexes are located within a tolerance
+    ///
+    /// Parameters
+    /// ----------
+    /// other : RangeMOCIndex
+    ///     The other index.
+    /// tolerance : float
+    ///     The maximum distance between the cells of both ellipsoids, in meters.
+    ///
+    /// Returns
+    /// -------
+    /// compatible : bool
+    ///     Whether the ellipsoids of both indexes are compatible.
+    fn is_compatible(&self, other: &RangeMOCIndex, tolerance: f64) -> bool {
+        self.region.is_compatible(&other.region, tolerance)
+    }
+
+    /// Move the index onto another ellipsoid
+    ///
+    /// The cells of the new ellipsoid whose centers lie within the index are
+    /// selected, matching geographic latitudes through the authalic latitudes.
+    ///
+    /// Parameters
+    /// ----------
+    /// ellipsoid : ellipsoid-like
+    ///     The new ellipsoid.
+    ///
+    /// Returns
+    /// -------
+    /// resampled : RangeMOCIndex
+    ///     The index on the new ellipsoid.
+    fn resample(&self, ellipsoid: EllipsoidLike) -> PyResult<Self> {
+        Ok(RangeMOCIndex {
+            region: self.region.resample(&ellipsoid.into_ellipsoid()?),
+        })
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "<healpix_geo.nested.RangeMOCIndex>\nindexing scheme: {}\n{}",
+            self.indexing_scheme(),
+            self.region.summary()
+        )
     }
 
     /// The size of the ranges in bytes, minus any overhead.
@@ -279,11 +357,28 @@ impl RangeMOCIndex {
         self.region.depth()
     }
 
+    /// The indexing scheme of the cell ids.
+    #[getter]
+    fn indexing_scheme(&self) -> &'static str {
+        match self.region.indexing_scheme() {
+            IndexingScheme::Nested => "nested",
+            IndexingScheme::Ring => "ring",
+            IndexingScheme::Zuniq => "zuniq",
+        }
+    }
+
+    /// The parameters of the ellipsoid of the index.
     #[getter]
     fn ellipsoid(&self) -> HashMap<String, f64> {
         self.region.ellipsoid().to_mapping()
     }
 
+    /// The name of the ellipsoid of the index, if it is a registered ellipsoid.
+    #[getter]
+    fn ellipsoid_name(&self) -> Option<&str> {
+        self.region.ellipsoid().name()
+    }
+
     pub fn __setstate__(&mut self, state: &[u8]) -> PyResult<()> {
         // Deserialize the data contained in the PyBytes object
         // and update the struct with the deserialized values.
@@ -327,7 +422,8 @@ impl RangeMOCIndex {
     /// Returns
     /// -------
     /// cell_ids : numpy.ndarray
-    ///     The cell ids contained by the index.
+    ///     The sorted cell ids contained by the index. Their order defines the
+    ///     positions used by the other methods.
     fn cell_ids<'a>(&self, py: Python<'a>) -> PyResult<Bound<'a, PyArray1<u64>>> {
         let cell_ids: Vec<u64> = self.region.cell_ids();
 
@@ -360,16 +456,56 @@ impl RangeMOCIndex {
     /// ----------
     /// indexer : slice of int or array-like
     ///     The cell ids or ranges of cell ids to find. If an array, must be of dtype uint64.
+    /// method : {None, "nearest"}, optional
+    ///     How to treat cell ids that are not part of the index. By default,
+    ///     they are missing. With ``"nearest"``, they are replaced by the
+    ///     nearest cell of the index.
+    /// tolerance : float, optional
+    ///     The maximum distance to the nearest cell. Cell ids without a cell
+    ///     within the tolerance are missing. Requires ``method="nearest"``.
+    /// metric : {"cell_id", "angular"}, default: "cell_id"
+    ///     The distance used by ``method="nearest"``: either the difference
+    ///     between the cell ids or the angular distance between the cell
+    ///     centers in degrees.
+    /// drop_missing : bool, default: False
+    ///     Skip missing cell ids instead of raising a ``KeyError``.
     ///
     /// Returns
     /// -------
     /// subset : RangeMOCIndex
     ///     The resulting subset.
     /// indexer : slice of int or array-like
-    ///     The integer positions of the selected cells as a uint64 array.
-    fn sel<'a>(&self, py: Python<'a>, indexer: IndexKind<'a>) -> PyResult<(IndexKind<'a>, Self)> {
+    ///     The integer positions of the selected cells as a uint64 array. The
+    ///     positions are unique and sorted like the cells of ``subset``.
+    #[pyo3(signature = (indexer, *, method=None, tolerance=None, metric="cell_id", drop_missing=false))]
+    fn sel<'a>(
+        &self,
+        py: Python<'a>,
+        indexer: IndexKind<'a>,
+        method: Option<&str>,
+        tolerance: Option<f64>,
+        metric: &str,
+        drop_missing: bool,
+    ) -> PyResult<(IndexKind<'a>, Self)> {
         let label_indexer = indexer.into_label_indexer()?;
-        let (region, positional_indexer) = self.region.sel(&label_indexer);
+        if let LabelIndexer::Array(array) = &label_indexer {
+            let scheme = self.region.indexing_scheme();
+            for &label in array.data.iter() {
+                scheme
+                    .check_label(label, self.region.depth())
+                    .map_err(invalid_label)?;
+            }
+        }
+        let method = selection_method(method, tolerance, metric)?;
+        let (region, positional_indexer, missing) =
+            self.region.sel_with_method(&label_indexer, &method);
+
+        if !drop_missing && !missing.data.is_empty() {
+            return Err(PyKeyError::new_err(format!(
+                "cell ids not found in the index: {:?}",
+                missing.data
+            )));
+        }
 
         let pyindexer = IndexKind::from_positional_indexer(py, positional_indexer)?;
         let new_index = Self { region };
@@ -408,4 +544,277 @@ impl RangeMOCIndex {
             RangeMOCIndex { region: new_region },
         ))
     }
+
+    /// Split the index into chunks aligned to coarser cells
+    ///
+    /// Indexes using the "ring" indexing scheme can't be chunked, since the
+    /// cells of a chunk don't have contiguous positions.
+    ///
+    /// Parameters
+    /// ----------
+    /// chunk_depth : int
+    ///     The depth of the cells to align the chunks to. Must not be greater
+    ///     than the depth of the index.
+    ///
+    /// Returns
+    /// -------
+    /// chunks : list of tuple of int, slice and RangeMOCIndex
+    ///     For each chunk, the parent cell id, the positions of the chunk's
+    ///     cells within the index, and the index of the chunk's cells.
+    fn chunk<'py>(
+        &self,
+        py: Python<'py>,
+        chunk_depth: u8,
+    ) -> PyResult<Vec<(u64, Bound<'py, PySlice>, Self)>> {
+        self.check_chunkable()?;
+        self.check_chunk_depth(chunk_depth)?;
+
+        Ok(into_pychunks(py, self.region.chunk(chunk_depth)))
+    }
+
+    /// Split the index into chunks containing at most `max_size` cells
+    ///
+    /// The chunks are aligned to the coarsest depth whose cells contain at
+    /// most `max_size` cells of the index.
+    ///
+    /// Parameters
+    /// ----------
+    /// max_size : int
+    ///     The maximum number of cells per chunk.
+    ///
+    /// Returns
+    /// -------
+    /// chunks : list of tuple of int, slice and RangeMOCIndex
+    ///     For each chunk, the parent cell id, the positions of the chunk's
+    ///     cells within the index, and the index of the chunk's cells.
+    fn chunk_to_size<'py>(
+        &self,
+        py: Python<'py>,
+        max_size: u64,
+    ) -> PyResult<Vec<(u64, Bound<'py, PySlice>, Self)>> {
+        self.check_chunkable()?;
+        if max_size == 0 {
+            return Err(PyValueError::new_err("max_size must be positive"));
+        }
+
+        Ok(into_pychunks(py, self.region.chunk_to_size(max_size)))
+    }
+
+    /// Find the chunks intersecting a geometry
+    ///
+    /// Parameters
+    /// ----------
+    /// chunk_depth : int
+    ///     The depth the chunks are aligned to.
+    /// geometry : healpix_geo.geometry.Bbox or geometry-like
+    ///     The geometry to query by.
+    ///
+    /// Returns
+    /// -------
+    /// chunks : list of int
+    ///     The positions of the intersecting chunks within the result of
+    ///     ``chunk(chunk_depth)``.
+    fn query_chunks<'py>(
+        &self,
+        py: Python<'py>,
+        chunk_depth: u8,
+        geometry: &Bound<'py, PyAny>,
+    ) -> PyResult<Vec<usize>> {
+        self.check_chunkable()?;
+        self.check_chunk_depth(chunk_depth)?;
+        let geom = GeometryTypes::from_pyobject(py, geometry)?.into_geometry()?;
+
+        Ok(self.region.query_chunks(chunk_depth, &geom))
+    }
+}
+
+impl RangeMOCIndex {
+    /// Refuse to combine indexes with different schemes or ellipsoids
+    fn check_combinable(&self, other: &RangeMOCIndex) -> PyResult<()> {
+        let (first, second) = (self.region.ellipsoid(), other.region.ellipsoid());
+
+        if self.indexing_scheme() != other.indexing_scheme() {
+            Err(PyValueError::new_err(format!(
+                "indexing schemes don't match: {} and {}",
+                self.indexing_scheme(),
+                other.indexing_scheme()
+            )))
+        } else if !first.same_body(second) {
+            Err(PyValueError::new_err(format!(
+                "indexes are defined on different bodies: {} and {}",
+                first.body().unwrap_or_default(),
+                second.body().unwrap_or_default()
+            )))
+        } else if first != second {
+            Err(PyValueError::new_err("ellipsoids don't match"))
+        } else {
+            Ok(())
+        }
+    }
+
+    fn check_chunkable(&self) -> PyResult<()> {
+        if self.region.indexing_scheme() == IndexingScheme::Ring {
+            return Err(PyValueError::new_err(
+                "indexes with the \"ring\" indexing scheme can't be chunked",
+            ));
+        }
+
+        Ok(())
+    }
+
+    fn check_chunk_depth(&self, chunk_depth: u8) -> PyResult<()> {
+        if chunk_depth > self.region.depth() {
+            return Err(PyValueError::new_err(format!(
+                "chunk depth must not be greater than the depth of the index ({})",
+                self.region.depth()
+            )));
+        }
+
+        Ok(())
+    }
+}
+
+fn parse_indexing_scheme(name: &str) -> PyResult<IndexingScheme> {
+    match name {
+        "nested" => Ok(IndexingScheme::Nested),
+        "ring" => Ok(IndexingScheme::Ring),
+        "zuniq" => Ok(IndexingScheme::Zuniq),
+        _ => Err(PyValueError::new_err(format!(
+            "unknown indexing scheme: {name}"
+        ))),
+    }
+}
+
+fn selection_method(
+    method: Option<&str>,
+    tolerance: Option<f64>,
+    metric: &str,
+) -> PyResult<SelectionMethod> {
+    let metric = match metric {
+        "cell_id" => DistanceMetric::CellId,
+        "angular" => DistanceMetric::Angular,
+        _ => {
+            return Err(PyValueError::new_err(format!(
+                "unknown distance metric: {metric}"
+            )));
+        }
+    };
+
+    match (method, tolerance) {
+        // missing labels are reported instead of panicking, so they can be
+        // raised as `KeyError`
+        (None, None) => Ok(SelectionMethod::DropMissing),
+        (None, Some(_)) => Err(PyValueError::new_err(
+            "tolerance requires method=\"nearest\"",
+        )),
+        (Some("nearest"), tolerance) => Ok(SelectionMethod::Nearest { metric, tolerance }),
+        (Some(method), _) => Err(PyValueError::new_err(format!(
+            "unknown selection method: {method}"
+        ))),
+    }
+}
+
+fn into_pychunks(
+    py: Python<'_>,
+    chunks: Vec<RegionChunk>,
+) -> Vec<(u64, Bound<'_, PySlice>, RangeMOCIndex)> {
+    chunks
+        .into_iter()
+        .map(|chunk| {
+            (
+                chunk.cell_id,
+                chunk.positions.into_pyslice(py),
+                RangeMOCIndex {
+                    region: chunk.region,
+                },
+            )
+        })
+        .collect()
+}
+
+/// Incrementally build a range-based index from batches of cell ids
+///
+/// The cell ids may be unsorted and contain duplicates. Only a bounded number
+/// of them is kept in memory before being merged into the index.
+#[pyclass]
+#[pyo3(module = "healpix_geo.nested")]
+pub struct RangeMOCIndexBuilder {
+    builder: Option<CellRegionBuilder>,
+}
+
+#[pymethods]
+impl RangeMOCIndexBuilder {
+    /// Parameters
+    /// ----------
+    /// depth : int
+    ///     The cell depth.
+    /// buffer_size : int, optional
+    ///     The maximum number of cell ids to hold before merging them.
+    /// indexing_scheme : {"nested", "ring", "zuniq"}, default: "nested"
+    ///     The indexing scheme of the cell ids.
+    #[new]
+    #[pyo3(signature = (depth, ellipsoid=EllipsoidLike::Named("sphere".to_string()), buffer_size=None, indexing_scheme="nested"))]
+    fn new(
+        depth: u8,
+        ellipsoid: EllipsoidLike,
+        buffer_size: Option<usize>,
+        indexing_scheme: &str,
+    ) -> PyResult<Self> {
+        let ellipsoid = ellipsoid.into_ellipsoid()?;
+        let indexing_scheme = parse_indexing_scheme(indexing_scheme)?;
+        let builder = match buffer_size {
+            Some(0) => return Err(PyValueError::new_err("buffer_size must be positive")),
+            Some(size) => {
+                CellRegionBuilder::with_buffer_capacity(depth, indexing_scheme, ellipsoid, size)
+            }
+            None => CellRegionBuilder::new(depth, indexing_scheme, ellipsoid),
+        };
+
+        Ok(Self {
+            builder: Some(builder),
+        })
+    }
+
+    /// Add a batch of cell ids
+    ///
+    /// Parameters
+    /// ----------
+    /// cell_ids : numpy.ndarray
+    ///     The cell ids to add, as a uint64 array.
+    fn add_cell_ids(&mut self, cell_ids: &Bound<'_, PyArray1<u64>>) -> PyResult<()> {
+        let builder = self.builder.as_mut().ok_or_else(already_built)?;
+
+        // no copy of the batch is made if the array is contiguous
+        let readonly = cell_ids.readonly();
+        let result = match readonly.as_slice() {
+            Ok(values) => builder.add_cell_ids(values),
+            Err(_) => builder.add_cell_ids(&cell_ids.to_vec()?),
+        };
+
+        result.map_err(invalid_label)
+    }
+
+    /// Create the index
+    ///
+    /// The builder can't be used afterwards.
+    ///
+    /// Returns
+    /// -------
+    /// index : RangeMOCIndex
+    ///     The index containing all added cell ids.
+    fn build(&mut self) -> PyResult<RangeMOCIndex> {
+        let builder = self.builder.take().ok_or_else(already_built)?;
+
+        Ok(RangeMOCIndex {
+            region: builder.build(),
+        })
+    }
+}
+
+fn already_built() -> PyErr {
+    PyRuntimeError::new_err("the index has already been built")
+}
+
+fn invalid_label(error: LabelError) -> PyErr {
+    PyValueError::new_err(error.to_string())
 }
@@ -2,6 +2,7 @@ use cdshealpix as healpix;
 use numpy::{PyArray1, PyArray2, PyArrayDyn, PyArrayMethods, PyUntypedArrayMethods};
 use pyo3::prelude::*;
 
+use healpix_geo_core::scalar::hierarchy::{n_neighbourhood, n_neighbours};
 use healpix_geo_core::vectorized::nested::hierarchy as vectorized;
 
 /// Wrapper of `kth_neighbours`
@@ -17,12 +18,16 @@ pub(crate) fn kth_neighbours<'py>(
     let ipix_ = ipix.readonly();
 
     let layer = healpix::nested::get(depth);
-    let result = vectorized::kth_neighbours(ipix_.as_slice()?, layer, &ring, nthreads as usize);
+    let result =
+        vectorized::kth_neighbours_flat(ipix_.as_slice()?, layer, &ring, nthreads as usize);
 
-    let n_neighbours = 8 * ring as usize;
-    let output_shape: Vec<usize> = input_shape.iter().copied().chain([n_neighbours]).collect();
+    let output_shape: Vec<usize> = input_shape
+        .iter()
+        .copied()
+        .chain([n_neighbours(&ring)])
+        .collect();
 
-    PyArray2::from_vec2(py, &result)?.reshape(output_shape.as_slice())
+    PyArray1::from_vec(py, result).reshape(output_shape.as_slice())
 }
 
 /// Wrapper of `kth_neighbourhood`
@@ -38,12 +43,16 @@ pub(crate) fn kth_neighbourhood<'py>(
     let ipix_ = ipix.readonly();
 
     let layer = healpix::nested::get(depth);
-    let result = vectorized::kth_neighbourhood(ipix_.as_slice()?, layer, &ring, nthreads as usize);
+    let result =
+        vectorized::kth_neighbourhood_flat(ipix_.as_slice()?, layer, &ring, nthreads as usize);
 
-    let n_neighbours = usize::pow(2 * ring as usize + 1, 2);
-    let output_shape: Vec<usize> = input_shape.iter().copied().chain([n_neighbours]).collect();
+    let output_shape: Vec<usize> = input_shape
+        .iter()
+        .copied()
+        .chain([n_neighbourhood(&ring)])
+        .collect();
 
-    PyArray2::from_vec2(py, &result)?.reshape(output_shape.as_slice())
+    PyArray1::from_vec(py, result).reshape(output_shape.as_slice())
 }
 
 #[pyfunction]
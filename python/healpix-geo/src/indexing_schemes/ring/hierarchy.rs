@@ -1,7 +1,8 @@
 use cdshealpix as healpix;
-use numpy::{PyArray2, PyArrayDyn, PyArrayMethods, PyUntypedArrayMethods};
+use numpy::{PyArray1, PyArrayDyn, PyArrayMethods, PyUntypedArrayMethods};
 use pyo3::prelude::*;
 
+use healpix_geo_core::scalar::hierarchy::{n_neighbourhood, n_neighbours};
 use healpix_geo_core::vectorized::ring::hierarchy as vectorized;
 
 /// Wrapper of `kth_neighbours`
@@ -17,12 +18,16 @@ pub(crate) fn kth_neighbours<'py>(
     let ipix_ = ipix.readonly();
 
     let nside = healpix::nside(depth);
-    let result = vectorized::kth_neighbours(ipix_.as_slice()?, &nside, &ring, nthreads as usize);
+    let result =
+        vectorized::kth_neighbours_flat(ipix_.as_slice()?, &nside, &ring, nthreads as usize);
 
-    let n_neighbours = 8 * ring as usize;
-    let output_shape: Vec<usize> = input_shape.iter().copied().chain([n_neighbours]).collect();
+    let output_shape: Vec<usize> = input_shape
+        .iter()
+        .copied()
+        .chain([n_neighbours(&ring)])
+        .collect();
 
-    PyArray2::from_vec2(py, &result)?.reshape(output_shape.as_slice())
+    PyArray1::from_vec(py, result).reshape(output_shape.as_slice())
 }
 
 /// Wrapper of `kth_neighbourhood`
@@ -38,17 +43,14 @@ pub(crate) fn kth_neighbourhood<'py>(
     let input_shape = ipix.shape();
 
     let nside = healpix::nside(depth);
-    let result = vectorized::kth_neighbourhood(ipix_.as_slice()?, &nside, &ring, nthreads as usize);
-
-    let output_shape: Vec<usize> = if ipix.len() == 0 {
-        input_shape.to_vec()
-    } else {
-        input_shape
-            .iter()
-            .copied()
-            .chain([result[0].len()])
-            .collect()
-    };
-
-    PyArray2::from_vec2(py, &result)?.reshape(output_shape.as_slice())
+    let result =
+        vectorized::kth_neighbourhood_flat(ipix_.as_slice()?, &nside, &ring, nthreads as usize);
+
+    let output_shape: Vec<usize> = input_shape
+        .iter()
+        .copied()
+        .chain([n_neighbourhood(&ring)])
+        .collect();
+
+    PyArray1::from_vec(py, result).reshape(output_shape.as_slice())
 }
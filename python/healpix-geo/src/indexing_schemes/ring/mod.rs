@@ -3,8 +3,8 @@ mod coverage;
 mod hierarchy;
 
 pub(crate) use self::coordinates::{
-    angular_distances, bilinear_interpolation, cartesian_to_healpix, healpix_to_cartesian,
-    healpix_to_lonlat, lonlat_to_healpix, vertices,
+    angular_distances, bilinear_interpolation, cartesian_to_healpix, geodesic_distances,
+    healpix_to_cartesian, healpix_to_lonlat, lonlat_to_healpix, point_geodesic_distances, vertices,
 };
 pub(crate) use self::coverage::{
     box_coverage, cone_coverage, elliptical_cone_coverage, polygon_coverage, zone_coverage,
@@ -1,4 +1,6 @@
 use crate::ellipsoid::EllipsoidLike;
+use crate::float::{FloatArray, check_shapes};
+use crate::{with_float_slices, with_float_slices_mut};
 use cdshealpix as healpix;
 
 use numpy::{PyArray1, PyArray2, PyArrayDyn, PyArrayMethods, PyUntypedArrayMethods};
@@ -6,59 +8,71 @@ use pyo3::exceptions::PyNotImplementedError;
 use pyo3::prelude::*;
 
 use crate::indexing_schemes::depth::DepthLike;
-use crate::traits::Unzip3;
 use healpix_geo_core::vectorized::zuniq::coordinates as vectorized;
 
-#[allow(clippy::type_complexity)]
 #[pyfunction]
 pub(crate) fn healpix_to_lonlat<'py>(
     py: Python<'py>,
     ipix: &Bound<'py, PyArrayDyn<u64>>,
     ellipsoid_like: EllipsoidLike,
     nthreads: u16,
-) -> PyResult<(Bound<'py, PyArrayDyn<f64>>, Bound<'py, PyArrayDyn<f64>>)> {
+    dtype: &str,
+) -> PyResult<(FloatArray<'py>, FloatArray<'py>)> {
     let ellipsoid = ellipsoid_like.into_ellipsoid()?;
     let input_shape = ipix.shape();
 
     let ipix_ = ipix.readonly();
 
-    let (lon, lat): (Vec<f64>, Vec<f64>) =
-        vectorized::healpix_to_lonlat(ipix_.as_slice()?, &ellipsoid, nthreads as usize)
-            .into_iter()
-            .unzip();
-
-    Ok((
-        PyArray1::from_vec(py, lon).reshape(input_shape)?,
-        PyArray1::from_vec(py, lat).reshape(input_shape)?,
-    ))
+    let lon = FloatArray::zeros(py, input_shape, dtype)?;
+    let lat = FloatArray::zeros(py, input_shape, dtype)?;
+    with_float_slices_mut!((lon, lat), {
+        vectorized::healpix_to_lonlat_into(
+            ipix_.as_slice()?,
+            &ellipsoid,
+            lon,
+            lat,
+            nthreads as usize,
+        );
+
+        Ok(())
+    })?;
+
+    Ok((lon, lat))
 }
 
 #[pyfunction]
 pub(crate) fn lonlat_to_healpix<'py>(
     py: Python<'py>,
     depth: DepthLike,
-    longitude: &Bound<'py, PyArrayDyn<f64>>,
-    latitude: &Bound<'py, PyArrayDyn<f64>>,
+    longitude: FloatArray<'py>,
+    latitude: FloatArray<'py>,
     ellipsoid_like: EllipsoidLike,
     nthreads: u16,
 ) -> PyResult<Bound<'py, PyArrayDyn<u64>>> {
     let ellipsoid = ellipsoid_like.into_ellipsoid()?;
+    check_shapes(&[&longitude, &latitude])?;
     let input_shape = longitude.shape();
 
-    let lon = longitude.readonly();
-    let lat = latitude.readonly();
-    let coords: Vec<(f64, f64)> = lon
-        .as_slice()?
-        .iter()
-        .zip(lat.as_slice()?)
-        .map(|(&lon, &lat)| (lon, lat))
-        .collect();
+    let ipix = PyArray1::<u64>::zeros(py, input_shape.iter().product(), false);
 
-    let ipix = match depth {
+    match depth {
         DepthLike::Constant(d) => {
             let layer = healpix::nested::get(d);
 
-            vectorized::lonlat_to_healpix(&coords, layer, &ellipsoid, nthreads as usize)
+            with_float_slices!((longitude, latitude), {
+                let mut ipix_ = ipix.readwrite();
+
+                vectorized::lonlat_to_healpix_into(
+                    longitude,
+                    latitude,
+                    layer,
+                    &ellipsoid,
+                    ipix_.as_slice_mut()?,
+                    nthreads as usize,
+                );
+
+                Ok(())
+            })?;
         }
         DepthLike::Array(_depths) => {
             return Err(PyNotImplementedError::new_err("not implemented yet!"));
@@ -90,65 +104,75 @@ pub(crate) fn lonlat_to_healpix<'py>(
         }
     };
 
-    PyArray1::from_vec(py, ipix).reshape(input_shape)
+    ipix.reshape(input_shape)
 }
 
-#[allow(clippy::type_complexity)]
 #[pyfunction]
 pub(crate) fn healpix_to_cartesian<'py>(
     py: Python<'py>,
     ipix: &Bound<'py, PyArrayDyn<u64>>,
     ellipsoid_like: EllipsoidLike,
     nthreads: u16,
-) -> PyResult<(
-    Bound<'py, PyArrayDyn<f64>>,
-    Bound<'py, PyArrayDyn<f64>>,
-    Bound<'py, PyArrayDyn<f64>>,
-)> {
+    dtype: &str,
+) -> PyResult<(FloatArray<'py>, FloatArray<'py>, FloatArray<'py>)> {
     let ellipsoid = ellipsoid_like.into_ellipsoid()?;
     let input_shape = ipix.shape();
 
     let ipix_ = ipix.readonly();
 
-    let (x, y, z): (Vec<f64>, Vec<f64>, Vec<f64>) =
-        vectorized::healpix_to_cartesian(ipix_.as_slice()?, &ellipsoid, nthreads as usize).unzip3();
-
-    Ok((
-        PyArray1::from_vec(py, x).reshape(input_shape)?,
-        PyArray1::from_vec(py, y).reshape(input_shape)?,
-        PyArray1::from_vec(py, z).reshape(input_shape)?,
-    ))
+    let x = FloatArray::zeros(py, input_shape, dtype)?;
+    let y = FloatArray::zeros(py, input_shape, dtype)?;
+    let z = FloatArray::zeros(py, input_shape, dtype)?;
+    with_float_slices_mut!((x, y, z), {
+        vectorized::healpix_to_cartesian_into(
+            ipix_.as_slice()?,
+            &ellipsoid,
+            x,
+            y,
+            z,
+            nthreads as usize,
+        );
+
+        Ok(())
+    })?;
+
+    Ok((x, y, z))
 }
 
 #[pyfunction]
 pub(crate) fn cartesian_to_healpix<'py>(
     py: Python<'py>,
     depth: u8,
-    x: &Bound<'py, PyArrayDyn<f64>>,
-    y: &Bound<'py, PyArrayDyn<f64>>,
-    z: &Bound<'py, PyArrayDyn<f64>>,
+    x: FloatArray<'py>,
+    y: FloatArray<'py>,
+    z: FloatArray<'py>,
     ellipsoid_like: EllipsoidLike,
     nthreads: u16,
 ) -> PyResult<Bound<'py, PyArrayDyn<u64>>> {
     let ellipsoid = ellipsoid_like.into_ellipsoid()?;
+    check_shapes(&[&x, &y, &z])?;
     let input_shape = x.shape();
 
-    let x = x.readonly();
-    let y = y.readonly();
-    let z = z.readonly();
-    let coords: Vec<(f64, f64, f64)> = x
-        .as_slice()?
-        .iter()
-        .zip(y.as_slice()?)
-        .zip(z.as_slice()?)
-        .map(|((&x, &y), &z)| (x, y, z))
-        .collect();
-
     let layer = healpix::nested::get(depth);
 
-    let ipix = vectorized::cartesian_to_healpix(&coords, layer, &ellipsoid, nthreads as usize);
+    let ipix = PyArray1::<u64>::zeros(py, input_shape.iter().product(), false);
+    with_float_slices!((x, y, z), {
+        let mut ipix_ = ipix.readwrite();
 
-    PyArray1::from_vec(py, ipix).reshape(input_shape)
+        vectorized::cartesian_to_healpix_into(
+            x,
+            y,
+            z,
+            layer,
+            &ellipsoid,
+            ipix_.as_slice_mut()?,
+            nthreads as usize,
+        );
+
+        Ok(())
+    })?;
+
+    ipix.reshape(input_shape)
 }
 
 #[allow(clippy::type_complexity)]
@@ -219,3 +243,70 @@ pub(crate) fn bilinear_interpolation<'py>(
 
     Ok((ipix_, weights_))
 }
+
+/// Geodesic distances between cell centers, in meters
+///
+/// The rows of `to` are compared to the corresponding entry of `from`.
+#[pyfunction]
+pub(crate) fn geodesic_distances<'py>(
+    py: Python<'py>,
+    from: &Bound<'py, PyArray1<u64>>,
+    to: &Bound<'py, PyArray2<u64>>,
+    ellipsoid_like: EllipsoidLike,
+    nthreads: u16,
+) -> PyResult<Bound<'py, PyArray2<f64>>> {
+    use healpix_geo_core::vectorized::zuniq::distances as vectorized;
+
+    let ellipsoid = ellipsoid_like.into_geodesic_ellipsoid()?;
+    let from_ = from.readonly();
+    let to_ = to.readonly();
+    let cols = to.shape()[1];
+
+    let result = vectorized::geodesic_distances(
+        from_.as_slice()?,
+        to_.as_slice()?,
+        cols,
+        &ellipsoid,
+        nthreads as usize,
+    );
+
+    Ok(PyArray2::from_vec2(py, &result)?)
+}
+
+/// Geodesic distances between points and cell centers, in meters
+///
+/// The rows of `to` are compared to the corresponding point.
+#[allow(clippy::too_many_arguments)]
+#[pyfunction]
+pub(crate) fn point_geodesic_distances<'py>(
+    py: Python<'py>,
+    longitude: &Bound<'py, PyArray1<f64>>,
+    latitude: &Bound<'py, PyArray1<f64>>,
+    to: &Bound<'py, PyArray2<u64>>,
+    ellipsoid_like: EllipsoidLike,
+    nthreads: u16,
+) -> PyResult<Bound<'py, PyArray2<f64>>> {
+    use healpix_geo_core::vectorized::zuniq::distances as vectorized;
+
+    let ellipsoid = ellipsoid_like.into_geodesic_ellipsoid()?;
+    let lon = longitude.readonly();
+    let lat = latitude.readonly();
+    let points: Vec<(f64, f64)> = lon
+        .as_slice()?
+        .iter()
+        .zip(lat.as_slice()?)
+        .map(|(&lon, &lat)| (lon, lat))
+        .collect();
+    let to_ = to.readonly();
+    let cols = to.shape()[1];
+
+    let result = vectorized::point_geodesic_distances(
+        &points,
+        to_.as_slice()?,
+        cols,
+        &ellipsoid,
+        nthreads as usize,
+    );
+
+    Ok(PyArray2::from_vec2(py, &result)?)
+}
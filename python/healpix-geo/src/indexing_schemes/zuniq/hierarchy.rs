@@ -1,6 +1,7 @@
-use numpy::{PyArray2, PyArrayDyn, PyArrayMethods, PyUntypedArrayMethods};
+use numpy::{PyArray1, PyArrayDyn, PyArrayMethods, PyUntypedArrayMethods};
 use pyo3::prelude::*;
 
+use healpix_geo_core::scalar::hierarchy::{n_neighbourhood, n_neighbours};
 use healpix_geo_core::vectorized::zuniq::hierarchy as vectorized;
 
 /// Wrapper of `kth_neighbourhood`
@@ -14,19 +15,15 @@ pub(crate) fn kth_neighbours<'py>(
     let ipix_ = ipix.readonly();
     let input_shape = ipix.shape();
 
-    let result = vectorized::kth_neighbours(ipix_.as_slice()?, &ring, nthreads as usize);
+    let result = vectorized::kth_neighbours_flat(ipix_.as_slice()?, &ring, nthreads as usize);
 
-    let output_shape: Vec<usize> = if ipix.len() == 0 {
-        input_shape.to_vec()
-    } else {
-        input_shape
-            .iter()
-            .copied()
-            .chain([result[0].len()])
-            .collect()
-    };
+    let output_shape: Vec<usize> = input_shape
+        .iter()
+        .copied()
+        .chain([n_neighbours(&ring)])
+        .collect();
 
-    PyArray2::from_vec2(py, &result)?.reshape(output_shape.as_slice())
+    PyArray1::from_vec(py, result).reshape(output_shape.as_slice())
 }
 
 /// Wrapper of `kth_neighbourhood`
@@ -40,17 +37,13 @@ pub(crate) fn kth_neighbourhood<'py>(
     let ipix_ = ipix.readonly();
     let input_shape = ipix.shape();
 
-    let result = vectorized::kth_neighbourhood(ipix_.as_slice()?, &ring, nthreads as usize);
+    let result = vectorized::kth_neighbourhood_flat(ipix_.as_slice()?, &ring, nthreads as usize);
 
-    let output_shape: Vec<usize> = if ipix.len() == 0 {
-        input_shape.to_vec()
-    } else {
-        input_shape
-            .iter()
-            .copied()
-            .chain([result[0].len()])
-            .collect()
-    };
+    let output_shape: Vec<usize> = input_shape
+        .iter()
+        .copied()
+        .chain([n_neighbourhood(&ring)])
+        .collect();
 
-    PyArray2::from_vec2(py, &result)?.reshape(output_shape.as_slice())
+    PyArray1::from_vec(py, result).reshape(output_shape.as_slice())
 }
@@ -5,8 +5,8 @@ mod hierarchy;
 
 pub(crate) use self::conversion::{from_nested, to_nested};
 pub(crate) use self::coordinates::{
-    bilinear_interpolation, cartesian_to_healpix, healpix_to_cartesian, healpix_to_lonlat,
-    lonlat_to_healpix, vertices,
+    bilinear_interpolation, cartesian_to_healpix, geodesic_distances, healpix_to_cartesian,
+    healpix_to_lonlat, lonlat_to_healpix, point_geodesic_distances, vertices,
 };
 pub(crate) use self::coverage::{
     box_coverage, cone_coverage, elliptical_cone_coverage, polygon_coverage, zone_coverage,
@@ -2,22 +2,24 @@ use pyo3::prelude::*;
 
 mod ellipsoid;
 mod execution;
+mod float;
 mod geometry;
 mod index;
 mod indexing_schemes;
 mod traits;
+mod transform;
 
 #[pymodule]
 mod nested {
     #[pymodule_export]
-    use super::index::RangeMOCIndex;
+    use super::index::{RangeMOCIndex, RangeMOCIndexBuilder};
 
     #[pymodule_export]
     use crate::indexing_schemes::nested::{
         angular_distances, bilinear_interpolation, box_coverage, cartesian_to_healpix,
-        cone_coverage, elliptical_cone_coverage, healpix_to_cartesian, healpix_to_lonlat,
-        internal_boundary, kth_neighbourhood, kth_neighbours, lonlat_to_healpix, polygon_coverage,
-        siblings, vertices, zone_coverage, zoom_to,
+        cone_coverage, elliptical_cone_coverage, geodesic_distances, healpix_to_cartesian,
+        healpix_to_lonlat, internal_boundary, kth_neighbourhood, kth_neighbours, lonlat_to_healpix,
+        point_geodesic_distances, polygon_coverage, siblings, vertices, zone_coverage, zoom_to,
     };
 }
 
@@ -26,9 +28,9 @@ mod ring {
     #[pymodule_export]
     use crate::indexing_schemes::ring::{
         angular_distances, bilinear_interpolation, box_coverage, cartesian_to_healpix,
-        cone_coverage, elliptical_cone_coverage, healpix_to_cartesian, healpix_to_lonlat,
-        kth_neighbourhood, kth_neighbours, lonlat_to_healpix, polygon_coverage, vertices,
-        zone_coverage,
+        cone_coverage, elliptical_cone_coverage, geodesic_distances, healpix_to_cartesian,
+        healpix_to_lonlat, kth_neighbourhood, kth_neighbours, lonlat_to_healpix,
+        point_geodesic_distances, polygon_coverage, vertices, zone_coverage,
     };
 }
 
@@ -37,9 +39,9 @@ mod zuniq {
     #[pymodule_export]
     use crate::indexing_schemes::zuniq::{
         bilinear_interpolation, box_coverage, cartesian_to_healpix, cone_coverage,
-        elliptical_cone_coverage, from_nested, healpix_to_cartesian, healpix_to_lonlat,
-        kth_neighbourhood, kth_neighbours, lonlat_to_healpix, polygon_coverage, to_nested,
-        vertices, zone_coverage,
+        elliptical_cone_coverage, from_nested, geodesic_distances, healpix_to_cartesian,
+        healpix_to_lonlat, kth_neighbourhood, kth_neighbours, lonlat_to_healpix,
+        point_geodesic_distances, polygon_coverage, to_nested, vertices, zone_coverage,
     };
 }
 
@@ -66,4 +68,10 @@ mod healpix_geo {
 
     #[pymodule_export]
     use crate::geometry::{cartesian_to_lonlat, lonlat_to_cartesian};
+
+    #[pymodule_export]
+    use crate::ellipsoid::ellipsoid_names;
+
+    #[pymodule_export]
+    use crate::transform::InputTransform;
 }
@@ -0,0 +1,136 @@
+use numpy::{PyArray1, PyArrayDyn, PyArrayMethods, PyUntypedArrayMethods};
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+
+use healpix_geo_core::transform::{
+    InputCoordinates, InputTransform as HgInputTransform, TransformError,
+};
+
+fn to_pyerr(error: TransformError) -> PyErr {
+    PyValueError::new_err(error.to_string())
+}
+
+fn check_shapes(shapes: &[&[usize]]) -> PyResult<()> {
+    if shapes.windows(2).any(|pair| pair[0] != pair[1]) {
+        return Err(PyValueError::new_err(format!(
+            "the coordinate arrays must have the same shape: {shapes:?}"
+        )));
+    }
+
+    Ok(())
+}
+
+/// geodesy operator applied to input coordinates
+#[derive(Clone)]
+#[pyclass(from_py_object)]
+#[pyo3(module = "healpix_geo", frozen)]
+pub struct InputTransform {
+    transform: HgInputTransform,
+}
+
+#[pymethods]
+impl InputTransform {
+    #[new]
+    #[pyo3(signature = (definition, *, inverse=false, projected=false))]
+    fn new(definition: &str, inverse: bool, projected: bool) -> PyResult<Self> {
+        let input = if projected {
+            InputCoordinates::Projected
+        } else {
+            InputCoordinates::Geographic
+        };
+
+        let transform = HgInputTransform::new(definition, input).map_err(to_pyerr)?;
+        let transform = if inverse {
+            transform.inverted()
+        } else {
+            transform
+        };
+
+        Ok(Self { transform })
+    }
+
+    #[getter]
+    fn definition(&self) -> &str {
+        self.transform.definition()
+    }
+
+    #[pyo3(signature = (x, y, *, num_threads=0))]
+    fn apply<'py>(
+        &self,
+        py: Python<'py>,
+        x: &Bound<'py, PyArrayDyn<f64>>,
+        y: &Bound<'py, PyArrayDyn<f64>>,
+        num_threads: u16,
+    ) -> PyResult<(Bound<'py, PyArrayDyn<f64>>, Bound<'py, PyArrayDyn<f64>>)> {
+        check_shapes(&[x.shape(), y.shape()])?;
+        let input_shape = x.shape();
+
+        let x = x.readonly();
+        let y = y.readonly();
+        let coords: Vec<(f64, f64)> = x
+            .as_slice()?
+            .iter()
+            .zip(y.as_slice()?)
+            .map(|(&x, &y)| (x, y))
+            .collect();
+
+        let (lon, lat): (Vec<f64>, Vec<f64>) = self
+            .transform
+            .apply(&coords, num_threads as usize)
+            .map_err(to_pyerr)?
+            .into_iter()
+            .unzip();
+
+        Ok((
+            PyArray1::from_vec(py, lon).reshape(input_shape)?,
+            PyArray1::from_vec(py, lat).reshape(input_shape)?,
+        ))
+    }
+
+    #[allow(clippy::type_complexity)]
+    #[pyo3(signature = (x, y, z, *, num_threads=0))]
+    fn apply_cartesian<'py>(
+        &self,
+        py: Python<'py>,
+        x: &Bound<'py, PyArrayDyn<f64>>,
+        y: &Bound<'py, PyArrayDyn<f64>>,
+        z: &Bound<'py, PyArrayDyn<f64>>,
+        num_threads: u16,
+    ) -> PyResult<(
+        Bound<'py, PyArrayDyn<f64>>,
+        Bound<'py, PyArrayDyn<f64>>,
+        Bound<'py, PyArrayDyn<f64>>,
+    )> {
+        check_shapes(&[x.shape(), y.shape(), z.shape()])?;
+        let input_shape = x.shape();
+
+        let x = x.readonly();
+        let y = y.readonly();
+        let z = z.readonly();
+        let coords: Vec<(f64, f64, f64)> = x
+            .as_slice()?
+            .iter()
+            .zip(y.as_slice()?)
+            .zip(z.as_slice()?)
+            .map(|((&x, &y), &z)| (x, y, z))
+            .collect();
+
+        let transformed = self
+            .transform
+            .apply_cartesian(&coords, num_threads as usize)
+            .map_err(to_pyerr)?;
+        let x: Vec<f64> = transformed.iter().map(|coord| coord.0).collect();
+        let y: Vec<f64> = transformed.iter().map(|coord| coord.1).collect();
+        let z: Vec<f64> = transformed.iter().map(|coord| coord.2).collect();
+
+        Ok((
+            PyArray1::from_vec(py, x).reshape(input_shape)?,
+            PyArray1::from_vec(py, y).reshape(input_shape)?,
+            PyArray1::from_vec(py, z).reshape(input_shape)?,
+        ))
+    }
+
+    fn __repr__(&self) -> String {
+        format!("InputTransform({:?})", self.transform.definition())
+    }
+}
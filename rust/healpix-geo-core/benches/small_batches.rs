@@ -0,0 +1,72 @@
+//! Throughput of many small batches, as produced by chunked array libraries
+//!
+//! Compares the shared thread pools against building a new pool per batch.
+//! Run with `cargo bench --bench small_batches`.
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use cdshealpix as healpix;
+use healpix_geo_core::ellipsoid::Ellipsoid;
+use healpix_geo_core::scalar::nested::coordinates as scalar;
+use healpix_geo_core::vectorized::nested::coordinates as vectorized;
+use rayon::prelude::*;
+
+const BATCHES: usize = 2000;
+const NTHREADS: usize = 4;
+
+fn measure(label: &str, batch_size: usize, mut run: impl FnMut()) -> Duration {
+    let start = Instant::now();
+    for _ in 0..BATCHES {
+        run();
+    }
+    let elapsed = start.elapsed();
+
+    let throughput = (BATCHES * batch_size) as f64 / elapsed.as_secs_f64();
+    println!("{label:>12}: {elapsed:>12.3?} ({throughput:.3e} points/s)");
+
+    elapsed
+}
+
+fn main() {
+    let layer = healpix::nested::get(10);
+    let ellipsoid = Ellipsoid::named("WGS84").unwrap();
+
+    for batch_size in [10, 100, 1000] {
+        let lon: Vec<f64> = (0..batch_size)
+            .map(|i| i as f64 * 360.0 / batch_size as f64)
+            .collect();
+        let lat: Vec<f64> = (0..batch_size)
+            .map(|i| i as f64 * 180.0 / batch_size as f64 - 90.0)
+            .collect();
+        let mut ipix = vec![0u64; batch_size];
+
+        println!("{BATCHES} batches of {batch_size} points, {NTHREADS} threads");
+
+        let fresh = measure("fresh pool", batch_size, || {
+            // the behaviour before pools were shared
+            let pool = rayon::ThreadPoolBuilder::new()
+                .num_threads(NTHREADS)
+                .build()
+                .unwrap();
+            pool.install(|| {
+                (lon.par_iter(), lat.par_iter(), ipix.par_iter_mut())
+                    .into_par_iter()
+                    .for_each(|(lon, lat, hash)| {
+                        *hash = scalar::lonlat_to_healpix(lon, lat, layer, &ellipsoid)
+                    })
+            });
+            black_box(&ipix);
+        });
+        let shared = measure("shared pool", batch_size, || {
+            vectorized::lonlat_to_healpix_into(&lon, &lat, layer, &ellipsoid, &mut ipix, NTHREADS);
+            black_box(&ipix);
+        });
+
+        println!(
+            "{:>12}: {:.1}x\n",
+            "speedup",
+            fresh.as_secs_f64() / shared.as_secs_f64()
+        );
+    }
+}
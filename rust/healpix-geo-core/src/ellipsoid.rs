@@ -7,34 +7,158 @@ use serde::{de, ser};
 use std::collections::HashMap;
 use std::fmt;
 
+mod authalic;
+mod parameters;
+mod registry;
+mod triaxial;
+
+pub use self::authalic::AuthalicMethod;
+pub use self::parameters::ParameterError;
+pub use self::registry::{NamedEllipsoid, lookup, lookup_epsg, lookup_epsg_str, registry};
+pub use self::triaxial::ReferenceTriaxial;
+
+/// The auxiliary latitudes a geographic latitude can be converted to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LatitudeKind {
+    Geographic,
+    Geocentric,
+    /// Also known as the reduced latitude
+    Parametric,
+    Conformal,
+    Rectifying,
+    Authalic,
+}
+
 pub trait ReferenceBody {
     fn latitude_authalic_to_geographic(&self, latitude: f64) -> f64;
     fn latitude_geographic_to_authalic(&self, latitude: f64) -> f64;
 
+    fn latitude_geocentric_to_geographic(&self, latitude: f64) -> f64;
+    fn latitude_geographic_to_geocentric(&self, latitude: f64) -> f64;
+
+    fn latitude_parametric_to_geographic(&self, latitude: f64) -> f64;
+    fn latitude_geographic_to_parametric(&self, latitude: f64) -> f64;
+
+    fn latitude_conformal_to_geographic(&self, latitude: f64) -> f64;
+    fn latitude_geographic_to_conformal(&self, latitude: f64) -> f64;
+
+    fn latitude_rectifying_to_geographic(&self, latitude: f64) -> f64;
+    fn latitude_geographic_to_rectifying(&self, latitude: f64) -> f64;
+
+    /// Convert a latitude in radians between any two kinds of latitudes
+    ///
+    /// Conversions go through the geographic latitude.
+    fn convert_latitude(&self, latitude: f64, from: LatitudeKind, to: LatitudeKind) -> f64 {
+        if from == to {
+            return latitude;
+        }
+
+        let geographic = match from {
+            LatitudeKind::Geographic => latitude,
+            LatitudeKind::Geocentric => self.latitude_geocentric_to_geographic(latitude),
+            LatitudeKind::Parametric => self.latitude_parametric_to_geographic(latitude),
+            LatitudeKind::Conformal => self.latitude_conformal_to_geographic(latitude),
+            LatitudeKind::Rectifying => self.latitude_rectifying_to_geographic(latitude),
+            LatitudeKind::Authalic => self.latitude_authalic_to_geographic(latitude),
+        };
+
+        match to {
+            LatitudeKind::Geographic => geographic,
+            LatitudeKind::Geocentric => self.latitude_geographic_to_geocentric(geographic),
+            LatitudeKind::Parametric => self.latitude_geographic_to_parametric(geographic),
+            LatitudeKind::Conformal => self.latitude_geographic_to_conformal(geographic),
+            LatitudeKind::Rectifying => self.latitude_geographic_to_rectifying(geographic),
+            LatitudeKind::Authalic => self.latitude_geographic_to_authalic(geographic),
+        }
+    }
+
+    /// Map geographic longitude and latitude in radians to the authalic sphere
+    ///
+    /// Bodies of revolution keep the longitude and only convert the latitude.
+    fn geographic_to_authalic(&self, point: &(f64, f64)) -> (f64, f64) {
+        (point.0, self.latitude_geographic_to_authalic(point.1))
+    }
+
+    /// Map authalic longitude and latitude in radians back to the body
+    fn authalic_to_geographic(&self, point: &(f64, f64)) -> (f64, f64) {
+        (point.0, self.latitude_authalic_to_geographic(point.1))
+    }
+
     fn to_mapping(&self) -> HashMap<String, f64>;
-    fn from_mapping(mapping: &HashMap<String, f64>) -> Self;
+    /// Create the body from its parameters
+    ///
+    /// Ellipsoids accept the semimajor axis (or `semi_major_axis`) together
+    /// with one of the flattening, the inverse flattening, the semiminor axis
+    /// (or `semi_minor_axis`) or the eccentricity. Spheres accept the radius.
+    /// Triaxial ellipsoids accept the semimajor, semimedian (or
+    /// `semi_median_axis`) and semiminor axes.
+    fn from_mapping(mapping: &HashMap<String, f64>) -> Result<Self, ParameterError>
+    where
+        Self: Sized;
 
+    /// The underlying ellipsoid of revolution
+    ///
+    /// For triaxial bodies this is the ellipsoid of revolution through the
+    /// prime meridian, so anything built on it is approximate.
     fn ellipsoid(&self) -> &GeodesyEllipsoid;
 
     fn geographic_to_cartesian(&self, point: &(f64, f64)) -> (f64, f64, f64) {
-        let p = Coor3D::raw(point.0, point.1, 0.0);
-        self.ellipsoid().cartesian(&p).xyz()
+        self.geographic_height_to_cartesian(&(point.0, point.1, 0.0))
     }
 
     fn cartesian_to_geographic(&self, point: &(f64, f64, f64)) -> (f64, f64) {
+        let (lon, lat, _) = self.cartesian_to_geographic_height(point);
+
+        (lon, lat)
+    }
+
+    /// Cartesian coordinates of a point given as longitude, latitude and
+    /// ellipsoidal height
+    fn geographic_height_to_cartesian(&self, point: &(f64, f64, f64)) -> (f64, f64, f64) {
         let p = Coor3D::raw(point.0, point.1, point.2);
-        self.ellipsoid().geographic(&p).xy()
+        self.ellipsoid().cartesian(&p).xyz()
+    }
+
+    /// Longitude, latitude and ellipsoidal height of a cartesian point
+    fn cartesian_to_geographic_height(&self, point: &(f64, f64, f64)) -> (f64, f64, f64) {
+        let p = Coor3D::raw(point.0, point.1, point.2);
+        self.ellipsoid().geographic(&p).xyz()
     }
 }
 
 #[derive(Clone, Debug)]
 pub struct ReferenceSphere {
     ellipsoid: GeodesyEllipsoid,
+    name: Option<String>,
+    body: Option<String>,
 }
 
 impl ReferenceSphere {
     pub fn new(ellipsoid: GeodesyEllipsoid) -> Self {
-        Self { ellipsoid }
+        Self {
+            ellipsoid,
+            name: None,
+            body: None,
+        }
+    }
+
+    pub fn with_name(mut self, name: &str) -> Self {
+        self.name = Some(name.to_string());
+        self
+    }
+
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    /// Mark the celestial body described by the reference body
+    pub fn with_body(mut self, body: &str) -> Self {
+        self.body = Some(body.to_string());
+        self
+    }
+
+    pub fn body(&self) -> Option<&str> {
+        self.body.as_deref()
     }
 }
 
@@ -47,6 +171,38 @@ impl ReferenceBody for ReferenceSphere {
         latitude
     }
 
+    fn latitude_geocentric_to_geographic(&self, latitude: f64) -> f64 {
+        latitude
+    }
+
+    fn latitude_geographic_to_geocentric(&self, latitude: f64) -> f64 {
+        latitude
+    }
+
+    fn latitude_parametric_to_geographic(&self, latitude: f64) -> f64 {
+        latitude
+    }
+
+    fn latitude_geographic_to_parametric(&self, latitude: f64) -> f64 {
+        latitude
+    }
+
+    fn latitude_conformal_to_geographic(&self, latitude: f64) -> f64 {
+        latitude
+    }
+
+    fn latitude_geographic_to_conformal(&self, latitude: f64) -> f64 {
+        latitude
+    }
+
+    fn latitude_rectifying_to_geographic(&self, latitude: f64) -> f64 {
+        latitude
+    }
+
+    fn latitude_geographic_to_rectifying(&self, latitude: f64) -> f64 {
+        latitude
+    }
+
     fn ellipsoid(&self) -> &GeodesyEllipsoid {
         &self.ellipsoid
     }
@@ -58,12 +214,10 @@ impl ReferenceBody for ReferenceSphere {
         mapping
     }
 
-    fn from_mapping(mapping: &HashMap<String, f64>) -> Self {
-        let radius = mapping.get("radius").unwrap();
+    fn from_mapping(mapping: &HashMap<String, f64>) -> Result<Self, ParameterError> {
+        let radius = parameters::radius(mapping)?;
 
-        let ellipsoid = GeodesyEllipsoid::new(*radius, 0.0);
-
-        Self { ellipsoid }
+        Ok(Self::new(GeodesyEllipsoid::new(radius, 0.0)))
     }
 }
 
@@ -72,11 +226,18 @@ impl ser::Serialize for ReferenceSphere {
     where
         S: ser::Serializer,
     {
-        let mut state = serializer.serialize_struct("ReferenceSphere", 2)?;
+        let n_fields = 1 + self.name.is_some() as usize + self.body.is_some() as usize;
+        let mut state = serializer.serialize_struct("ReferenceSphere", n_fields)?;
 
         let radius = self.ellipsoid.semimajor_axis();
 
         state.serialize_field("radius", &radius)?;
+        if let Some(name) = &self.name {
+            state.serialize_field("name", name)?;
+        }
+        if let Some(body) = &self.body {
+            state.serialize_field("body", body)?;
+        }
 
         state.end()
     }
@@ -92,6 +253,10 @@ impl<'de> de::Deserialize<'de> for ReferenceSphere {
         enum Field {
             #[serde(rename = "radius")]
             Radius,
+            #[serde(rename = "name")]
+            Name,
+            #[serde(rename = "body")]
+            Body,
         }
 
         struct ReferenceSphereVisitor;
@@ -108,6 +273,8 @@ impl<'de> de::Deserialize<'de> for ReferenceSphere {
                 V: de::MapAccess<'de>,
             {
                 let mut radius = None;
+                let mut name: Option<String> = None;
+                let mut body: Option<String> = None;
 
                 while let Some(key) = map.next_key()? {
                     match key {
@@ -118,17 +285,35 @@ impl<'de> de::Deserialize<'de> for ReferenceSphere {
 
                             radius = Some(map.next_value()?);
                         }
+                        Field::Name => {
+                            if name.is_some() {
+                                return Err(de::Error::duplicate_field("name"));
+                            }
+
+                            name = Some(map.next_value()?);
+                        }
+                        Field::Body => {
+                            if body.is_some() {
+                                return Err(de::Error::duplicate_field("body"));
+                            }
+
+                            body = Some(map.next_value()?);
+                        }
                     }
                 }
 
                 let radius = radius.ok_or_else(|| de::Error::missing_field("radius"))?;
 
                 let ellipsoid = GeodesyEllipsoid::new(radius, 0.0);
-                Ok(ReferenceSphere::new(ellipsoid))
+                Ok(ReferenceSphere {
+                    ellipsoid,
+                    name,
+                    body,
+                })
             }
         }
 
-        const FIELDS: &[&str] = &["radius"];
+        const FIELDS: &[&str] = &["radius", "name", "body"];
         deserializer.deserialize_struct("ReferenceSphere", FIELDS, ReferenceSphereVisitor)
     }
 }
@@ -142,29 +327,114 @@ impl PartialEq for ReferenceSphere {
 #[derive(Clone, Debug)]
 pub struct ReferenceEllipsoid {
     ellipsoid: GeodesyEllipsoid,
-    coefficients: FourierCoefficients,
+    authalic_method: AuthalicMethod,
+    authalic_coefficients: FourierCoefficients,
+    conformal_coefficients: FourierCoefficients,
+    rectifying_coefficients: FourierCoefficients,
+    name: Option<String>,
+    body: Option<String>,
 }
 
 impl ReferenceEllipsoid {
     pub fn new(ellipsoid: GeodesyEllipsoid) -> Self {
-        let coefficients = ellipsoid.coefficients_for_authalic_latitude_computations();
+        Self::with_authalic_method(ellipsoid, AuthalicMethod::default())
+    }
 
+    /// Create the ellipsoid, selecting how authalic latitudes are computed
+    pub fn with_authalic_method(ellipsoid: GeodesyEllipsoid, method: AuthalicMethod) -> Self {
         Self {
             ellipsoid,
-            coefficients,
+            authalic_method: method,
+            authalic_coefficients: method
+                .truncate(ellipsoid.coefficients_for_authalic_latitude_computations()),
+            conformal_coefficients: ellipsoid.coefficients_for_conformal_latitude_computations(),
+            rectifying_coefficients: ellipsoid.coefficients_for_rectifying_latitude_computations(),
+            name: None,
+            body: None,
         }
     }
+
+    pub fn with_name(mut self, name: &str) -> Self {
+        self.name = Some(name.to_string());
+        self
+    }
+
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    /// Mark the celestial body described by the reference body
+    pub fn with_body(mut self, body: &str) -> Self {
+        self.body = Some(body.to_string());
+        self
+    }
+
+    pub fn body(&self) -> Option<&str> {
+        self.body.as_deref()
+    }
+
+    pub fn authalic_method(&self) -> AuthalicMethod {
+        self.authalic_method
+    }
 }
 
 impl ReferenceBody for ReferenceEllipsoid {
     fn latitude_authalic_to_geographic(&self, latitude: f64) -> f64 {
-        self.ellipsoid
-            .latitude_authalic_to_geographic(latitude, &self.coefficients)
+        match self.authalic_method {
+            AuthalicMethod::Series(_) => self
+                .ellipsoid
+                .latitude_authalic_to_geographic(latitude, &self.authalic_coefficients),
+            AuthalicMethod::Exact => {
+                authalic::authalic_to_geographic(self.ellipsoid.eccentricity_squared(), latitude)
+            }
+        }
     }
 
     fn latitude_geographic_to_authalic(&self, latitude: f64) -> f64 {
+        match self.authalic_method {
+            AuthalicMethod::Series(_) => self
+                .ellipsoid
+                .latitude_geographic_to_authalic(latitude, &self.authalic_coefficients),
+            AuthalicMethod::Exact => {
+                authalic::geographic_to_authalic(self.ellipsoid.eccentricity_squared(), latitude)
+            }
+        }
+    }
+
+    fn latitude_geocentric_to_geographic(&self, latitude: f64) -> f64 {
+        self.ellipsoid.latitude_geocentric_to_geographic(latitude)
+    }
+
+    fn latitude_geographic_to_geocentric(&self, latitude: f64) -> f64 {
+        self.ellipsoid.latitude_geographic_to_geocentric(latitude)
+    }
+
+    fn latitude_parametric_to_geographic(&self, latitude: f64) -> f64 {
+        self.ellipsoid.latitude_reduced_to_geographic(latitude)
+    }
+
+    fn latitude_geographic_to_parametric(&self, latitude: f64) -> f64 {
+        self.ellipsoid.latitude_geographic_to_reduced(latitude)
+    }
+
+    fn latitude_conformal_to_geographic(&self, latitude: f64) -> f64 {
+        self.ellipsoid
+            .latitude_conformal_to_geographic(latitude, &self.conformal_coefficients)
+    }
+
+    fn latitude_geographic_to_conformal(&self, latitude: f64) -> f64 {
+        self.ellipsoid
+            .latitude_geographic_to_conformal(latitude, &self.conformal_coefficients)
+    }
+
+    fn latitude_rectifying_to_geographic(&self, latitude: f64) -> f64 {
+        self.ellipsoid
+            .latitude_rectifying_to_geographic(latitude, &self.rectifying_coefficients)
+    }
+
+    fn latitude_geographic_to_rectifying(&self, latitude: f64) -> f64 {
         self.ellipsoid
-            .latitude_geographic_to_authalic(latitude, &self.coefficients)
+            .latitude_geographic_to_rectifying(latitude, &self.rectifying_coefficients)
     }
 
     fn ellipsoid(&self) -> &GeodesyEllipsoid {
@@ -182,23 +452,16 @@ impl ReferenceBody for ReferenceEllipsoid {
         mapping
     }
 
-    fn from_mapping(mapping: &HashMap<String, f64>) -> Self {
-        let semimajor_axis = mapping.get("semimajor_axis").unwrap();
-        let flattening = mapping.get("flattening").unwrap();
+    fn from_mapping(mapping: &HashMap<String, f64>) -> Result<Self, ParameterError> {
+        let (semimajor_axis, flattening) = parameters::semimajor_axis_and_flattening(mapping)?;
 
-        let ellipsoid = GeodesyEllipsoid::new(*semimajor_axis, *flattening);
-        let coefficients = ellipsoid.coefficients_for_authalic_latitude_computations();
-
-        Self {
-            ellipsoid,
-            coefficients,
-        }
+        Ok(Self::new(GeodesyEllipsoid::new(semimajor_axis, flattening)))
     }
 }
 
 impl PartialEq for ReferenceEllipsoid {
     fn eq(&self, other: &Self) -> bool {
-        self.ellipsoid == other.ellipsoid
+        self.ellipsoid == other.ellipsoid && self.authalic_method == other.authalic_method
     }
 }
 
@@ -207,13 +470,27 @@ impl ser::Serialize for ReferenceEllipsoid {
     where
         S: ser::Serializer,
     {
-        let mut state = serializer.serialize_struct("ReferenceEllipsoid", 2)?;
+        let custom_method = self.authalic_method != AuthalicMethod::default();
+        let n_fields = 2
+            + custom_method as usize
+            + self.name.is_some() as usize
+            + self.body.is_some() as usize;
+        let mut state = serializer.serialize_struct("ReferenceEllipsoid", n_fields)?;
 
         let semimajor_axis = self.ellipsoid.semimajor_axis();
         let flattening = self.ellipsoid.flattening();
 
         state.serialize_field("semimajor_axis", &semimajor_axis)?;
         state.serialize_field("flattening", &flattening)?;
+        if custom_method {
+            state.serialize_field("authalic_method", &self.authalic_method)?;
+        }
+        if let Some(name) = &self.name {
+            state.serialize_field("name", name)?;
+        }
+        if let Some(body) = &self.body {
+            state.serialize_field("body", body)?;
+        }
 
         state.end()
     }
@@ -231,6 +508,12 @@ impl<'de> de::Deserialize<'de> for ReferenceEllipsoid {
             SemimajorAxis,
             #[serde(rename = "flattening")]
             Flattening,
+            #[serde(rename = "authalic_method")]
+            AuthalicMethod,
+            #[serde(rename = "name")]
+            Name,
+            #[serde(rename = "body")]
+            Body,
         }
 
         struct ReferenceEllipsoidVisitor;
@@ -248,6 +531,9 @@ impl<'de> de::Deserialize<'de> for ReferenceEllipsoid {
             {
                 let mut semimajor_axis = None;
                 let mut flattening = None;
+                let mut authalic_method: Option<AuthalicMethod> = None;
+                let mut name: Option<String> = None;
+                let mut body: Option<String> = None;
 
                 while let Some(key) = map.next_key()? {
                     match key {
@@ -265,6 +551,27 @@ impl<'de> de::Deserialize<'de> for ReferenceEllipsoid {
 
                             flattening = Some(map.next_value()?);
                         }
+                        Field::AuthalicMethod => {
+                            if authalic_method.is_some() {
+                                return Err(de::Error::duplicate_field("authalic_method"));
+                            }
+
+                            authalic_method = Some(map.next_value()?);
+                        }
+                        Field::Name => {
+                            if name.is_some() {
+                                return Err(de::Error::duplicate_field("name"));
+                            }
+
+                            name = Some(map.next_value()?);
+                        }
+                        Field::Body => {
+                            if body.is_some() {
+                                return Err(de::Error::duplicate_field("body"));
+                            }
+
+                            body = Some(map.next_value()?);
+                        }
                     }
                 }
 
@@ -273,12 +580,30 @@ impl<'de> de::Deserialize<'de> for ReferenceEllipsoid {
                 let flattening =
                     flattening.ok_or_else(|| de::Error::missing_field("flattening"))?;
 
+                let authalic_method = authalic_method.unwrap_or_default();
+                if !authalic_method.is_valid() {
+                    return Err(de::Error::custom(
+                        "the series order must be between 1 and 6",
+                    ));
+                }
+
                 let ellipsoid = GeodesyEllipsoid::new(semimajor_axis, flattening);
-                Ok(ReferenceEllipsoid::new(ellipsoid))
+                let mut reference =
+                    ReferenceEllipsoid::with_authalic_method(ellipsoid, authalic_method);
+                reference.name = name;
+                reference.body = body;
+
+                Ok(reference)
             }
         }
 
-        const FIELDS: &[&str] = &["semimajor_axis", "flattening"];
+        const FIELDS: &[&str] = &[
+            "semimajor_axis",
+            "flattening",
+            "authalic_method",
+            "name",
+            "body",
+        ];
         deserializer.deserialize_struct("ReferenceEllipsoid", FIELDS, ReferenceEllipsoidVisitor)
     }
 }
@@ -287,6 +612,7 @@ impl<'de> de::Deserialize<'de> for ReferenceEllipsoid {
 pub enum Ellipsoid {
     Ellipsoid(ReferenceEllipsoid),
     Sphere(ReferenceSphere),
+    Triaxial(ReferenceTriaxial),
 }
 
 impl ReferenceBody for Ellipsoid {
@@ -294,6 +620,7 @@ impl ReferenceBody for Ellipsoid {
         match self {
             Self::Ellipsoid(wrapped) => wrapped.latitude_authalic_to_geographic(latitude),
             Self::Sphere(wrapped) => wrapped.latitude_authalic_to_geographic(latitude),
+            Self::Triaxial(wrapped) => wrapped.latitude_authalic_to_geographic(latitude),
         }
     }
 
@@ -301,6 +628,71 @@ impl ReferenceBody for Ellipsoid {
         match self {
             Self::Ellipsoid(wrapped) => wrapped.latitude_geographic_to_authalic(latitude),
             Self::Sphere(wrapped) => wrapped.latitude_geographic_to_authalic(latitude),
+            Self::Triaxial(wrapped) => wrapped.latitude_geographic_to_authalic(latitude),
+        }
+    }
+
+    fn latitude_geocentric_to_geographic(&self, latitude: f64) -> f64 {
+        match self {
+            Self::Ellipsoid(wrapped) => wrapped.latitude_geocentric_to_geographic(latitude),
+            Self::Sphere(wrapped) => wrapped.latitude_geocentric_to_geographic(latitude),
+            Self::Triaxial(wrapped) => wrapped.latitude_geocentric_to_geographic(latitude),
+        }
+    }
+
+    fn latitude_geographic_to_geocentric(&self, latitude: f64) -> f64 {
+        match self {
+            Self::Ellipsoid(wrapped) => wrapped.latitude_geographic_to_geocentric(latitude),
+            Self::Sphere(wrapped) => wrapped.latitude_geographic_to_geocentric(latitude),
+            Self::Triaxial(wrapped) => wrapped.latitude_geographic_to_geocentric(latitude),
+        }
+    }
+
+    fn latitude_parametric_to_geographic(&self, latitude: f64) -> f64 {
+        match self {
+            Self::Ellipsoid(wrapped) => wrapped.latitude_parametric_to_geographic(latitude),
+            Self::Sphere(wrapped) => wrapped.latitude_parametric_to_geographic(latitude),
+            Self::Triaxial(wrapped) => wrapped.latitude_parametric_to_geographic(latitude),
+        }
+    }
+
+    fn latitude_geographic_to_parametric(&self, latitude: f64) -> f64 {
+        match self {
+            Self::Ellipsoid(wrapped) => wrapped.latitude_geographic_to_parametric(latitude),
+            Self::Sphere(wrapped) => wrapped.latitude_geographic_to_parametric(latitude),
+            Self::Triaxial(wrapped) => wrapped.latitude_geographic_to_parametric(latitude),
+        }
+    }
+
+    fn latitude_conformal_to_geographic(&self, latitude: f64) -> f64 {
+        match self {
+            Self::Ellipsoid(wrapped) => wrapped.latitude_conformal_to_geographic(latitude),
+            Self::Sphere(wrapped) => wrapped.latitude_conformal_to_geographic(latitude),
+            Self::Triaxial(wrapped) => wrapped.latitude_conformal_to_geographic(latitude),
+        }
+    }
+
+    fn latitude_geographic_to_conformal(&self, latitude: f64) -> f64 {
+        match self {
+            Self::Ellipsoid(wrapped) => wrapped.latitude_geographic_to_conformal(latitude),
+            Self::Sphere(wrapped) => wrapped.latitude_geographic_to_conformal(latitude),
+            Self::Triaxial(wrapped) => wrapped.latitude_geographic_to_conformal(latitude),
+        }
+    }
+
+    fn latitude_rectifying_to_geographic(&self, latitude: f64) -> f64 {
+        match self {
+            Self::Ellipsoid(wrapped) => wrapped.latitude_rectifying_to_geographic(latitude),
+            Self::Sphere(wrapped) => wrapped.latitude_rectifying_to_geographic(latitude),
+            Self::Triaxial(wrapped) => wrapped.latitude_rectifying_to_geographic(latitude),
+        }
+    }
+
+    fn latitude_geographic_to_rectifying(&self, latitude: f64) -> f64 {
+        match self {
+            Self::Ellipsoid(wrapped) => wrapped.latitude_geographic_to_rectifying(latitude),
+            Self::Sphere(wrapped) => wrapped.latitude_geographic_to_rectifying(latitude),
+            Self::Triaxial(wrapped) => wrapped.latitude_geographic_to_rectifying(latitude),
         }
     }
 
@@ -308,6 +700,7 @@ impl ReferenceBody for Ellipsoid {
         match self {
             Self::Ellipsoid(wrapped) => wrapped.ellipsoid(),
             Self::Sphere(wrapped) => wrapped.ellipsoid(),
+            Self::Triaxial(wrapped) => wrapped.ellipsoid(),
         }
     }
 
@@ -315,23 +708,186 @@ impl ReferenceBody for Ellipsoid {
         match self {
             Self::Ellipsoid(wrapped) => wrapped.to_mapping(),
             Self::Sphere(wrapped) => wrapped.to_mapping(),
+            Self::Triaxial(wrapped) => wrapped.to_mapping(),
         }
     }
 
-    fn from_mapping(mapping: &HashMap<String, f64>) -> Self {
-        if mapping.contains_key("flattening") {
-            Self::Ellipsoid(ReferenceEllipsoid::from_mapping(mapping))
+    fn from_mapping(mapping: &HashMap<String, f64>) -> Result<Self, ParameterError> {
+        let is_sphere = parameters::contains_any(mapping, parameters::RADIUS);
+        let is_ellipsoid = parameters::contains_any(mapping, parameters::SEMIMAJOR_AXIS);
+
+        match (is_sphere, is_ellipsoid) {
+            (true, true) => Err(ParameterError::Conflicting(
+                [parameters::RADIUS, parameters::SEMIMAJOR_AXIS].concat(),
+            )),
+            (true, false) => ReferenceSphere::from_mapping(mapping).map(Self::Sphere),
+            (false, _) if parameters::contains_any(mapping, parameters::SEMIMEDIAN_AXIS) => {
+                ReferenceTriaxial::from_mapping(mapping).map(Self::Triaxial)
+            }
+            (false, _) => ReferenceEllipsoid::from_mapping(mapping).map(Self::Ellipsoid),
+        }
+    }
+
+    fn geographic_to_authalic(&self, point: &(f64, f64)) -> (f64, f64) {
+        match self {
+            Self::Triaxial(wrapped) => wrapped.geographic_to_authalic(point),
+            _ => (point.0, self.latitude_geographic_to_authalic(point.1)),
+        }
+    }
+
+    fn authalic_to_geographic(&self, point: &(f64, f64)) -> (f64, f64) {
+        match self {
+            Self::Triaxial(wrapped) => wrapped.authalic_to_geographic(point),
+            _ => (point.0, self.latitude_authalic_to_geographic(point.1)),
+        }
+    }
+
+    fn geographic_height_to_cartesian(&self, point: &(f64, f64, f64)) -> (f64, f64, f64) {
+        match self {
+            Self::Triaxial(wrapped) => wrapped.geographic_height_to_cartesian(point),
+            _ => {
+                let p = Coor3D::raw(point.0, point.1, point.2);
+                self.ellipsoid().cartesian(&p).xyz()
+            }
+        }
+    }
+
+    fn cartesian_to_geographic_height(&self, point: &(f64, f64, f64)) -> (f64, f64, f64) {
+        match self {
+            Self::Triaxial(wrapped) => wrapped.cartesian_to_geographic_height(point),
+            _ => {
+                let p = Coor3D::raw(point.0, point.1, point.2);
+                self.ellipsoid().geographic(&p).xyz()
+            }
+        }
+    }
+}
+
+impl Ellipsoid {
+    /// Create a registered body by name or alias
+    pub fn named(name: &str) -> Option<Self> {
+        lookup(name).map(NamedEllipsoid::to_ellipsoid)
+    }
+
+    /// Create a registered body by EPSG code
+    pub fn from_epsg(code: u32) -> Option<Self> {
+        lookup_epsg(code).map(NamedEllipsoid::to_ellipsoid)
+    }
+
+    /// The canonical name of the body, if it was created from the registry
+    pub fn name(&self) -> Option<&str> {
+        match self {
+            Self::Ellipsoid(wrapped) => wrapped.name(),
+            Self::Sphere(wrapped) => wrapped.name(),
+            Self::Triaxial(wrapped) => wrapped.name(),
+        }
+    }
+
+    /// The celestial body described, if known
+    pub fn body(&self) -> Option<&str> {
+        match self {
+            Self::Ellipsoid(wrapped) => wrapped.body(),
+            Self::Sphere(wrapped) => wrapped.body(),
+            Self::Triaxial(wrapped) => wrapped.body(),
+        }
+    }
+
+    /// Whether both describe the same celestial body
+    ///
+    /// Bodies that are not known are assumed to match any other body.
+    pub fn same_body(&self, other: &Self) -> bool {
+        match (self.body(), other.body()) {
+            (Some(first), Some(second)) => first.eq_ignore_ascii_case(second),
+            _ => true,
+        }
+    }
+
+    /// Whether the body is a triaxial ellipsoid
+    pub fn is_triaxial(&self) -> bool {
+        matches!(self, Self::Triaxial(_))
+    }
+
+    /// The longitudes, in radians, at which the authalic mapping is sampled
+    ///
+    /// Bodies of revolution map every meridian the same way. Triaxial bodies
+    /// are symmetric with respect to the quarter meridians, so the first
+    /// quarter is sampled in steps of 5°.
+    fn sampled_longitudes(bodies: &[&Self]) -> Vec<f64> {
+        if bodies.iter().any(|body| body.is_triaxial()) {
+            (0..=18)
+                .map(|step| (step as f64 * 5.0).to_radians())
+                .collect()
         } else {
-            Self::Sphere(ReferenceSphere::from_mapping(mapping))
+            vec![0.0]
         }
     }
+
+    /// The EPSG code of the body, if it is a named body with a code
+    pub fn epsg(&self) -> Option<u32> {
+        self.name().and_then(lookup).and_then(|entry| entry.epsg)
+    }
+
+    /// The largest distance, in meters, between the positions a geographic
+    /// position maps to on the authalic spheres of both bodies
+    ///
+    /// This is a bound on how far apart the HEALPix cells of both bodies are.
+    /// The latitudes are sampled in steps of 0.1°, and triaxial bodies are
+    /// sampled along several meridians.
+    pub fn authalic_offset(&self, other: &Self) -> f64 {
+        let max_difference = Self::sampled_longitudes(&[self, other])
+            .into_iter()
+            .flat_map(|lon| (0..=900).map(move |step| (lon, (step as f64 / 10.0).to_radians())))
+            .map(|point| {
+                angular_distance(
+                    self.geographic_to_authalic(&point),
+                    other.geographic_to_authalic(&point),
+                )
+            })
+            .fold(0.0, f64::max);
+
+        max_difference * self.ellipsoid().semimajor_axis()
+    }
+
+    /// The largest distance, in meters, between a geographic position and the
+    /// position obtained by converting it to the authalic sphere and back
+    ///
+    /// This describes the accuracy of the mapping between the body and the
+    /// HEALPix grid. The latitudes are sampled in steps of 0.1°, and triaxial
+    /// bodies are sampled along several meridians.
+    pub fn authalic_roundtrip_error(&self) -> f64 {
+        let max_difference = Self::sampled_longitudes(&[self])
+            .into_iter()
+            .flat_map(|lon| (-900..=900).map(move |step| (lon, (step as f64 / 10.0).to_radians())))
+            .map(|point| {
+                let authalic = self.geographic_to_authalic(&point);
+
+                angular_distance(self.authalic_to_geographic(&authalic), point)
+            })
+            .fold(0.0, f64::max);
+
+        max_difference * self.ellipsoid().semimajor_axis()
+    }
+
+    /// Whether the HEALPix cells of both bodies are within `tolerance` meters
+    pub fn is_compatible(&self, other: &Self, tolerance: f64) -> bool {
+        self.same_body(other) && (self == other || self.authalic_offset(other) <= tolerance)
+    }
+}
+
+/// The angle in radians between two positions given as longitude and latitude
+fn angular_distance(first: (f64, f64), second: (f64, f64)) -> f64 {
+    let half_lon = (second.0 - first.0) / 2.0;
+    let half_lat = (second.1 - first.1) / 2.0;
+
+    let haversine =
+        half_lat.sin().powi(2) + first.1.cos() * second.1.cos() * half_lon.sin().powi(2);
+
+    2.0 * haversine.sqrt().min(1.0).asin()
 }
 
 impl Default for Ellipsoid {
     fn default() -> Self {
-        Self::Sphere(ReferenceSphere {
-            ellipsoid: GeodesyEllipsoid::named("sphere").unwrap(),
-        })
+        Self::named("sphere").unwrap()
     }
 }
 
@@ -381,6 +937,197 @@ mod tests {
         assert_eq!(geographic, lat);
     }
 
+    #[test]
+    fn test_from_mapping() {
+        let mapping: HashMap<String, f64> = [
+            ("semi_major_axis".to_string(), 6378137.0),
+            ("semi_minor_axis".to_string(), 6356752.314245179),
+        ]
+        .into_iter()
+        .collect();
+        let actual = Ellipsoid::from_mapping(&mapping).unwrap();
+        let expected = Ellipsoid::named("WGS84").unwrap();
+        assert!(
+            (actual.ellipsoid().flattening() - expected.ellipsoid().flattening()).abs() < 1e-12
+        );
+
+        // roundtrip
+        let actual = Ellipsoid::from_mapping(&expected.to_mapping()).unwrap();
+        assert_eq!(actual, expected);
+
+        let sphere = Ellipsoid::default();
+        assert_eq!(Ellipsoid::from_mapping(&sphere.to_mapping()), Ok(sphere));
+
+        let conflicting: HashMap<String, f64> = [
+            ("radius".to_string(), 1.0),
+            ("semimajor_axis".to_string(), 1.0),
+        ]
+        .into_iter()
+        .collect();
+        assert!(matches!(
+            Ellipsoid::from_mapping(&conflicting),
+            Err(ParameterError::Conflicting(_))
+        ));
+    }
+
+    #[test]
+    fn test_triaxial() {
+        let mapping: HashMap<String, f64> = [
+            ("semimajor_axis".to_string(), 6378171.36),
+            ("semi_median_axis".to_string(), 6378102.0),
+            ("semiminor_axis".to_string(), 6356752.0),
+        ]
+        .into_iter()
+        .collect();
+        let ellipsoid = Ellipsoid::from_mapping(&mapping).unwrap();
+        assert!(matches!(ellipsoid, Ellipsoid::Triaxial(_)));
+
+        let roundtripped: Ellipsoid =
+            serde_json::from_str(&serde_json::to_string(&ellipsoid).unwrap()).unwrap();
+        assert_eq!(roundtripped, ellipsoid);
+
+        // the longitude changes as well
+        let point = (0.5, 0.7);
+        let authalic = ellipsoid.geographic_to_authalic(&point);
+        assert!((authalic.0 - point.0).abs() > 1e-9);
+
+        let (lon, lat) = ellipsoid.authalic_to_geographic(&authalic);
+        assert!((lon - point.0).abs() < 1e-12);
+        assert!((lat - point.1).abs() < 1e-12);
+
+        // the prime meridian agrees with the ellipsoid of revolution, but the
+        // other meridians don't
+        let meridian = Ellipsoid::Ellipsoid(ReferenceEllipsoid::new(*ellipsoid.ellipsoid()));
+        assert!(ellipsoid.authalic_offset(&meridian) > 1.0);
+        assert!(ellipsoid.authalic_roundtrip_error() < 1e-3);
+    }
+
+    #[test]
+    fn test_named() {
+        let ellipsoid = Ellipsoid::named("wgs 84").unwrap();
+
+        assert_eq!(ellipsoid.name(), Some("WGS84"));
+        assert_eq!(ellipsoid.epsg(), Some(7030));
+        assert_eq!(Ellipsoid::from_epsg(7030), Some(ellipsoid));
+        assert_eq!(Ellipsoid::default().name(), Some("sphere"));
+        assert_eq!(Ellipsoid::named("unknown"), None);
+    }
+
+    #[test]
+    fn test_name_serialization() {
+        let named = Ellipsoid::named("GRS80").unwrap();
+        let unnamed = Ellipsoid::Ellipsoid(ReferenceEllipsoid::new(
+            GeodesyEllipsoid::named("GRS80").unwrap(),
+        ));
+
+        let roundtripped: Ellipsoid =
+            serde_json::from_str(&serde_json::to_string(&named).unwrap()).unwrap();
+        assert_eq!(roundtripped.name(), Some("GRS80"));
+
+        // names don't take part in comparisons, and are optional in the serialized form
+        let roundtripped: Ellipsoid =
+            serde_json::from_str(&serde_json::to_string(&unnamed).unwrap()).unwrap();
+        assert_eq!(roundtripped.name(), None);
+        assert_eq!(roundtripped, named);
+
+        let mars = Ellipsoid::named("mars").unwrap();
+        let roundtripped: Ellipsoid =
+            serde_json::from_str(&serde_json::to_string(&mars).unwrap()).unwrap();
+        assert_eq!(roundtripped.body(), Some("Mars"));
+    }
+
+    #[test]
+    fn test_is_compatible() {
+        let wgs84 = Ellipsoid::Ellipsoid(ReferenceEllipsoid::new(
+            GeodesyEllipsoid::named("WGS84").unwrap(),
+        ));
+        let grs80 = Ellipsoid::Ellipsoid(ReferenceEllipsoid::new(
+            GeodesyEllipsoid::named("GRS80").unwrap(),
+        ));
+        let sphere = Ellipsoid::default();
+
+        assert!(wgs84.authalic_offset(&grs80) < 0.01);
+        assert!(wgs84.is_compatible(&grs80, 0.01));
+        assert!(!wgs84.is_compatible(&sphere, 1000.0));
+        assert!(sphere.is_compatible(&sphere, 0.0));
+    }
+
+    #[test]
+    fn test_auxiliary_latitudes() {
+        let ellipsoid = Ellipsoid::named("WGS84").unwrap();
+        let sphere = Ellipsoid::default();
+        let kinds = [
+            LatitudeKind::Geographic,
+            LatitudeKind::Geocentric,
+            LatitudeKind::Parametric,
+            LatitudeKind::Conformal,
+            LatitudeKind::Rectifying,
+            LatitudeKind::Authalic,
+        ];
+        let latitude = 45f64.to_radians();
+
+        for kind in kinds {
+            let converted = ellipsoid.convert_latitude(latitude, LatitudeKind::Geographic, kind);
+            let roundtripped =
+                ellipsoid.convert_latitude(converted, kind, LatitudeKind::Geographic);
+            assert!((roundtripped - latitude).abs() < 1e-10);
+
+            assert_eq!(
+                sphere.convert_latitude(latitude, LatitudeKind::Geographic, kind),
+                latitude
+            );
+        }
+
+        // closed forms: tan(geocentric) = (1 - f)² tan(φ), tan(parametric) = (1 - f) tan(φ)
+        let f = ellipsoid.ellipsoid().flattening();
+        let geocentric = ellipsoid.latitude_geographic_to_geocentric(latitude);
+        assert!((geocentric.tan() - (1.0 - f).powi(2) * latitude.tan()).abs() < 1e-12);
+        let parametric = ellipsoid.latitude_geographic_to_parametric(latitude);
+        assert!((parametric.tan() - (1.0 - f) * latitude.tan()).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_authalic_method() {
+        // a strongly flattened body
+        let geodesy_ellipsoid = GeodesyEllipsoid::new(1.0, 0.3);
+        let exact =
+            ReferenceEllipsoid::with_authalic_method(geodesy_ellipsoid, AuthalicMethod::Exact);
+        let series = ReferenceEllipsoid::new(geodesy_ellipsoid);
+        let truncated =
+            ReferenceEllipsoid::with_authalic_method(geodesy_ellipsoid, AuthalicMethod::Series(2));
+
+        assert_eq!(series.authalic_method(), AuthalicMethod::Series(6));
+        assert_eq!(exact.authalic_method(), AuthalicMethod::Exact);
+
+        let errors = [exact.clone(), series.clone(), truncated]
+            .map(|reference| Ellipsoid::Ellipsoid(reference).authalic_roundtrip_error());
+        assert!(errors[0] < 1e-12);
+        assert!(errors[0] <= errors[1]);
+        assert!(errors[1] < errors[2]);
+
+        // on the earth, the default series is accurate enough
+        let wgs84 = Ellipsoid::named("WGS84").unwrap();
+        assert!(wgs84.authalic_roundtrip_error() < 1e-3);
+
+        let latitude = 0.7;
+        let expected = exact.latitude_geographic_to_authalic(latitude);
+        assert!((series.latitude_geographic_to_authalic(latitude) - expected).abs() < 1e-4);
+
+        // the method takes part in comparisons and survives serialization
+        assert_ne!(exact, series);
+        for reference in [exact, series] {
+            let roundtripped: ReferenceEllipsoid =
+                serde_json::from_str(&serde_json::to_string(&reference).unwrap()).unwrap();
+
+            assert_eq!(roundtripped.authalic_method(), reference.authalic_method());
+            assert_eq!(roundtripped, reference);
+        }
+
+        let invalid =
+            r#"{"semimajor_axis": 1.0, "flattening": 0.3, "authalic_method": {"series": 7}}"#;
+        assert!(serde_json::from_str::<ReferenceEllipsoid>(invalid).is_err());
+    }
+
     #[test]
     fn test_reference_ellipsoid_conversions_bessel() {
         let ellipsoid = ReferenceEllipsoid::new(GeodesyEllipsoid::named("bessel").unwrap());
@@ -0,0 +1,302 @@
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+
+/// Error raised when constructing a reference body from its parameters
+#[derive(Debug, Clone, PartialEq)]
+pub enum ParameterError {
+    /// None of the given names is in the mapping
+    Missing(Vec<&'static str>),
+    /// More than one of the names describing the same quantity is in the mapping
+    Conflicting(Vec<&'static str>),
+    /// The value of a parameter is outside of its valid range
+    OutOfRange {
+        name: &'static str,
+        value: f64,
+        expected: &'static str,
+    },
+}
+
+impl fmt::Display for ParameterError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Missing(names) => {
+                write!(
+                    f,
+                    "Missing parameter, expected one of: {}.",
+                    names.join(", ")
+                )
+            }
+            Self::Conflicting(names) => write!(
+                f,
+                "Conflicting parameters, expected only one of: {}.",
+                names.join(", ")
+            ),
+            Self::OutOfRange {
+                name,
+                value,
+                expected,
+            } => write!(f, "The {name} must be {expected}, but got {value:?}."),
+        }
+    }
+}
+
+impl Error for ParameterError {}
+
+/// Accepted spellings of the semimajor axis
+pub(crate) const SEMIMAJOR_AXIS: &[&str] = &["semimajor_axis", "semi_major_axis"];
+const SEMIMINOR_AXIS: &[&str] = &["semiminor_axis", "semi_minor_axis"];
+/// Accepted spellings of the semimedian axis of triaxial ellipsoids
+pub(crate) const SEMIMEDIAN_AXIS: &[&str] = &["semimedian_axis", "semi_median_axis"];
+const FLATTENING: &[&str] = &["flattening"];
+const INVERSE_FLATTENING: &[&str] = &["inverse_flattening"];
+const ECCENTRICITY: &[&str] = &["eccentricity"];
+pub(crate) const RADIUS: &[&str] = &["radius"];
+
+/// Whether the mapping contains any of the names
+pub(crate) fn contains_any(mapping: &HashMap<String, f64>, names: &[&str]) -> bool {
+    names.iter().any(|name| mapping.contains_key(*name))
+}
+
+/// Look up a parameter that may be spelled in different ways
+///
+/// Returns `None` if none of the spellings is present.
+fn lookup(
+    mapping: &HashMap<String, f64>,
+    names: &[&'static str],
+) -> Result<Option<f64>, ParameterError> {
+    let found: Vec<_> = names
+        .iter()
+        .filter_map(|name| mapping.get(*name).map(|value| (*name, *value)))
+        .collect();
+
+    match found.as_slice() {
+        [] => Ok(None),
+        [(_, value)] => Ok(Some(*value)),
+        _ => Err(ParameterError::Conflicting(
+            found.into_iter().map(|(name, _)| name).collect(),
+        )),
+    }
+}
+
+fn require(mapping: &HashMap<String, f64>, names: &[&'static str]) -> Result<f64, ParameterError> {
+    lookup(mapping, names)?.ok_or_else(|| ParameterError::Missing(names.to_vec()))
+}
+
+fn positive(name: &'static str, value: f64) -> Result<f64, ParameterError> {
+    if value > 0.0 {
+        Ok(value)
+    } else {
+        Err(ParameterError::OutOfRange {
+            name,
+            value,
+            expected: "greater than 0",
+        })
+    }
+}
+
+/// Extract the radius of a sphere
+pub(crate) fn radius(mapping: &HashMap<String, f64>) -> Result<f64, ParameterError> {
+    positive("radius", require(mapping, RADIUS)?)
+}
+
+/// Extract the semimajor axis and flattening of an ellipsoid
+///
+/// The shape can be given by exactly one of the flattening, the inverse
+/// flattening, the semiminor axis or the eccentricity.
+pub(crate) fn semimajor_axis_and_flattening(
+    mapping: &HashMap<String, f64>,
+) -> Result<(f64, f64), ParameterError> {
+    let semimajor_axis = positive("semimajor axis", require(mapping, SEMIMAJOR_AXIS)?)?;
+
+    let shape_names: Vec<&'static str> =
+        [FLATTENING, INVERSE_FLATTENING, SEMIMINOR_AXIS, ECCENTRICITY].concat();
+    let given: Vec<&'static str> = shape_names
+        .iter()
+        .copied()
+        .filter(|name| mapping.contains_key(*name))
+        .collect();
+    if given.len() > 1 {
+        return Err(ParameterError::Conflicting(given));
+    }
+
+    let flattening = if let Some(flattening) = lookup(mapping, FLATTENING)? {
+        if !(0.0..=0.5).contains(&flattening) {
+            return Err(ParameterError::OutOfRange {
+                name: "flattening",
+                value: flattening,
+                expected: "between 0 and 0.5",
+            });
+        }
+
+        flattening
+    } else if let Some(inverse_flattening) = lookup(mapping, INVERSE_FLATTENING)? {
+        if inverse_flattening < 2.0 {
+            return Err(ParameterError::OutOfRange {
+                name: "inverse flattening",
+                value: inverse_flattening,
+                expected: "greater than or equal to 2",
+            });
+        }
+
+        1.0 / inverse_flattening
+    } else if let Some(semiminor_axis) = lookup(mapping, SEMIMINOR_AXIS)? {
+        if !(semimajor_axis / 2.0..=semimajor_axis).contains(&semiminor_axis) {
+            return Err(ParameterError::OutOfRange {
+                name: "semiminor axis",
+                value: semiminor_axis,
+                expected: "between half the semimajor axis and the semimajor axis",
+            });
+        }
+
+        (semimajor_axis - semiminor_axis) / semimajor_axis
+    } else if let Some(eccentricity) = lookup(mapping, ECCENTRICITY)? {
+        // a flattening of 0.5 corresponds to an eccentricity of sqrt(3) / 2
+        if !(0.0..=3f64.sqrt() / 2.0).contains(&eccentricity) {
+            return Err(ParameterError::OutOfRange {
+                name: "eccentricity",
+                value: eccentricity,
+                expected: "between 0 and sqrt(3) / 2",
+            });
+        }
+
+        1.0 - (1.0 - eccentricity.powi(2)).sqrt()
+    } else {
+        return Err(ParameterError::Missing(shape_names));
+    };
+
+    Ok((semimajor_axis, flattening))
+}
+
+/// Extract the three semi-axes of a triaxial ellipsoid
+///
+/// The axes must be sorted by decreasing length.
+pub(crate) fn triaxial_axes(
+    mapping: &HashMap<String, f64>,
+) -> Result<(f64, f64, f64), ParameterError> {
+    let semimajor_axis = positive("semimajor axis", require(mapping, SEMIMAJOR_AXIS)?)?;
+    let semimedian_axis = positive("semimedian axis", require(mapping, SEMIMEDIAN_AXIS)?)?;
+    let semiminor_axis = positive("semiminor axis", require(mapping, SEMIMINOR_AXIS)?)?;
+
+    if semimedian_axis > semimajor_axis {
+        return Err(ParameterError::OutOfRange {
+            name: "semimedian axis",
+            value: semimedian_axis,
+            expected: "smaller than or equal to the semimajor axis",
+        });
+    }
+    if semiminor_axis > semimedian_axis {
+        return Err(ParameterError::OutOfRange {
+            name: "semiminor axis",
+            value: semiminor_axis,
+            expected: "smaller than or equal to the semimedian axis",
+        });
+    }
+
+    Ok((semimajor_axis, semimedian_axis, semiminor_axis))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mapping(items: &[(&str, f64)]) -> HashMap<String, f64> {
+        items
+            .iter()
+            .map(|(name, value)| (name.to_string(), *value))
+            .collect()
+    }
+
+    #[test]
+    fn test_alternatives() {
+        let a = 6378137.0;
+        let f = 1.0 / 298.257223563;
+        let b = a * (1.0 - f);
+        let e = (f * (2.0 - f)).sqrt();
+
+        let cases = [
+            mapping(&[("semimajor_axis", a), ("flattening", f)]),
+            mapping(&[("semi_major_axis", a), ("inverse_flattening", 1.0 / f)]),
+            mapping(&[("semimajor_axis", a), ("semiminor_axis", b)]),
+            mapping(&[("semi_major_axis", a), ("semi_minor_axis", b)]),
+            mapping(&[("semimajor_axis", a), ("eccentricity", e)]),
+        ];
+
+        for case in cases {
+            let (actual_a, actual_f) = semimajor_axis_and_flattening(&case).unwrap();
+
+            assert_eq!(actual_a, a);
+            assert!((actual_f - f).abs() < 1e-15);
+        }
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!(
+            radius(&mapping(&[])),
+            Err(ParameterError::Missing(vec!["radius"]))
+        );
+        assert_eq!(
+            radius(&mapping(&[("radius", -1.0)]))
+                .unwrap_err()
+                .to_string(),
+            "The radius must be greater than 0, but got -1.0."
+        );
+        assert_eq!(
+            semimajor_axis_and_flattening(&mapping(&[
+                ("semimajor_axis", 1.0),
+                ("semi_major_axis", 1.0)
+            ])),
+            Err(ParameterError::Conflicting(vec![
+                "semimajor_axis",
+                "semi_major_axis"
+            ]))
+        );
+        assert_eq!(
+            semimajor_axis_and_flattening(&mapping(&[
+                ("semimajor_axis", 1.0),
+                ("flattening", 0.1),
+                ("inverse_flattening", 10.0)
+            ])),
+            Err(ParameterError::Conflicting(vec![
+                "flattening",
+                "inverse_flattening"
+            ]))
+        );
+        assert_eq!(
+            semimajor_axis_and_flattening(&mapping(&[
+                ("semimajor_axis", 1.0),
+                ("inverse_flattening", 0.5)
+            ]))
+            .unwrap_err()
+            .to_string(),
+            "The inverse flattening must be greater than or equal to 2, but got 0.5."
+        );
+        assert!(matches!(
+            semimajor_axis_and_flattening(&mapping(&[("semimajor_axis", 1.0)])),
+            Err(ParameterError::Missing(_))
+        ));
+    }
+
+    #[test]
+    fn test_triaxial_axes() {
+        assert_eq!(
+            triaxial_axes(&mapping(&[
+                ("semimajor_axis", 3.0),
+                ("semi_median_axis", 2.0),
+                ("semiminor_axis", 1.0)
+            ])),
+            Ok((3.0, 2.0, 1.0))
+        );
+        assert_eq!(
+            triaxial_axes(&mapping(&[
+                ("semimajor_axis", 3.0),
+                ("semimedian_axis", 1.0),
+                ("semiminor_axis", 2.0)
+            ]))
+            .unwrap_err()
+            .to_string(),
+            "The semiminor axis must be smaller than or equal to the semimedian axis, but got 2.0."
+        );
+    }
+}
@@ -0,0 +1,300 @@
+use super::{Ellipsoid, ReferenceEllipsoid, ReferenceSphere};
+use geodesy::ellps::Ellipsoid as GeodesyEllipsoid;
+
+/// A reference body known by name
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct NamedEllipsoid {
+    /// The canonical name
+    pub name: &'static str,
+    /// Alternative names, compared case-insensitively like the canonical name
+    pub aliases: &'static [&'static str],
+    /// The EPSG code of the ellipsoid, if there is one
+    pub epsg: Option<u32>,
+    /// The celestial body the ellipsoid describes, if it is specific to one
+    pub body: Option<&'static str>,
+    pub semimajor_axis: f64,
+    /// The inverse flattening, or 0 for spheres
+    pub inverse_flattening: f64,
+}
+
+impl NamedEllipsoid {
+    pub fn is_sphere(&self) -> bool {
+        self.inverse_flattening == 0.0
+    }
+
+    /// Whether `name` is the canonical name or one of the aliases
+    pub fn matches(&self, name: &str) -> bool {
+        self.name.eq_ignore_ascii_case(name)
+            || self
+                .aliases
+                .iter()
+                .any(|alias| alias.eq_ignore_ascii_case(name))
+    }
+
+    /// Create the body, carrying the canonical name
+    pub fn to_ellipsoid(&self) -> Ellipsoid {
+        if self.is_sphere() {
+            let ellipsoid = GeodesyEllipsoid::new(self.semimajor_axis, 0.0);
+
+            let mut sphere = ReferenceSphere::new(ellipsoid).with_name(self.name);
+            if let Some(body) = self.body {
+                sphere = sphere.with_body(body);
+            }
+
+            Ellipsoid::Sphere(sphere)
+        } else {
+            let ellipsoid =
+                GeodesyEllipsoid::new(self.semimajor_axis, 1.0 / self.inverse_flattening);
+
+            let mut reference = ReferenceEllipsoid::new(ellipsoid).with_name(self.name);
+            if let Some(body) = self.body {
+                reference = reference.with_body(body);
+            }
+
+            Ellipsoid::Ellipsoid(reference)
+        }
+    }
+}
+
+const REGISTRY: &[NamedEllipsoid] = &[
+    NamedEllipsoid {
+        name: "WGS84",
+        aliases: &["WGS 84", "WGS 1984"],
+        epsg: Some(7030),
+        body: Some("Earth"),
+        semimajor_axis: 6378137.0,
+        inverse_flattening: 298.257223563,
+    },
+    NamedEllipsoid {
+        name: "GRS80",
+        aliases: &["GRS 1980"],
+        epsg: Some(7019),
+        body: Some("Earth"),
+        semimajor_axis: 6378137.0,
+        inverse_flattening: 298.257222101,
+    },
+    NamedEllipsoid {
+        name: "WGS72",
+        aliases: &["WGS 72", "WGS 1972"],
+        epsg: Some(7043),
+        body: Some("Earth"),
+        semimajor_axis: 6378135.0,
+        inverse_flattening: 298.26,
+    },
+    NamedEllipsoid {
+        name: "GRS67",
+        aliases: &["GRS 1967"],
+        epsg: Some(7036),
+        body: Some("Earth"),
+        semimajor_axis: 6378160.0,
+        inverse_flattening: 298.247167427,
+    },
+    NamedEllipsoid {
+        name: "intl",
+        aliases: &["International 1924", "Hayford"],
+        epsg: Some(7022),
+        body: Some("Earth"),
+        semimajor_axis: 6378388.0,
+        inverse_flattening: 297.0,
+    },
+    NamedEllipsoid {
+        name: "bessel",
+        aliases: &["Bessel 1841"],
+        epsg: Some(7004),
+        body: Some("Earth"),
+        semimajor_axis: 6377397.155,
+        inverse_flattening: 299.1528128,
+    },
+    NamedEllipsoid {
+        name: "clrk66",
+        aliases: &["Clarke 1866"],
+        epsg: Some(7008),
+        body: Some("Earth"),
+        semimajor_axis: 6378206.4,
+        inverse_flattening: 294.978698214,
+    },
+    NamedEllipsoid {
+        name: "clrk80",
+        aliases: &["Clarke 1880 (RGS)"],
+        epsg: Some(7012),
+        body: Some("Earth"),
+        semimajor_axis: 6378249.145,
+        inverse_flattening: 293.465,
+    },
+    NamedEllipsoid {
+        name: "airy",
+        aliases: &["Airy 1830"],
+        epsg: Some(7001),
+        body: Some("Earth"),
+        semimajor_axis: 6377563.396,
+        inverse_flattening: 299.3249646,
+    },
+    NamedEllipsoid {
+        name: "mod_airy",
+        aliases: &["Airy Modified 1849"],
+        epsg: Some(7002),
+        body: Some("Earth"),
+        semimajor_axis: 6377340.189,
+        inverse_flattening: 299.3249646,
+    },
+    NamedEllipsoid {
+        name: "krass",
+        aliases: &["Krassowsky 1940", "Krasovsky 1940"],
+        epsg: Some(7024),
+        body: Some("Earth"),
+        semimajor_axis: 6378245.0,
+        inverse_flattening: 298.3,
+    },
+    NamedEllipsoid {
+        name: "sphere",
+        aliases: &["Clarke 1866 Authalic Sphere"],
+        epsg: Some(7052),
+        body: Some("Earth"),
+        semimajor_axis: 6370997.0,
+        inverse_flattening: 0.0,
+    },
+    NamedEllipsoid {
+        name: "unitsphere",
+        aliases: &[],
+        epsg: None,
+        body: None,
+        semimajor_axis: 1.0,
+        inverse_flattening: 0.0,
+    },
+    NamedEllipsoid {
+        name: "mars",
+        aliases: &["Mars 2000", "IAU Mars 2000"],
+        epsg: None,
+        body: Some("Mars"),
+        semimajor_axis: 3396190.0,
+        inverse_flattening: 169.8944472236118,
+    },
+    NamedEllipsoid {
+        name: "mars_sphere",
+        aliases: &["Mars 2000 Sphere", "IAU Mars 2000 Sphere"],
+        epsg: None,
+        body: Some("Mars"),
+        semimajor_axis: 3396190.0,
+        inverse_flattening: 0.0,
+    },
+    NamedEllipsoid {
+        name: "moon",
+        aliases: &["Moon 2000", "IAU Moon 2000"],
+        epsg: None,
+        body: Some("Moon"),
+        semimajor_axis: 1737400.0,
+        inverse_flattening: 0.0,
+    },
+    NamedEllipsoid {
+        name: "venus",
+        aliases: &["Venus 2000", "IAU Venus 2000"],
+        epsg: None,
+        body: Some("Venus"),
+        semimajor_axis: 6051800.0,
+        inverse_flattening: 0.0,
+    },
+    NamedEllipsoid {
+        name: "mercury",
+        aliases: &["Mercury 2000", "IAU Mercury 2000"],
+        epsg: None,
+        body: Some("Mercury"),
+        semimajor_axis: 2439700.0,
+        inverse_flattening: 0.0,
+    },
+];
+
+/// All known reference bodies
+pub fn registry() -> &'static [NamedEllipsoid] {
+    REGISTRY
+}
+
+/// Find a reference body by name or alias
+pub fn lookup(name: &str) -> Option<&'static NamedEllipsoid> {
+    REGISTRY.iter().find(|entry| entry.matches(name))
+}
+
+/// Find a reference body by EPSG code
+pub fn lookup_epsg(code: u32) -> Option<&'static NamedEllipsoid> {
+    REGISTRY.iter().find(|entry| entry.epsg == Some(code))
+}
+
+/// Find a reference body by an EPSG code given as `"EPSG:<code>"`
+pub fn lookup_epsg_str(code: &str) -> Option<&'static NamedEllipsoid> {
+    let (authority, code) = code.split_once(':')?;
+
+    if !authority.eq_ignore_ascii_case("EPSG") {
+        return None;
+    }
+
+    lookup_epsg(code.trim().parse().ok()?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ellipsoid::ReferenceBody;
+    use geodesy::ellps::EllipsoidBase;
+
+    #[test]
+    fn test_lookup() {
+        assert_eq!(lookup("WGS84").map(|e| e.name), Some("WGS84"));
+        assert_eq!(lookup("wgs 84").map(|e| e.name), Some("WGS84"));
+        assert_eq!(lookup("Bessel 1841").map(|e| e.name), Some("bessel"));
+        assert_eq!(lookup("unknown"), None);
+    }
+
+    #[test]
+    fn test_lookup_epsg() {
+        assert_eq!(lookup_epsg(7019).map(|e| e.name), Some("GRS80"));
+        assert_eq!(lookup_epsg_str("EPSG:7030").map(|e| e.name), Some("WGS84"));
+        assert_eq!(
+            lookup_epsg_str("epsg: 7052").map(|e| e.name),
+            Some("sphere")
+        );
+        // the GRS 1980 authalic sphere has a different radius
+        assert_eq!(lookup_epsg(7047), None);
+        assert_eq!(lookup_epsg(4326), None);
+        assert_eq!(lookup_epsg_str("ESRI:7030"), None);
+    }
+
+    #[test]
+    fn test_planetary_bodies() {
+        let mars = lookup("Mars 2000").unwrap().to_ellipsoid();
+        let moon = Ellipsoid::named("moon").unwrap();
+
+        assert_eq!(mars.body(), Some("Mars"));
+        assert_eq!(moon.body(), Some("Moon"));
+        assert_eq!(Ellipsoid::default().body(), Some("Earth"));
+        assert_eq!(Ellipsoid::named("unitsphere").unwrap().body(), None);
+
+        assert!(!mars.same_body(&moon));
+        assert!(mars.same_body(&Ellipsoid::named("mars_sphere").unwrap()));
+        assert!(moon.same_body(&Ellipsoid::named("unitsphere").unwrap()));
+    }
+
+    #[test]
+    fn test_unique_names() {
+        for (index, entry) in REGISTRY.iter().enumerate() {
+            for other in &REGISTRY[index + 1..] {
+                assert!(!other.matches(entry.name));
+                assert!(entry.aliases.iter().all(|alias| !other.matches(alias)));
+            }
+        }
+    }
+
+    #[test]
+    fn test_matches_geodesy() {
+        for entry in REGISTRY {
+            let Ok(expected) = GeodesyEllipsoid::named(entry.name) else {
+                continue;
+            };
+            let actual = entry.to_ellipsoid();
+
+            assert_eq!(
+                actual.ellipsoid().semimajor_axis(),
+                expected.semimajor_axis()
+            );
+            assert!((actual.ellipsoid().flattening() - expected.flattening()).abs() < 1e-12);
+        }
+    }
+}
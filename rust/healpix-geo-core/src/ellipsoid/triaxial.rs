@@ -0,0 +1,482 @@
+use super::{ParameterError, ReferenceBody, ReferenceEllipsoid, authalic, parameters};
+use geodesy::ellps::Ellipsoid as GeodesyEllipsoid;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::f64::consts::{FRAC_PI_2, TAU};
+
+/// Nodes and weights of the 8-point Gauss-Legendre quadrature on [-1, 1]
+const GAUSS_LEGENDRE: [(f64, f64); 4] = [
+    (0.1834346424956498, 0.3626837833783620),
+    (0.5255324099163290, 0.3137066458778873),
+    (0.7966664774136267, 0.2223810344533745),
+    (0.9602898564975363, 0.1012285362903763),
+];
+/// Number of quadrature panels on a quarter of the equator
+const PANELS: usize = 8;
+const MAX_ITERATIONS: usize = 50;
+
+/// A triaxial ellipsoid
+///
+/// The semimajor axis points towards longitude 0°, the semimedian axis
+/// towards longitude 90° and the semiminor axis towards the north pole.
+/// Geographic coordinates are the directions of the surface normal.
+///
+/// The equal-area mapping to the authalic sphere is built in two steps: the
+/// authalic longitude is proportional to the area of the lune between the
+/// prime meridian and the meridian of the point, and along each meridian the
+/// sine of the authalic latitude is proportional to the area south of the
+/// point. Meridians thus map to meridians.
+///
+/// Conversions that only take a latitude are evaluated on the prime
+/// meridian, which is an ellipse with the semimajor and semiminor axes. The
+/// same goes for `ReferenceBody::ellipsoid`, which returns the ellipsoid of
+/// revolution through the prime meridian. Geodesics are not supported.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(from = "TriaxialParameters", into = "TriaxialParameters")]
+pub struct ReferenceTriaxial {
+    semimajor_axis: f64,
+    semimedian_axis: f64,
+    semiminor_axis: f64,
+    /// The ellipsoid of revolution through the prime meridian
+    meridian: ReferenceEllipsoid,
+    /// The integral of the lune area density over a quarter of the equator
+    quarter_lune_area: f64,
+    name: Option<String>,
+    body: Option<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct TriaxialParameters {
+    semimajor_axis: f64,
+    semimedian_axis: f64,
+    semiminor_axis: f64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    body: Option<String>,
+}
+
+impl From<TriaxialParameters> for ReferenceTriaxial {
+    fn from(parameters: TriaxialParameters) -> Self {
+        let mut triaxial = Self::new(
+            parameters.semimajor_axis,
+            parameters.semimedian_axis,
+            parameters.semiminor_axis,
+        );
+        triaxial.name = parameters.name;
+        triaxial.body = parameters.body;
+
+        triaxial
+    }
+}
+
+impl From<ReferenceTriaxial> for TriaxialParameters {
+    fn from(triaxial: ReferenceTriaxial) -> Self {
+        Self {
+            semimajor_axis: triaxial.semimajor_axis,
+            semimedian_axis: triaxial.semimedian_axis,
+            semiminor_axis: triaxial.semiminor_axis,
+            name: triaxial.name,
+            body: triaxial.body,
+        }
+    }
+}
+
+impl ReferenceTriaxial {
+    pub fn new(semimajor_axis: f64, semimedian_axis: f64, semiminor_axis: f64) -> Self {
+        if !(semimajor_axis >= semimedian_axis
+            && semimedian_axis >= semiminor_axis
+            && semiminor_axis > 0.0)
+        {
+            // TODO: custom error type
+            panic!("the axes must be positive and sorted by decreasing length");
+        }
+
+        let flattening = (semimajor_axis - semiminor_axis) / semimajor_axis;
+        let meridian = ReferenceEllipsoid::new(GeodesyEllipsoid::new(semimajor_axis, flattening));
+
+        let mut triaxial = Self {
+            semimajor_axis,
+            semimedian_axis,
+            semiminor_axis,
+            meridian,
+            quarter_lune_area: 0.0,
+            name: None,
+            body: None,
+        };
+        triaxial.quarter_lune_area = triaxial.lune_area(FRAC_PI_2);
+
+        triaxial
+    }
+
+    pub fn with_name(mut self, name: &str) -> Self {
+        self.name = Some(name.to_string());
+        self
+    }
+
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    /// Mark the celestial body described by the reference body
+    pub fn with_body(mut self, body: &str) -> Self {
+        self.body = Some(body.to_string());
+        self
+    }
+
+    pub fn body(&self) -> Option<&str> {
+        self.body.as_deref()
+    }
+
+    pub fn axes(&self) -> (f64, f64, f64) {
+        (
+            self.semimajor_axis,
+            self.semimedian_axis,
+            self.semiminor_axis,
+        )
+    }
+
+    /// The squared radius of the equator at `longitude`, as seen by the normal
+    fn equatorial_term(&self, longitude: f64) -> f64 {
+        (self.semimajor_axis * longitude.cos()).powi(2)
+            + (self.semimedian_axis * longitude.sin()).powi(2)
+    }
+
+    /// The squared eccentricity of the meridian at `longitude`
+    ///
+    /// Along a meridian, the area element has the same form as on an
+    /// ellipsoid of revolution with this eccentricity.
+    fn meridian_eccentricity_squared(&self, longitude: f64) -> f64 {
+        1.0 - self.semiminor_axis.powi(2) / self.equatorial_term(longitude)
+    }
+
+    /// The density of the lune area with respect to the longitude, up to a factor
+    fn lune_density(&self, longitude: f64) -> f64 {
+        let p = self.equatorial_term(longitude);
+
+        authalic::area_integral(self.meridian_eccentricity_squared(longitude), 1.0) / p.powi(2)
+    }
+
+    /// The area of the lune between the prime meridian and `longitude`, up to a factor
+    fn lune_area(&self, longitude: f64) -> f64 {
+        let width = longitude / PANELS as f64;
+
+        (0..PANELS)
+            .map(|panel| {
+                let center = (panel as f64 + 0.5) * width;
+
+                GAUSS_LEGENDRE
+                    .iter()
+                    .map(|(node, weight)| {
+                        let offset = node * width / 2.0;
+
+                        weight
+                            * (self.lune_density(center - offset)
+                                + self.lune_density(center + offset))
+                    })
+                    .sum::<f64>()
+                    * width
+                    / 2.0
+            })
+            .sum()
+    }
+
+    /// The authalic longitude for a longitude in `[0, π/2]`
+    fn quarter_authalic_longitude(&self, longitude: f64) -> f64 {
+        FRAC_PI_2 * self.lune_area(longitude) / self.quarter_lune_area
+    }
+
+    /// Invert `quarter_authalic_longitude`
+    fn quarter_geographic_longitude(&self, longitude: f64) -> f64 {
+        let scale = FRAC_PI_2 / self.quarter_lune_area;
+
+        let mut current = longitude;
+        for _ in 0..MAX_ITERATIONS {
+            let step = (self.quarter_authalic_longitude(current) - longitude)
+                / (scale * self.lune_density(current));
+            current = (current - step).clamp(0.0, FRAC_PI_2);
+
+            if step.abs() < 1e-15 {
+                break;
+            }
+        }
+
+        current
+    }
+
+    /// Apply a map defined on `[0, π/2]` to any longitude
+    ///
+    /// The lune area density is symmetric with respect to every multiple of
+    /// π/2, so odd quarters are mirrored.
+    fn by_quarter(&self, longitude: f64, quarter_map: impl Fn(f64) -> f64) -> f64 {
+        let turns = longitude.div_euclid(TAU);
+        let reduced = longitude.rem_euclid(TAU);
+
+        let quarter = (reduced / FRAC_PI_2).floor().min(3.0);
+        let remainder = reduced - quarter * FRAC_PI_2;
+
+        let mapped = if quarter as u8 % 2 == 0 {
+            quarter_map(remainder)
+        } else {
+            FRAC_PI_2 - quarter_map(FRAC_PI_2 - remainder)
+        };
+
+        turns * TAU + quarter * FRAC_PI_2 + mapped
+    }
+}
+
+impl ReferenceBody for ReferenceTriaxial {
+    fn latitude_authalic_to_geographic(&self, latitude: f64) -> f64 {
+        self.authalic_to_geographic(&(0.0, latitude)).1
+    }
+
+    fn latitude_geographic_to_authalic(&self, latitude: f64) -> f64 {
+        self.geographic_to_authalic(&(0.0, latitude)).1
+    }
+
+    fn latitude_geocentric_to_geographic(&self, latitude: f64) -> f64 {
+        self.meridian.latitude_geocentric_to_geographic(latitude)
+    }
+
+    fn latitude_geographic_to_geocentric(&self, latitude: f64) -> f64 {
+        self.meridian.latitude_geographic_to_geocentric(latitude)
+    }
+
+    fn latitude_parametric_to_geographic(&self, latitude: f64) -> f64 {
+        self.meridian.latitude_parametric_to_geographic(latitude)
+    }
+
+    fn latitude_geographic_to_parametric(&self, latitude: f64) -> f64 {
+        self.meridian.latitude_geographic_to_parametric(latitude)
+    }
+
+    fn latitude_conformal_to_geographic(&self, latitude: f64) -> f64 {
+        self.meridian.latitude_conformal_to_geographic(latitude)
+    }
+
+    fn latitude_geographic_to_conformal(&self, latitude: f64) -> f64 {
+        self.meridian.latitude_geographic_to_conformal(latitude)
+    }
+
+    fn latitude_rectifying_to_geographic(&self, latitude: f64) -> f64 {
+        self.meridian.latitude_rectifying_to_geographic(latitude)
+    }
+
+    fn latitude_geographic_to_rectifying(&self, latitude: f64) -> f64 {
+        self.meridian.latitude_geographic_to_rectifying(latitude)
+    }
+
+    fn geographic_to_authalic(&self, point: &(f64, f64)) -> (f64, f64) {
+        let (lon, lat) = *point;
+
+        (
+            self.by_quarter(lon, |l| self.quarter_authalic_longitude(l)),
+            authalic::geographic_to_authalic(self.meridian_eccentricity_squared(lon), lat),
+        )
+    }
+
+    fn authalic_to_geographic(&self, point: &(f64, f64)) -> (f64, f64) {
+        let (lon, lat) = *point;
+
+        let lon_ = self.by_quarter(lon, |l| self.quarter_geographic_longitude(l));
+
+        (
+            lon_,
+            authalic::authalic_to_geographic(self.meridian_eccentricity_squared(lon_), lat),
+        )
+    }
+
+    fn ellipsoid(&self) -> &GeodesyEllipsoid {
+        self.meridian.ellipsoid()
+    }
+
+    fn to_mapping(&self) -> HashMap<String, f64> {
+        let mut mapping = HashMap::new();
+        mapping.insert("semimajor_axis".to_string(), self.semimajor_axis);
+        mapping.insert("semimedian_axis".to_string(), self.semimedian_axis);
+        mapping.insert("semiminor_axis".to_string(), self.semiminor_axis);
+
+        mapping
+    }
+
+    fn from_mapping(mapping: &HashMap<String, f64>) -> Result<Self, ParameterError> {
+        let (a, b, c) = parameters::triaxial_axes(mapping)?;
+
+        Ok(Self::new(a, b, c))
+    }
+
+    fn geographic_height_to_cartesian(&self, point: &(f64, f64, f64)) -> (f64, f64, f64) {
+        let (lon, lat, height) = *point;
+        let normal = (lat.cos() * lon.cos(), lat.cos() * lon.sin(), lat.sin());
+        let (a, b, c) = self.axes();
+
+        let n = ((a * normal.0).powi(2) + (b * normal.1).powi(2) + (c * normal.2).powi(2)).sqrt();
+
+        (
+            (a * a / n + height) * normal.0,
+            (b * b / n + height) * normal.1,
+            (c * c / n + height) * normal.2,
+        )
+    }
+
+    fn cartesian_to_geographic_height(&self, point: &(f64, f64, f64)) -> (f64, f64, f64) {
+        let p = [point.0, point.1, point.2];
+        let axes = [
+            self.semimajor_axis,
+            self.semimedian_axis,
+            self.semiminor_axis,
+        ];
+
+        // the foot point is `p_i a_i² / (a_i² + t)`, with `t` the root of a convex,
+        // decreasing function. Starting on its left, newton steps approach the root
+        // monotonically.
+        let foot_point_equation = |t: f64| {
+            axes.iter()
+                .zip(p)
+                .fold((-1.0, 0.0), |(value, slope), (a, p)| {
+                    let term = p * a / (a * a + t);
+
+                    (
+                        value + term.powi(2),
+                        slope - 2.0 * term.powi(2) / (a * a + t),
+                    )
+                })
+        };
+
+        let mut t = axes
+            .iter()
+            .zip(p)
+            .map(|(a, p)| a * p.abs() - a * a)
+            .fold(f64::NEG_INFINITY, f64::max);
+        for _ in 0..MAX_ITERATIONS {
+            let (value, slope) = foot_point_equation(t);
+            if value == 0.0 || slope == 0.0 {
+                break;
+            }
+
+            let step = value / slope;
+            t -= step;
+
+            if step.abs() <= 1e-14 * t.abs().max(1.0) {
+                break;
+            }
+        }
+
+        // the normal at the foot point is parallel to `p_i / (a_i² + t)`
+        let normal: Vec<f64> = axes.iter().zip(p).map(|(a, p)| p / (a * a + t)).collect();
+        let norm = normal.iter().map(|n| n * n).sum::<f64>().sqrt();
+
+        let lon = normal[1].atan2(normal[0]);
+        let lat = normal[2].atan2(normal[0].hypot(normal[1]));
+
+        (lon, lat, t * norm)
+    }
+}
+
+impl PartialEq for ReferenceTriaxial {
+    fn eq(&self, other: &Self) -> bool {
+        self.axes() == other.axes()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use geodesy::ellps::EllipsoidBase;
+
+    fn triaxial() -> ReferenceTriaxial {
+        // a triaxial model of the earth
+        ReferenceTriaxial::new(6378171.36, 6378102.0, 6356752.0)
+    }
+
+    #[test]
+    fn test_biaxial_limit() {
+        let wgs84 = GeodesyEllipsoid::named("WGS84").unwrap();
+        let a = wgs84.semimajor_axis();
+        let c = wgs84.semiminor_axis();
+
+        let triaxial = ReferenceTriaxial::new(a, a, c);
+        let reference = ReferenceEllipsoid::new(wgs84);
+
+        for degrees in [-80.0, -30.0, 0.0, 10.0, 45.0, 89.0] {
+            let latitude = f64::to_radians(degrees);
+
+            let actual = triaxial.geographic_to_authalic(&(1.0, latitude));
+            let expected = reference.latitude_geographic_to_authalic(latitude);
+
+            assert!((actual.0 - 1.0).abs() < 1e-12);
+            assert!((actual.1 - expected).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_authalic_roundtrip() {
+        let triaxial = triaxial();
+
+        for lon in [-3.0, 0.0, 0.3, 1.2, 2.0, 3.5, 5.9, 7.0] {
+            for lat in [-1.5, -0.7, 0.0, 0.2, 1.1] {
+                let authalic = triaxial.geographic_to_authalic(&(lon, lat));
+                let (lon_, lat_) = triaxial.authalic_to_geographic(&authalic);
+
+                assert!((lon_ - lon).abs() < 1e-12, "{lon} {lon_}");
+                assert!((lat_ - lat).abs() < 1e-12, "{lat} {lat_}");
+            }
+        }
+
+        // the axes are fixed points of the mapping
+        for lon in [0.0, FRAC_PI_2, 3.0 * FRAC_PI_2] {
+            let (actual, _) = triaxial.geographic_to_authalic(&(lon, 0.3));
+            assert!((actual - lon).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_equal_area() {
+        // the quarter meridians, the poles and the equator divide the body into
+        // eight parts of equal area, which are kept by the mapping
+        let triaxial = triaxial();
+
+        let (lon, _) = triaxial.geographic_to_authalic(&(FRAC_PI_2, 0.0));
+        let (_, lat) = triaxial.geographic_to_authalic(&(0.7, FRAC_PI_2));
+        let (_, equator) = triaxial.geographic_to_authalic(&(0.7, 0.0));
+
+        assert!((lon - FRAC_PI_2).abs() < 1e-12);
+        assert!((lat - FRAC_PI_2).abs() < 1e-12);
+        assert!(equator.abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_cartesian_roundtrip() {
+        let triaxial = triaxial();
+
+        for point in [
+            (0.0, 0.0, 0.0),
+            (0.4, 0.7, 100.0),
+            (2.0, -1.2, 35786000.0),
+            (-2.5, 1.5, -2000.0),
+        ] {
+            let cartesian = triaxial.geographic_height_to_cartesian(&point);
+            let (lon, lat, height) = triaxial.cartesian_to_geographic_height(&cartesian);
+
+            assert!((lon - point.0).abs() < 1e-10);
+            assert!((lat - point.1).abs() < 1e-10);
+            assert!((height - point.2).abs() < 1e-4);
+        }
+
+        let (x, y, z) = triaxial.geographic_height_to_cartesian(&(FRAC_PI_2, 0.0, 0.0));
+        assert!(x.abs() < 1e-6);
+        assert!((y - 6378102.0).abs() < 1e-6);
+        assert_eq!(z, 0.0);
+    }
+
+    #[test]
+    fn test_serialization() {
+        let triaxial = triaxial().with_name("test");
+
+        let serialized = serde_json::to_string(&triaxial).unwrap();
+        let roundtripped: ReferenceTriaxial = serde_json::from_str(&serialized).unwrap();
+
+        assert_eq!(roundtripped, triaxial);
+        assert_eq!(roundtripped.name(), Some("test"));
+    }
+}
@@ -0,0 +1,188 @@
+use super::region::CellRegion;
+use super::scheme::{IndexingScheme, LabelError};
+use crate::ellipsoid::Ellipsoid;
+use moc::moc::range::RangeMOC;
+use moc::qty::Hpx;
+
+/// Number of cell ids buffered before they are merged into the partial region
+const DEFAULT_BUFFER_CAPACITY: usize = 1 << 20;
+
+/// Incrementally build a `CellRegion` from batches of cell ids
+///
+/// Cell ids may arrive in any order and contain duplicates. At most
+/// `buffer_capacity` of them are held at once: whenever the buffer is full
+/// it is turned into a MOC and merged into the region built so far, so the
+/// memory use depends on the shape of the region rather than on the total
+/// number of cell ids.
+#[derive(Debug, Clone)]
+pub struct CellRegionBuilder {
+    depth: u8,
+    indexing_scheme: IndexingScheme,
+    ellipsoid: Ellipsoid,
+    buffer: Vec<u64>,
+    buffer_capacity: usize,
+    moc: RangeMOC<u64, Hpx<u64>>,
+}
+
+impl CellRegionBuilder {
+    pub fn new(depth: u8, indexing_scheme: IndexingScheme, ellipsoid: Ellipsoid) -> Self {
+        Self::with_buffer_capacity(depth, indexing_scheme, ellipsoid, DEFAULT_BUFFER_CAPACITY)
+    }
+
+    pub fn with_buffer_capacity(
+        depth: u8,
+        indexing_scheme: IndexingScheme,
+        ellipsoid: Ellipsoid,
+        buffer_capacity: usize,
+    ) -> Self {
+        if buffer_capacity == 0 {
+            // TODO: custom error type
+            panic!("the buffer capacity must be positive");
+        }
+
+        Self {
+            depth,
+            indexing_scheme,
+            ellipsoid,
+            buffer: Vec::new(),
+            buffer_capacity,
+            moc: RangeMOC::new_empty(depth),
+        }
+    }
+
+    /// Add a batch of cell ids following the indexing scheme of the builder
+    ///
+    /// Fails if a cell id can't be converted to a cell at the depth of the
+    /// builder. The batch is checked before any of its cell ids are added.
+    pub fn add_cell_ids(&mut self, cell_ids: &[u64]) -> Result<(), LabelError> {
+        for &label in cell_ids {
+            self.indexing_scheme.check_label(label, self.depth)?;
+        }
+
+        for chunk in cell_ids.chunks(self.buffer_capacity) {
+            let available = self.buffer_capacity - self.buffer.len();
+            let (head, tail) = chunk.split_at(available.min(chunk.len()));
+
+            self.extend_buffer(head);
+            if self.buffer.len() == self.buffer_capacity {
+                self.flush();
+            }
+
+            self.extend_buffer(tail);
+        }
+
+        Ok(())
+    }
+
+    /// The number of cell ids waiting to be merged
+    pub fn n_buffered(&self) -> usize {
+        self.buffer.len()
+    }
+
+    /// Merge the remaining cell ids and create the region
+    pub fn build(mut self) -> CellRegion {
+        self.flush();
+
+        CellRegion::from_moc(self.moc, self.indexing_scheme, self.ellipsoid)
+    }
+
+    /// Convert and buffer cell ids that have already been checked
+    fn extend_buffer(&mut self, cell_ids: &[u64]) {
+        let depth = self.depth;
+        let scheme = self.indexing_scheme;
+
+        self.buffer.extend(cell_ids.iter().map(|&label| {
+            scheme
+                .to_nested(label, depth)
+                .expect("the cell ids have been checked")
+        }));
+    }
+
+    fn flush(&mut self) {
+        if self.buffer.is_empty() {
+            return;
+        }
+
+        self.buffer.sort_unstable();
+        self.buffer.dedup();
+
+        let partial = RangeMOC::from_fixed_depth_cells(self.depth, self.buffer.drain(..), None);
+        self.moc = self.moc.union(&partial);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unsorted_batches() {
+        let depth = 4;
+        let ellipsoid = Ellipsoid::named("WGS84").unwrap();
+        let mut builder = CellRegionBuilder::with_buffer_capacity(
+            depth,
+            IndexingScheme::Nested,
+            ellipsoid.clone(),
+            3,
+        );
+
+        builder.add_cell_ids(&[17, 3, 5, 3]).unwrap();
+        assert!(builder.n_buffered() < 3);
+        builder.add_cell_ids(&[]).unwrap();
+        builder.add_cell_ids(&[100, 4, 17, 2, 99, 5, 1000]).unwrap();
+
+        let actual = builder.build();
+        let expected =
+            CellRegion::from_cell_ids(depth, vec![2, 3, 4, 5, 17, 99, 100, 1000], ellipsoid);
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_ring() {
+        let depth = 3;
+        let cell_ids = vec![700, 3, 64, 3, 12, 65];
+        let ellipsoid = Ellipsoid::named("WGS84").unwrap();
+        let mut builder = CellRegionBuilder::with_buffer_capacity(
+            depth,
+            IndexingScheme::Ring,
+            ellipsoid.clone(),
+            2,
+        );
+
+        for batch in cell_ids.chunks(4) {
+            builder.add_cell_ids(batch).unwrap();
+        }
+
+        let actual = builder.build();
+        let expected =
+            CellRegion::from_cell_ids_with_scheme(depth, cell_ids, IndexingScheme::Ring, ellipsoid)
+                .unwrap();
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_zuniq_depth_mismatch() {
+        let ellipsoid = Ellipsoid::named("WGS84").unwrap();
+        let mut builder = CellRegionBuilder::new(3, IndexingScheme::Zuniq, ellipsoid.clone());
+
+        let valid = IndexingScheme::Zuniq.from_nested(5, 3);
+        let invalid = IndexingScheme::Zuniq.from_nested(5, 2);
+
+        assert!(builder.add_cell_ids(&[valid, invalid]).is_err());
+        assert_eq!(builder.n_buffered(), 0);
+        assert_eq!(
+            builder.build(),
+            CellRegion::empty_with_scheme(3, IndexingScheme::Zuniq, ellipsoid)
+        );
+    }
+
+    #[test]
+    fn test_empty() {
+        let ellipsoid = Ellipsoid::named("WGS84").unwrap();
+        let builder = CellRegionBuilder::new(5, IndexingScheme::Nested, ellipsoid.clone());
+
+        assert_eq!(builder.build(), CellRegion::empty(5, ellipsoid));
+    }
+}
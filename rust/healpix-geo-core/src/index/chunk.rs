@@ -0,0 +1,89 @@
+use super::indexers::ConcreteSlice;
+use super::region::CellRegion;
+use std::ops::Range;
+
+/// Part of a region contained in a single cell of a coarser depth
+#[derive(Debug, Clone, PartialEq)]
+pub struct RegionChunk {
+    /// The depth of the parent cell
+    pub depth: u8,
+    /// The id of the parent cell, following the indexing scheme of the region
+    pub cell_id: u64,
+    /// The positions of the chunk's cells within the region
+    pub positions: ConcreteSlice<isize>,
+    /// The cells of the chunk
+    pub region: CellRegion,
+}
+
+/// Cells of a region sharing the same parent cell
+pub(crate) struct ParentGroup {
+    /// The nested id of the parent cell
+    pub parent: u64,
+    pub positions: Range<usize>,
+    /// The cell ranges at the depth of the region
+    pub ranges: Vec<Range<u64>>,
+}
+
+/// Split ranges of cells at the boundaries of their parent cells
+///
+/// `ranges` have to be sorted, expressed at the depth of the region, and
+/// `shift` is twice the depth difference to the parent cells.
+pub(crate) fn group_by_parent(
+    ranges: impl Iterator<Item = Range<u64>>,
+    offsets: &[usize],
+    shift: u8,
+) -> Vec<ParentGroup> {
+    let mut groups: Vec<ParentGroup> = Vec::new();
+
+    for (range, &offset) in ranges.zip(offsets) {
+        let first_parent = range.start >> shift;
+        let last_parent = (range.end - 1) >> shift;
+
+        for parent in first_parent..=last_parent {
+            let start = range.start.max(parent << shift);
+            let end = range.end.min((parent + 1) << shift);
+
+            let position_start = offset + (start - range.start) as usize;
+            let position_end = position_start + (end - start) as usize;
+
+            match groups.last_mut() {
+                Some(group) if group.parent == parent => {
+                    group.positions.end = position_end;
+                    group.ranges.push(start..end);
+                }
+                _ => groups.push(ParentGroup {
+                    parent,
+                    positions: position_start..position_end,
+                    ranges: vec![start..end],
+                }),
+            }
+        }
+    }
+
+    groups
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_group_by_parent() {
+        // depth difference of 1: parents cover 4 cells
+        let ranges = vec![1..6, 7..8, 12..20];
+        let offsets = vec![0, 5, 6];
+
+        let actual: Vec<_> = group_by_parent(ranges.into_iter(), &offsets, 2)
+            .into_iter()
+            .map(|group| (group.parent, group.positions, group.ranges))
+            .collect();
+        let expected = vec![
+            (0, 0..3, vec![1..4]),
+            (1, 3..6, vec![4..6, 7..8]),
+            (3, 6..10, vec![12..16]),
+            (4, 10..14, vec![16..20]),
+        ];
+
+        assert_eq!(actual, expected);
+    }
+}
@@ -113,11 +113,38 @@ impl Array<isize> {
     }
 }
 
+/// Distance used to find the nearest cell
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DistanceMetric {
+    /// Absolute difference between the cell ids
+    CellId,
+    /// Angular distance between the cell centers, in degrees
+    Angular,
+}
+
+/// How to treat labels that are not part of the index
+#[derive(Debug, Clone, PartialEq)]
+pub enum SelectionMethod {
+    /// All labels must be present.
+    Exact,
+    /// Skip missing labels.
+    DropMissing,
+    /// Replace missing labels by the nearest cell in the index.
+    ///
+    /// Labels without any cell within `tolerance` are skipped.
+    Nearest {
+        metric: DistanceMetric,
+        tolerance: Option<f64>,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq)]
 pub enum PositionalIndexer {
     Slice(Slice<isize>),
     Array(Array<isize>),
 }
 
+#[derive(Debug, Clone, PartialEq)]
 pub enum LabelIndexer {
     Slice(Slice<u64>),
     Array(Array<u64>),
@@ -1,7 +1,13 @@
-use super::indexers::{Array, ConcreteSlice, LabelIndexer, PositionalIndexer};
+use super::indexers::{
+    Array, ConcreteSlice, DistanceMetric, LabelIndexer, PositionalIndexer, SelectionMethod,
+};
+use super::ranges::{FixedDepthRanges, RangeSource};
+use cdshealpix::nested::Layer;
+use cdshealpix::sph_geom::coo3d::{UnitVec3, vec3_of};
 use moc::elemset::range::MocRanges;
 use moc::moc::range::RangeMOC;
 use moc::qty::Hpx;
+use std::f64::consts::PI;
 use std::ops::Range;
 
 pub trait Indexing {
@@ -9,28 +15,50 @@ pub trait Indexing {
     where
         Self: Sized;
 
+    /// Select by label, with a strategy for labels that can't be found
+    ///
+    /// Returns the subset, the positions of the selected cells and the labels
+    /// that were skipped.
+    fn sel_with_method(
+        &self,
+        indexer: &LabelIndexer,
+        method: &SelectionMethod,
+    ) -> (Self, PositionalIndexer, Array<u64>)
+    where
+        Self: Sized;
+
     fn isel(&self, indexer: &PositionalIndexer) -> Self
     where
         Self: Sized;
 }
 
 pub trait PositionIndexing {
-    fn position_slice(&self, slice: &ConcreteSlice<isize>) -> Self;
-    fn position_index(&self, array: &Array<isize>) -> Self;
+    fn position_slice(&self, slice: &ConcreteSlice<isize>) -> RangeMOC<u64, Hpx<u64>>;
+    fn position_index(&self, array: &Array<isize>) -> RangeMOC<u64, Hpx<u64>>;
 }
 
 pub trait LabelIndexing {
-    fn label_slice(&self, slice: ConcreteSlice<u64>) -> (Self, ConcreteSlice<usize>)
-    where
-        Self: Sized;
-
-    fn label_index(&self, array: &Array<u64>) -> (Self, Array<usize>)
-    where
-        Self: Sized;
+    fn label_slice(
+        &self,
+        slice: ConcreteSlice<u64>,
+    ) -> (RangeMOC<u64, Hpx<u64>>, ConcreteSlice<usize>);
+
+    fn label_index(&self, array: &Array<u64>) -> (RangeMOC<u64, Hpx<u64>>, Array<usize>);
+
+    /// Select cells by label, with a strategy for labels that can't be found
+    ///
+    /// The positions are sorted and unique, such that the nth position is the
+    /// position of the nth cell of the subset. Labels resolving to the same
+    /// cell are only selected once.
+    fn label_index_with(
+        &self,
+        array: &Array<u64>,
+        method: &SelectionMethod,
+    ) -> (RangeMOC<u64, Hpx<u64>>, Array<usize>, Array<u64>);
 }
 
-impl PositionIndexing for RangeMOC<u64, Hpx<u64>> {
-    fn position_slice(&self, slice: &ConcreteSlice<isize>) -> Self {
+impl<S: RangeSource> PositionIndexing for S {
+    fn position_slice(&self, slice: &ConcreteSlice<isize>) -> RangeMOC<u64, Hpx<u64>> {
         if slice.step != 1 {
             panic!("Only step size 1 is supported, got {}", slice.step);
         }
@@ -42,9 +70,8 @@ impl PositionIndexing for RangeMOC<u64, Hpx<u64>> {
         let shift = delta_depth << 1;
 
         let ranges = MocRanges::new_from(
-            self.moc_ranges()
-                .iter()
-                .filter_map(|range: &Range<u64>| {
+            self.iter_ranges()
+                .filter_map(|range: Range<u64>| {
                     let range_size = ((range.end - range.start) >> shift) as isize;
 
                     if start >= range_size {
@@ -91,43 +118,23 @@ impl PositionIndexing for RangeMOC<u64, Hpx<u64>> {
         RangeMOC::new(self.depth_max(), ranges)
     }
 
-    fn position_index(&self, array: &Array<isize>) -> Self {
+    fn position_index(&self, array: &Array<isize>) -> RangeMOC<u64, Hpx<u64>> {
         let size = self.n_depth_max_cells() as usize;
         let normalized = array.normalize(size);
-        let delta_depth = 29 - self.depth_max();
-        let shift = delta_depth << 1;
 
-        let slice_offsets = self
-            .moc_ranges()
-            .iter()
-            .map(|range| ((range.end - range.start) >> shift) as usize)
-            .scan(0, |acc, x| {
-                let cur = *acc;
-                *acc += x;
-                Some((cur, *acc))
-            })
-            .collect::<Vec<(usize, usize)>>();
-        let slice_starts = self
-            .moc_ranges()
-            .iter()
-            .map(|range| range.start)
-            .collect::<Vec<u64>>();
+        let ranges = FixedDepthRanges::new(self);
+        let offsets = self.range_offsets();
 
         let cell_ids: Vec<u64> = normalized
             .data
             .iter()
             .map(|&index| {
-                let position = index;
                 if index >= size {
                     panic!("{index} is out of bounds");
                 } else {
-                    let slice_index = slice_offsets
-                        .iter()
-                        .position(|x| position >= x.0 && position < x.1)
-                        .unwrap_or(slice_offsets.len() - 1);
-                    let slice_start = slice_starts[slice_index] >> shift;
+                    let range_index = offsets.partition_point(|&offset| offset <= index) - 1;
 
-                    slice_start + (index as u64 - (slice_offsets[slice_index].0 as u64))
+                    ranges.get(range_index).start + (index - offsets[range_index]) as u64
                 }
             })
             .collect::<Vec<u64>>();
@@ -136,28 +143,16 @@ impl PositionIndexing for RangeMOC<u64, Hpx<u64>> {
     }
 }
 
-pub(crate) fn range_offsets(moc: &RangeMOC<u64, Hpx<u64>>) -> Vec<usize> {
-    let relative_depth = 29 - moc.depth_max();
-
-    moc.moc_ranges()
-        .iter()
-        .map(|r| ((r.end - r.start) >> (relative_depth << 1)) as usize)
-        .scan(0, |state, x| {
-            let val = *state;
-            *state += x;
-            Some(val)
-        })
-        .collect()
-}
-
-impl LabelIndexing for RangeMOC<u64, Hpx<u64>> {
-    fn label_slice(&self, slice: ConcreteSlice<u64>) -> (Self, ConcreteSlice<usize>) {
+impl<S: RangeSource> LabelIndexing for S {
+    fn label_slice(
+        &self,
+        slice: ConcreteSlice<u64>,
+    ) -> (RangeMOC<u64, Hpx<u64>>, ConcreteSlice<usize>) {
         let depth = self.depth_max();
-        let offsets = range_offsets(self);
+        let offsets = self.range_offsets();
 
         let (slices, ranges): (Vec<_>, Vec<_>) = self
-            .moc_ranges()
-            .iter()
+            .iter_ranges()
             .enumerate()
             .filter_map(|(index, range)| {
                 let offset = offsets[index];
@@ -193,39 +188,154 @@ impl LabelIndexing for RangeMOC<u64, Hpx<u64>> {
         (new_moc, joined_slice)
     }
 
-    fn label_index(&self, array: &Array<u64>) -> (Self, Array<usize>) {
+    fn label_index(&self, array: &Array<u64>) -> (RangeMOC<u64, Hpx<u64>>, Array<usize>) {
+        let (new_moc, positions, _) = self.label_index_with(array, &SelectionMethod::Exact);
+
+        (new_moc, positions)
+    }
+
+    fn label_index_with(
+        &self,
+        array: &Array<u64>,
+        method: &SelectionMethod,
+    ) -> (RangeMOC<u64, Hpx<u64>>, Array<usize>, Array<u64>) {
         let depth = self.depth_max();
-        let offsets = range_offsets(self);
+        let layer = cdshealpix::nested::get(depth);
+        let offsets = self.range_offsets();
 
-        let delta_depth = 29 - depth;
-        let shift = delta_depth << 1;
+        let ranges = FixedDepthRanges::new(self);
 
-        let ranges = self
-            .moc_ranges()
-            .iter()
-            .map(|x| Range {
-                start: x.start >> shift,
-                end: x.end >> shift,
-            })
-            .collect::<Vec<_>>();
+        let mut selected = Vec::<(usize, u64)>::with_capacity(array.data.len());
+        let mut missing = Vec::<u64>::new();
 
-        let (positions, cell_ids): (Vec<_>, Vec<_>) = array
-            .data
-            .iter()
-            .map(|&hash| {
-                let range_index = ranges
-                    .iter()
-                    .position(|r: &Range<u64>| r.contains(&hash))
-                    .expect("Cannot find {hash}");
+        for &hash in array.data.iter() {
+            let found = match method {
+                SelectionMethod::Exact => {
+                    let range_index = ranges
+                        .find(hash)
+                        .unwrap_or_else(|| panic!("Cannot find {hash}"));
 
-                let position = (hash - ranges[range_index].start) as usize + offsets[range_index];
+                    Some((range_index, hash))
+                }
+                SelectionMethod::DropMissing => {
+                    ranges.find(hash).map(|range_index| (range_index, hash))
+                }
+                SelectionMethod::Nearest {
+                    metric: DistanceMetric::CellId,
+                    tolerance,
+                } => nearest_by_cell_id(&ranges, hash, *tolerance),
+                SelectionMethod::Nearest {
+                    metric: DistanceMetric::Angular,
+                    tolerance,
+                } => nearest_by_angular_distance(&ranges, layer, hash, *tolerance),
+            };
+
+            match found {
+                Some((range_index, cell_id)) => selected.push((
+                    (cell_id - ranges.get(range_index).start) as usize + offsets[range_index],
+                    cell_id,
+                )),
+                None => missing.push(hash),
+            }
+        }
 
-                (position, hash)
-            })
-            .unzip();
+        // the subset can't contain duplicates and is sorted, so the positions
+        // are sorted and deduplicated the same way to stay aligned with it.
+        // Positions increase with the cell ids.
+        selected.sort_unstable();
+        selected.dedup();
 
+        let (positions, cell_ids): (Vec<usize>, Vec<u64>) = selected.into_iter().unzip();
         let new_moc = RangeMOC::from_fixed_depth_cells(depth, cell_ids.into_iter(), None);
 
-        (new_moc, Array { data: positions })
+        (new_moc, Array { data: positions }, Array { data: missing })
+    }
+}
+
+fn nearest_by_cell_id<S: RangeSource>(
+    ranges: &FixedDepthRanges<'_, S>,
+    hash: u64,
+    tolerance: Option<f64>,
+) -> Option<(usize, u64)> {
+    let index = ranges.partition_point(|r| r.end <= hash);
+    if index < ranges.len() && ranges.get(index).start <= hash {
+        return Some((index, hash));
+    }
+
+    // the label is in the gap between the previous and the next range
+    let before = index.checked_sub(1).map(|i| (i, ranges.get(i).end - 1));
+    let after = (index < ranges.len()).then(|| (index, ranges.get(index).start));
+
+    [before, after]
+        .into_iter()
+        .flatten()
+        .min_by_key(|(_, cell_id)| cell_id.abs_diff(hash))
+        .filter(|(_, cell_id)| tolerance.is_none_or(|t| cell_id.abs_diff(hash) as f64 <= t))
+}
+
+fn nearest_by_angular_distance<S: RangeSource>(
+    ranges: &FixedDepthRanges<'_, S>,
+    layer: &Layer,
+    hash: u64,
+    tolerance: Option<f64>,
+) -> Option<(usize, u64)> {
+    if let Some(index) = ranges.find(hash) {
+        return Some((index, hash));
+    } else if ranges.is_empty() {
+        return None;
     }
+
+    let (lon, lat) = layer.center(hash);
+    let center = vec3_of(lon, lat);
+
+    let max_radius = tolerance.map_or(PI, f64::to_radians).min(PI);
+    // start at roughly the size of a cell and widen the search cone until a
+    // cell center is found within the cone
+    let mut radius = (4.0 * PI / layer.n_hash() as f64).sqrt().min(max_radius);
+
+    loop {
+        let nearest = layer
+            .cone_coverage_approx(lon, lat, radius)
+            .into_iter()
+            .flat_map(|cell| cells_within(ranges, layer.depth(), cell.depth, cell.hash))
+            .map(|(index, cell_id)| {
+                let (lon_, lat_) = layer.center(cell_id);
+
+                (index, cell_id, center.ang_dist(&vec3_of(lon_, lat_)))
+            })
+            .filter(|(_, _, distance)| *distance <= radius)
+            .min_by(|a, b| a.2.total_cmp(&b.2));
+
+        if let Some((index, cell_id, _)) = nearest {
+            return Some((index, cell_id));
+        } else if radius >= max_radius {
+            return None;
+        }
+
+        radius = (2.0 * radius).min(max_radius);
+    }
+}
+
+/// The cells of `ranges` contained in the cell `hash` at `cell_depth`
+///
+/// Only the cells that are part of `ranges` are enumerated, together with the
+/// index of the range containing them.
+fn cells_within<'a, S: RangeSource>(
+    ranges: &'a FixedDepthRanges<'a, S>,
+    depth: u8,
+    cell_depth: u8,
+    hash: u64,
+) -> impl Iterator<Item = (usize, u64)> + 'a {
+    let shift = (depth - cell_depth) << 1;
+    let start = hash << shift;
+    let end = (hash + 1) << shift;
+
+    let first = ranges.partition_point(|r| r.end <= start);
+
+    (first..ranges.len())
+        .map(move |index| (index, ranges.get(index)))
+        .take_while(move |(_, r)| r.start < end)
+        .flat_map(move |(index, r)| {
+            (r.start.max(start)..r.end.min(end)).map(move |cell_id| (index, cell_id))
+        })
 }
@@ -0,0 +1,185 @@
+use super::ranges::RangeSource;
+use super::scheme::IndexingScheme;
+use crate::ellipsoid::Ellipsoid;
+use memmap2::Mmap;
+use std::fs::File;
+use std::io::{self, Write};
+use std::ops::Range;
+use std::path::Path;
+
+/// Identifies files containing a memory-mappable region
+const MAGIC: &[u8; 8] = b"HPXGEORG";
+const VERSION: u32 = 1;
+
+/// Size of the fixed part of the header, in bytes
+///
+/// magic (8), version (4), depth (1), indexing scheme (1), padding (2),
+/// number of ranges (8), size of the ellipsoid json (8)
+const HEADER_SIZE: usize = 32;
+
+fn invalid_data(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.to_string())
+}
+
+fn scheme_to_byte(scheme: IndexingScheme) -> u8 {
+    match scheme {
+        IndexingScheme::Nested => 0,
+        IndexingScheme::Ring => 1,
+        IndexingScheme::Zuniq => 2,
+    }
+}
+
+fn scheme_from_byte(byte: u8) -> io::Result<IndexingScheme> {
+    match byte {
+        0 => Ok(IndexingScheme::Nested),
+        1 => Ok(IndexingScheme::Ring),
+        2 => Ok(IndexingScheme::Zuniq),
+        _ => Err(invalid_data("unknown indexing scheme")),
+    }
+}
+
+/// Write the ranges of a region in the memory-mappable format
+///
+/// The ranges follow the header as little-endian `u64` pairs at depth 29,
+/// aligned to 8 bytes.
+pub(crate) fn write<S: RangeSource>(
+    path: &Path,
+    ranges: &S,
+    indexing_scheme: IndexingScheme,
+    ellipsoid: &Ellipsoid,
+) -> io::Result<()> {
+    let ellipsoid_bytes = serde_json::to_vec(ellipsoid).map_err(io::Error::other)?;
+    let padding = ellipsoid_bytes.len().next_multiple_of(8) - ellipsoid_bytes.len();
+
+    let mut writer = io::BufWriter::new(File::create(path)?);
+
+    writer.write_all(MAGIC)?;
+    writer.write_all(&VERSION.to_le_bytes())?;
+    writer.write_all(&[ranges.depth_max(), scheme_to_byte(indexing_scheme), 0, 0])?;
+    writer.write_all(&(ranges.n_ranges() as u64).to_le_bytes())?;
+    writer.write_all(&(ellipsoid_bytes.len() as u64).to_le_bytes())?;
+    writer.write_all(&ellipsoid_bytes)?;
+    writer.write_all(&vec![0; padding])?;
+
+    for range in ranges.iter_ranges() {
+        writer.write_all(&range.start.to_le_bytes())?;
+        writer.write_all(&range.end.to_le_bytes())?;
+    }
+
+    writer.flush()
+}
+
+/// Ranges read directly from a memory-mapped file
+#[derive(Debug)]
+pub(crate) struct MappedRanges {
+    mmap: Mmap,
+    depth: u8,
+    offset: usize,
+    n_ranges: usize,
+}
+
+impl MappedRanges {
+    /// Map the file and validate its header
+    ///
+    /// Returns the ranges together with the indexing scheme and ellipsoid
+    /// stored in the header.
+    pub(crate) fn open(path: &Path) -> io::Result<(Self, IndexingScheme, Ellipsoid)> {
+        let file = File::open(path)?;
+        // SAFETY: the file is only read, but it must not be modified while mapped
+        let mmap = unsafe { Mmap::map(&file)? };
+
+        if mmap.len() < HEADER_SIZE || &mmap[..8] != MAGIC {
+            return Err(invalid_data("not a memory-mappable region file"));
+        }
+
+        let version = u32::from_le_bytes(mmap[8..12].try_into().unwrap());
+        if version != VERSION {
+            return Err(invalid_data("unsupported file version"));
+        }
+
+        let depth = mmap[12];
+        if depth > 29 {
+            return Err(invalid_data("invalid depth"));
+        }
+        let indexing_scheme = scheme_from_byte(mmap[13])?;
+
+        let n_ranges = u64::from_le_bytes(mmap[16..24].try_into().unwrap()) as usize;
+        let ellipsoid_size = u64::from_le_bytes(mmap[24..32].try_into().unwrap()) as usize;
+
+        let offset = HEADER_SIZE
+            .checked_add(ellipsoid_size.next_multiple_of(8))
+            .ok_or_else(|| invalid_data("file size does not match the header"))?;
+        let expected_size = n_ranges
+            .checked_mul(2 * size_of::<u64>())
+            .and_then(|size| size.checked_add(offset));
+        if expected_size != Some(mmap.len()) {
+            return Err(invalid_data("file size does not match the header"));
+        }
+
+        let ellipsoid: Ellipsoid =
+            serde_json::from_slice(&mmap[HEADER_SIZE..HEADER_SIZE + ellipsoid_size])
+                .map_err(|_| invalid_data("invalid ellipsoid"))?;
+
+        let ranges = Self {
+            mmap,
+            depth,
+            offset,
+            n_ranges,
+        };
+        ranges.validate()?;
+
+        Ok((ranges, indexing_scheme, ellipsoid))
+    }
+
+    /// Check that the ranges are sorted, non-overlapping and valid at `depth`
+    fn validate(&self) -> io::Result<()> {
+        let n_cells = 12u64 << (2 * 29);
+        let cell_size = 1u64 << (2 * (29 - self.depth));
+
+        let mut previous_end = 0;
+        for range in self.iter_ranges() {
+            if range.start >= range.end || range.end > n_cells {
+                return Err(invalid_data("invalid range"));
+            }
+            if range.start % cell_size != 0 || range.end % cell_size != 0 {
+                return Err(invalid_data("range does not match the depth"));
+            }
+            if range.start < previous_end {
+                return Err(invalid_data("ranges are unsorted or overlapping"));
+            }
+
+            previous_end = range.end;
+        }
+
+        Ok(())
+    }
+
+    /// The little-endian range bounds, without copying
+    fn bounds(&self) -> &[[u8; 8]] {
+        let (bounds, _) = self.mmap[self.offset..].as_chunks::<8>();
+
+        bounds
+    }
+}
+
+impl RangeSource for MappedRanges {
+    fn depth_max(&self) -> u8 {
+        self.depth
+    }
+
+    fn n_ranges(&self) -> usize {
+        self.n_ranges
+    }
+
+    fn range_at(&self, index: usize) -> Range<u64> {
+        let bounds = self.bounds();
+
+        u64::from_le_bytes(bounds[2 * index])..u64::from_le_bytes(bounds[2 * index + 1])
+    }
+
+    fn iter_ranges(&self) -> impl Iterator<Item = Range<u64>> + '_ {
+        self.bounds()
+            .chunks_exact(2)
+            .map(|pair| u64::from_le_bytes(pair[0])..u64::from_le_bytes(pair[1]))
+    }
+}
@@ -1,12 +1,25 @@
+mod builder;
+mod chunk;
 mod geometry;
 mod indexers;
 mod indexing;
+mod mapped;
 mod ops;
+mod ranges;
 mod region;
+mod scheme;
 mod set;
+mod summary;
 
+pub use self::builder::CellRegionBuilder;
+pub use self::chunk::RegionChunk;
 pub use self::geometry::GeometryQuery;
-pub use self::indexers::{Array, ConcreteSlice, LabelIndexer, PositionalIndexer, Slice};
+pub use self::indexers::{
+    Array, ConcreteSlice, DistanceMetric, LabelIndexer, PositionalIndexer, SelectionMethod, Slice,
+};
 pub use self::indexing::{Indexing, LabelIndexing, PositionIndexing};
+pub use self::ranges::RangeSource;
 pub use self::region::CellRegion;
-pub use self::set::SetOperations;
+pub use self::scheme::{IndexingScheme, LabelError};
+pub use self::set::{EllipsoidAlignment, SetOperations};
+pub use self::summary::RegionSummary;
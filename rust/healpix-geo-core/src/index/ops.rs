@@ -1,5 +1,5 @@
 use super::indexers::ConcreteSlice;
-use super::indexing::range_offsets;
+use super::ranges::{FixedDepthRanges, RangeSource, set_operation};
 use moc::moc::range::RangeMOC;
 use moc::qty::Hpx;
 
@@ -8,30 +8,36 @@ pub(crate) enum JoinOp {
 }
 
 pub(crate) trait JoinOps {
-    fn join(&self, other: &Self, method: JoinOp) -> (Vec<ConcreteSlice<isize>>, Self);
+    fn join<O: RangeSource>(
+        &self,
+        other: &O,
+        method: JoinOp,
+    ) -> (Vec<ConcreteSlice<isize>>, RangeMOC<u64, Hpx<u64>>);
 }
 
-impl JoinOps for RangeMOC<u64, Hpx<u64>> {
-    fn join(&self, other: &Self, method: JoinOp) -> (Vec<ConcreteSlice<isize>>, Self) {
+impl<S: RangeSource> JoinOps for S {
+    fn join<O: RangeSource>(
+        &self,
+        other: &O,
+        method: JoinOp,
+    ) -> (Vec<ConcreteSlice<isize>>, RangeMOC<u64, Hpx<u64>>) {
         let depth = self.depth_max();
         let relative_depth = 29 - depth;
         let shift = relative_depth << 1;
 
-        let offsets: Vec<usize> = range_offsets(self);
+        let offsets = self.range_offsets();
 
-        let new_region = self.intersection(other);
+        let new_region = set_operation(self, other, |a, b| a && b);
 
         let slices = match method {
             JoinOp::Intersection => other
-                .moc_ranges()
-                .iter()
+                .iter_ranges()
                 .filter_map(|range_o| {
                     let start_o = range_o.start >> shift;
                     let end_o = range_o.end >> shift;
 
                     let slices: Vec<_> = self
-                        .moc_ranges()
-                        .iter()
+                        .iter_ranges()
                         .enumerate()
                         .filter_map(|(index, range_s)| {
                             let start_s = range_s.start >> shift;
@@ -66,3 +72,31 @@ impl JoinOps for RangeMOC<u64, Hpx<u64>> {
         (slices, new_region)
     }
 }
+
+/// Pair every cell of `fine` with the cell of `coarse` containing it
+///
+/// Returns the positions within `fine` and `coarse`, respectively. The depth of
+/// `fine` must not be smaller than the depth of `coarse`.
+pub(crate) fn containment_pairs<F: RangeSource, C: RangeSource>(
+    fine: &F,
+    coarse: &C,
+) -> Vec<(usize, usize)> {
+    let shift = (fine.depth_max() - coarse.depth_max()) << 1;
+
+    let coarse_ranges = FixedDepthRanges::new(coarse);
+    let coarse_offsets = coarse.range_offsets();
+
+    fine.flatten_to_fixed_depth_cells()
+        .enumerate()
+        .filter_map(|(position, hash)| {
+            let parent = hash >> shift;
+
+            coarse_ranges.find(parent).map(|index| {
+                let coarse_position =
+                    (parent - coarse_ranges.get(index).start) as usize + coarse_offsets[index];
+
+                (position, coarse_position)
+            })
+        })
+        .collect()
+}
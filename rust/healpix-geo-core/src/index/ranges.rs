@@ -0,0 +1,272 @@
+use moc::elemset::range::MocRanges;
+use moc::moc::range::RangeMOC;
+use moc::qty::Hpx;
+use std::borrow::Cow;
+use std::ops::Range;
+
+/// Read access to the sorted, non-overlapping cell ranges of a region
+///
+/// Like in `moc`, the ranges are expressed at depth 29.
+pub trait RangeSource {
+    fn depth_max(&self) -> u8;
+    fn n_ranges(&self) -> usize;
+    fn range_at(&self, index: usize) -> Range<u64>;
+
+    fn iter_ranges(&self) -> impl Iterator<Item = Range<u64>> + '_ {
+        (0..self.n_ranges()).map(|index| self.range_at(index))
+    }
+
+    fn n_depth_max_cells(&self) -> u64 {
+        let shift = (29 - self.depth_max()) << 1;
+
+        self.iter_ranges().map(|r| (r.end - r.start) >> shift).sum()
+    }
+
+    fn flatten_to_fixed_depth_cells(&self) -> impl Iterator<Item = u64> + '_ {
+        let shift = (29 - self.depth_max()) << 1;
+
+        self.iter_ranges()
+            .flat_map(move |r| (r.start >> shift)..(r.end >> shift))
+    }
+
+    /// The position of the first cell of each range
+    ///
+    /// Sources that are queried repeatedly can cache the offsets.
+    fn range_offsets(&self) -> Cow<'_, [usize]> {
+        let shift = (29 - self.depth_max()) << 1;
+
+        Cow::Owned(
+            self.iter_ranges()
+                .map(|r| ((r.end - r.start) >> shift) as usize)
+                .scan(0, |state, x| {
+                    let val = *state;
+                    *state += x;
+                    Some(val)
+                })
+                .collect(),
+        )
+    }
+
+    /// Copy the ranges into a `RangeMOC`
+    fn to_range_moc(&self) -> RangeMOC<u64, Hpx<u64>> {
+        RangeMOC::new(
+            self.depth_max(),
+            MocRanges::new_from(self.iter_ranges().collect()),
+        )
+    }
+}
+
+/// The ranges of a source, expressed at its maximum depth
+///
+/// The ranges are shifted when accessed instead of being copied.
+pub(crate) struct FixedDepthRanges<'a, S: RangeSource> {
+    source: &'a S,
+    shift: u8,
+}
+
+impl<'a, S: RangeSource> FixedDepthRanges<'a, S> {
+    pub(crate) fn new(source: &'a S) -> Self {
+        Self {
+            source,
+            shift: (29 - source.depth_max()) << 1,
+        }
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.source.n_ranges()
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub(crate) fn get(&self, index: usize) -> Range<u64> {
+        let range = self.source.range_at(index);
+
+        (range.start >> self.shift)..(range.end >> self.shift)
+    }
+
+    pub(crate) fn iter(&self) -> impl Iterator<Item = Range<u64>> + '_ {
+        self.source
+            .iter_ranges()
+            .map(|range| (range.start >> self.shift)..(range.end >> self.shift))
+    }
+
+    /// Index of the first range for which `predicate` is false
+    ///
+    /// Like `slice::partition_point`, the predicate has to be true for all
+    /// ranges before that index and false for all ranges after it.
+    pub(crate) fn partition_point<P>(&self, predicate: P) -> usize
+    where
+        P: Fn(&Range<u64>) -> bool,
+    {
+        let mut low = 0;
+        let mut high = self.len();
+
+        while low < high {
+            let middle = low + (high - low) / 2;
+            if predicate(&self.get(middle)) {
+                low = middle + 1;
+            } else {
+                high = middle;
+            }
+        }
+
+        low
+    }
+
+    /// Index of the range containing `hash`, if any
+    ///
+    /// `hash` has to be at the maximum depth of the source.
+    pub(crate) fn find(&self, hash: u64) -> Option<usize> {
+        let index = self.partition_point(|r| r.end <= hash);
+
+        (index < self.len() && self.get(index).start <= hash).then_some(index)
+    }
+}
+
+impl RangeSource for RangeMOC<u64, Hpx<u64>> {
+    fn depth_max(&self) -> u8 {
+        RangeMOC::depth_max(self)
+    }
+
+    fn n_ranges(&self) -> usize {
+        self.moc_ranges().iter().len()
+    }
+
+    fn range_at(&self, index: usize) -> Range<u64> {
+        self.moc_ranges()[index].clone()
+    }
+
+    fn iter_ranges(&self) -> impl Iterator<Item = Range<u64>> + '_ {
+        self.moc_ranges().iter().cloned()
+    }
+
+    fn to_range_moc(&self) -> RangeMOC<u64, Hpx<u64>> {
+        self.clone()
+    }
+}
+
+/// Combine two sorted sequences of non-overlapping ranges
+///
+/// `op` decides, given whether a position is in the first and in the second
+/// sequence, whether it is part of the result.
+pub(crate) fn combine_ranges<A, B, F>(first: A, second: B, op: F) -> Vec<Range<u64>>
+where
+    A: Iterator<Item = Range<u64>>,
+    B: Iterator<Item = Range<u64>>,
+    F: Fn(bool, bool) -> bool,
+{
+    let mut first = first.flat_map(|r| [r.start, r.end]).peekable();
+    let mut second = second.flat_map(|r| [r.start, r.end]).peekable();
+
+    let mut in_first = false;
+    let mut in_second = false;
+    let mut current_start: Option<u64> = None;
+
+    let mut result = Vec::<Range<u64>>::new();
+    loop {
+        let boundary = match (first.peek(), second.peek()) {
+            (None, None) => break,
+            (Some(&b), None) | (None, Some(&b)) => b,
+            (Some(&a), Some(&b)) => a.min(b),
+        };
+
+        // touching ranges toggle twice, which merges them
+        while first.next_if_eq(&boundary).is_some() {
+            in_first = !in_first;
+        }
+        while second.next_if_eq(&boundary).is_some() {
+            in_second = !in_second;
+        }
+
+        match (current_start, op(in_first, in_second)) {
+            (None, true) => current_start = Some(boundary),
+            (Some(start), false) => {
+                result.push(start..boundary);
+                current_start = None;
+            }
+            _ => {}
+        }
+    }
+
+    result
+}
+
+/// Apply a set operation to two range sources
+pub(crate) fn set_operation<A, B, F>(first: &A, second: &B, op: F) -> RangeMOC<u64, Hpx<u64>>
+where
+    A: RangeSource,
+    B: RangeSource,
+    F: Fn(bool, bool) -> bool,
+{
+    let depth = first.depth_max().max(second.depth_max());
+    let ranges = combine_ranges(first.iter_ranges(), second.iter_ranges(), op);
+
+    RangeMOC::new(depth, MocRanges::new_from(ranges))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_combine_ranges() {
+        let first = vec![0..4, 6..10, 12..13];
+        let second = vec![2..7, 9..12];
+
+        let union = combine_ranges(
+            first.clone().into_iter(),
+            second.clone().into_iter(),
+            |a, b| a || b,
+        );
+        assert_eq!(union, vec![0..13]);
+
+        let intersection = combine_ranges(
+            first.clone().into_iter(),
+            second.clone().into_iter(),
+            |a, b| a && b,
+        );
+        assert_eq!(intersection, vec![2..4, 6..7, 9..10]);
+
+        let difference = combine_ranges(
+            first.clone().into_iter(),
+            second.clone().into_iter(),
+            |a, b| a && !b,
+        );
+        assert_eq!(difference, vec![0..2, 7..9, 12..13]);
+
+        let symmetric_difference =
+            combine_ranges(first.into_iter(), second.into_iter(), |a, b| a != b);
+        assert_eq!(symmetric_difference, vec![0..2, 4..6, 7..9, 10..13]);
+    }
+
+    #[test]
+    fn test_combine_ranges_touching() {
+        let first = vec![0..2, 2..4];
+        let second = vec![];
+
+        let actual = combine_ranges(first.into_iter(), second.into_iter(), |a, b| a || b);
+        assert_eq!(actual, vec![0..4]);
+    }
+
+    #[test]
+    fn test_fixed_depth_ranges() {
+        let depth = 2;
+        let shift = (29 - depth) << 1;
+        let moc: RangeMOC<u64, Hpx<u64>> = RangeMOC::new(
+            depth,
+            MocRanges::new_from(vec![(1 << shift)..(3 << shift), (7 << shift)..(8 << shift)]),
+        );
+
+        let ranges = FixedDepthRanges::new(&moc);
+
+        assert_eq!(ranges.iter().collect::<Vec<_>>(), vec![1..3, 7..8]);
+        assert_eq!(moc.range_offsets().as_ref(), &[0, 2]);
+        assert_eq!(ranges.find(0), None);
+        assert_eq!(ranges.find(2), Some(0));
+        assert_eq!(ranges.find(5), None);
+        assert_eq!(ranges.find(7), Some(1));
+        assert_eq!(ranges.find(8), None);
+    }
+}
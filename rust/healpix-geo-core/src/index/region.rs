@@ -1,50 +1,232 @@
+use super::chunk::{RegionChunk, group_by_parent};
 use super::geometry::GeometryQuery;
-use super::indexers::{Array, ConcreteSlice, LabelIndexer, PositionalIndexer, Slice};
+use super::indexers::{
+    Array, ConcreteSlice, LabelIndexer, PositionalIndexer, SelectionMethod, Slice,
+};
 use super::indexing::{Indexing, LabelIndexing, PositionIndexing};
-use super::ops::{JoinOp, JoinOps};
-use super::set::SetOperations;
+use super::mapped::{self, MappedRanges};
+use super::ops::{JoinOp, JoinOps, containment_pairs};
+use super::ranges::{FixedDepthRanges, RangeSource, set_operation};
+use super::scheme::{IndexingScheme, LabelError, RingOrder};
+use super::set::{EllipsoidAlignment, SetOperations};
+use super::summary::RegionSummary;
 use crate::ellipsoid::{Ellipsoid, ReferenceBody};
 use crate::geometry::Geometry;
 use crate::scalar;
 use cdshealpix::nested;
+use cdshealpix::nested::Layer;
+use itertools::Either;
 use moc::deser::json::from_json_aladin;
+use moc::elemset::range::MocRanges;
 use moc::moc::cell::CellMOC;
 use moc::moc::range::{CellSelection, RangeMOC};
 use moc::moc::{
     CellMOCIntoIterator, CellMOCIterator, HasMaxDepth, RangeMOCIntoIterator, RangeMOCIterator,
 };
 use moc::qty::Hpx;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::borrow::Cow;
+use std::io;
+use std::ops::Range;
+use std::path::Path;
+use std::sync::{Arc, OnceLock};
+
+/// The ranges of a region, either in memory or mapped from a file
+#[derive(Debug, Clone)]
+enum StoredRanges {
+    Owned(RangeMOC<u64, Hpx<u64>>),
+    Mapped(Arc<MappedRanges>),
+}
+
+impl RangeSource for StoredRanges {
+    fn depth_max(&self) -> u8 {
+        match self {
+            StoredRanges::Owned(moc) => RangeSource::depth_max(moc),
+            StoredRanges::Mapped(ranges) => ranges.depth_max(),
+        }
+    }
+
+    fn n_ranges(&self) -> usize {
+        match self {
+            StoredRanges::Owned(moc) => moc.n_ranges(),
+            StoredRanges::Mapped(ranges) => ranges.n_ranges(),
+        }
+    }
+
+    fn range_at(&self, index: usize) -> Range<u64> {
+        match self {
+            StoredRanges::Owned(moc) => moc.range_at(index),
+            StoredRanges::Mapped(ranges) => ranges.range_at(index),
+        }
+    }
+
+    fn iter_ranges(&self) -> impl Iterator<Item = Range<u64>> + '_ {
+        match self {
+            StoredRanges::Owned(moc) => Either::Left(moc.iter_ranges()),
+            StoredRanges::Mapped(ranges) => Either::Right(ranges.iter_ranges()),
+        }
+    }
+
+    fn to_range_moc(&self) -> RangeMOC<u64, Hpx<u64>> {
+        match self {
+            StoredRanges::Owned(moc) => moc.clone(),
+            StoredRanges::Mapped(ranges) => ranges.to_range_moc(),
+        }
+    }
+}
+
+/// The ranges of a region, with the position of the first cell of each range
+///
+/// The offsets are computed on first use and shared between clones, so
+/// positional lookups don't walk all ranges every time.
+#[derive(Debug, Clone)]
+struct Storage {
+    ranges: StoredRanges,
+    offsets: Arc<OnceLock<Vec<usize>>>,
+}
+
+impl Storage {
+    fn owned(moc: RangeMOC<u64, Hpx<u64>>) -> Self {
+        Self::new(StoredRanges::Owned(moc))
+    }
+
+    fn mapped(ranges: MappedRanges) -> Self {
+        Self::new(StoredRanges::Mapped(Arc::new(ranges)))
+    }
+
+    fn new(ranges: StoredRanges) -> Self {
+        Self {
+            ranges,
+            offsets: Arc::new(OnceLock::new()),
+        }
+    }
+}
+
+impl RangeSource for Storage {
+    fn depth_max(&self) -> u8 {
+        self.ranges.depth_max()
+    }
+
+    fn n_ranges(&self) -> usize {
+        self.ranges.n_ranges()
+    }
+
+    fn range_at(&self, index: usize) -> Range<u64> {
+        self.ranges.range_at(index)
+    }
+
+    fn iter_ranges(&self) -> impl Iterator<Item = Range<u64>> + '_ {
+        self.ranges.iter_ranges()
+    }
+
+    fn range_offsets(&self) -> Cow<'_, [usize]> {
+        let offsets = self
+            .offsets
+            .get_or_init(|| self.ranges.range_offsets().into_owned());
+
+        Cow::Borrowed(offsets.as_slice())
+    }
+
+    fn to_range_moc(&self) -> RangeMOC<u64, Hpx<u64>> {
+        self.ranges.to_range_moc()
+    }
+}
+
+impl PartialEq for Storage {
+    fn eq(&self, other: &Self) -> bool {
+        self.depth_max() == other.depth_max()
+            && self.n_ranges() == other.n_ranges()
+            && self.iter_ranges().eq(other.iter_ranges())
+    }
+}
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct CellRegion {
-    moc: RangeMOC<u64, Hpx<u64>>,
+    moc: Storage,
+    indexing_scheme: IndexingScheme,
     ellipsoid: Ellipsoid,
 }
 
 impl CellRegion {
     pub fn full_domain(depth: u8, ellipsoid: Ellipsoid) -> Self {
+        Self::full_domain_with_scheme(depth, IndexingScheme::Nested, ellipsoid)
+    }
+
+    pub fn empty(depth: u8, ellipsoid: Ellipsoid) -> Self {
+        Self::empty_with_scheme(depth, IndexingScheme::Nested, ellipsoid)
+    }
+
+    pub fn from_cell_ids(depth: u8, cell_ids: Vec<u64>, ellipsoid: Ellipsoid) -> Self {
+        let moc = RangeMOC::from_fixed_depth_cells(depth, cell_ids.into_iter(), None);
+
+        Self::from_moc(moc, IndexingScheme::Nested, ellipsoid)
+    }
+
+    /// Create a full domain region labelled with the given indexing scheme
+    pub fn full_domain_with_scheme(
+        depth: u8,
+        indexing_scheme: IndexingScheme,
+        ellipsoid: Ellipsoid,
+    ) -> Self {
         Self {
-            moc: RangeMOC::new_full_domain(depth),
+            moc: Storage::owned(RangeMOC::new_full_domain(depth)),
+            indexing_scheme,
             ellipsoid,
         }
     }
 
-    pub fn empty(depth: u8, ellipsoid: Ellipsoid) -> Self {
+    /// Create an empty region labelled with the given indexing scheme
+    pub fn empty_with_scheme(
+        depth: u8,
+        indexing_scheme: IndexingScheme,
+        ellipsoid: Ellipsoid,
+    ) -> Self {
         Self {
-            moc: RangeMOC::new_empty(depth),
+            moc: Storage::owned(RangeMOC::new_empty(depth)),
+            indexing_scheme,
             ellipsoid,
         }
     }
 
-    pub fn from_cell_ids(depth: u8, cell_ids: Vec<u64>, ellipsoid: Ellipsoid) -> Self {
+    /// Create a region from cell ids following the given indexing scheme
+    ///
+    /// Fails if a cell id can't be converted to a cell at `depth`.
+    pub fn from_cell_ids_with_scheme(
+        depth: u8,
+        mut cell_ids: Vec<u64>,
+        indexing_scheme: IndexingScheme,
+        ellipsoid: Ellipsoid,
+    ) -> Result<Self, LabelError> {
+        for label in cell_ids.iter_mut() {
+            *label = indexing_scheme.to_nested(*label, depth)?;
+        }
+
+        Ok(Self {
+            moc: Storage::owned(RangeMOC::from_fixed_depth_cells(
+                depth,
+                cell_ids.into_iter(),
+                None,
+            )),
+            indexing_scheme,
+            ellipsoid,
+        })
+    }
+
+    pub(crate) fn from_moc(
+        moc: RangeMOC<u64, Hpx<u64>>,
+        indexing_scheme: IndexingScheme,
+        ellipsoid: Ellipsoid,
+    ) -> Self {
         Self {
-            moc: RangeMOC::from_fixed_depth_cells(depth, cell_ids.into_iter(), None),
+            moc: Storage::owned(moc),
+            indexing_scheme,
             ellipsoid,
         }
     }
 
     pub fn nbytes(&self) -> usize {
-        self.moc.len() * 2 * u64::BITS as usize / 8
+        self.moc.n_ranges() * 2 * u64::BITS as usize / 8
     }
 
     pub fn size(&self) -> usize {
@@ -55,23 +237,46 @@ impl CellRegion {
         self.moc.depth_max()
     }
 
+    /// The cell ids in the indexing scheme of the region, in positional order
+    ///
+    /// The positions follow the sorted cell ids of the indexing scheme.
     pub fn cell_ids(&self) -> Vec<u64> {
-        self.moc.flatten_to_fixed_depth_cells().collect()
+        let depth = self.depth();
+
+        let mut cell_ids: Vec<u64> = self
+            .moc
+            .flatten_to_fixed_depth_cells()
+            .map(|hash| self.indexing_scheme.from_nested(hash, depth))
+            .collect();
+        if self.indexing_scheme == IndexingScheme::Ring {
+            cell_ids.sort_unstable();
+        }
+
+        cell_ids
     }
 
     pub fn cells_at_depth(&self) -> u64 {
         12 * 4u64.pow(self.depth() as u32)
     }
 
+    pub fn indexing_scheme(&self) -> IndexingScheme {
+        self.indexing_scheme
+    }
+
     pub fn ellipsoid(&self) -> &Ellipsoid {
         &self.ellipsoid
     }
 
+    /// Serialize the region
+    ///
+    /// The cells are written by `moc`, which needs a `RangeMOC`: the ranges are
+    /// copied once, which is intended since the serialized cells are larger
+    /// than the ranges anyway.
     pub fn to_bytes(&self) -> Vec<u8> {
         let mut moc_bytes: Vec<u8> = Default::default();
 
         self.moc
-            .clone()
+            .to_range_moc()
             .into_range_moc_iter()
             .cells()
             .to_json_aladin(None, &mut moc_bytes)
@@ -81,14 +286,95 @@ impl CellRegion {
         let ellipsoid_bytes = serde_json::to_vec(&self.ellipsoid).unwrap();
         let sizes: Vec<usize> = vec![moc_bytes.len(), ellipsoid_bytes.len()];
 
+        // the indexing scheme is appended last so older data (without it) can still be read
+        let scheme_bytes = serde_json::to_vec(&self.indexing_scheme).unwrap();
+
         sizes
             .into_iter()
             .flat_map(usize::to_le_bytes)
             .chain(moc_bytes)
             .chain(ellipsoid_bytes)
+            .chain(scheme_bytes)
             .collect()
     }
 
+    /// Compute statistics about the fragmentation of the region
+    pub fn summary(&self) -> RegionSummary {
+        let depth = self.depth();
+        let ranges = FixedDepthRanges::new(&self.moc);
+
+        let largest_run = ranges.iter().map(|r| r.end - r.start).max().unwrap_or(0);
+        let smallest_run = ranges.iter().map(|r| r.end - r.start).min().unwrap_or(0);
+
+        let cells_per_base = 4u64.pow(depth as u32);
+        let mut cells_per_base_cell = [0u64; 12];
+        for range in ranges.iter() {
+            let first_base = range.start / cells_per_base;
+            let last_base = (range.end - 1) / cells_per_base;
+
+            for base in first_base..=last_base {
+                let start = range.start.max(base * cells_per_base);
+                let end = range.end.min((base + 1) * cells_per_base);
+
+                cells_per_base_cell[base as usize] += end - start;
+            }
+        }
+
+        let size = self.moc.n_depth_max_cells();
+
+        RegionSummary {
+            depth,
+            size,
+            n_ranges: ranges.len(),
+            largest_run,
+            smallest_run,
+            coverage_fraction: size as f64 / self.cells_at_depth() as f64,
+            cells_per_base_cell,
+            serialized_size: self.serialized_size(),
+        }
+    }
+
+    /// An estimate of the size of the output of `to_bytes`
+    ///
+    /// The moc is not serialized: its size is derived from the digits of the
+    /// cells it decomposes into, with a fixed overhead per depth.
+    fn serialized_size(&self) -> usize {
+        let mut cells_per_depth = [0usize; 30];
+        let mut digits_per_depth = [0usize; 30];
+
+        for range in self.moc.iter_ranges() {
+            let mut start = range.start;
+            while start < range.end {
+                // the largest cell starting at `start` and contained in the range
+                let delta_depth = (start.trailing_zeros() / 2)
+                    .min((range.end - start).ilog2() / 2)
+                    .min(29);
+                let depth = 29 - delta_depth as usize;
+                let hash = start >> (2 * delta_depth);
+
+                cells_per_depth[depth] += 1;
+                digits_per_depth[depth] += n_digits(hash);
+
+                start += 1 << (2 * delta_depth);
+            }
+        }
+
+        // `{` and `}`, then `"depth":[` and `],` around the comma-separated
+        // cells of each depth. The maximum depth is always written.
+        let moc_size: usize = 2
+            + (0..30)
+                .filter(|&depth| cells_per_depth[depth] > 0 || depth == self.depth() as usize)
+                .map(|depth| {
+                    n_digits(depth as u64) + 6 + cells_per_depth[depth] + digits_per_depth[depth]
+                })
+                .sum::<usize>();
+
+        let ellipsoid_size = serde_json::to_vec(&self.ellipsoid).unwrap().len();
+        let scheme_size = serde_json::to_vec(&self.indexing_scheme).unwrap().len();
+
+        2 * usize::BITS as usize / 8 + moc_size + ellipsoid_size + scheme_size
+    }
+
     pub fn from_bytes(bytes: &[u8]) -> Self {
         // extract the json_aladin and the ellipsoid data, deserialize both
         let n_bytes: usize = usize::BITS as usize / 8;
@@ -97,10 +383,13 @@ impl CellRegion {
             .map(|c| usize::from_le_bytes(c.try_into().unwrap()))
             .collect();
 
-        let cell_moc: CellMOC<u64, Hpx<u64>> = from_json_aladin(
-            std::str::from_utf8(&bytes[2 * n_bytes..2 * n_bytes + sizes[0]]).unwrap(),
-        )
-        .unwrap();
+        let moc_start = 2 * n_bytes;
+        let ellipsoid_start = moc_start + sizes[0];
+        let scheme_start = ellipsoid_start + sizes[1];
+
+        let cell_moc: CellMOC<u64, Hpx<u64>> =
+            from_json_aladin(std::str::from_utf8(&bytes[moc_start..ellipsoid_start]).unwrap())
+                .unwrap();
         let reconstructed_moc = RangeMOC::from_cells(
             cell_moc.depth_max(),
             cell_moc
@@ -110,205 +399,742 @@ impl CellRegion {
         );
 
         let ellipsoid: Ellipsoid =
-            serde_json::from_slice(&bytes[2 * n_bytes + sizes[0]..]).unwrap();
+            serde_json::from_slice(&bytes[ellipsoid_start..scheme_start]).unwrap();
+
+        let indexing_scheme: IndexingScheme = if bytes.len() > scheme_start {
+            serde_json::from_slice(&bytes[scheme_start..]).unwrap()
+        } else {
+            IndexingScheme::Nested
+        };
 
         Self {
-            moc: reconstructed_moc,
+            moc: Storage::owned(reconstructed_moc),
+            indexing_scheme,
             ellipsoid,
         }
     }
-}
 
-impl SetOperations for CellRegion {
-    fn union(&self, other: &Self) -> Self {
-        if other.ellipsoid != self.ellipsoid {
-            // TODO: custom error type
-            panic!("ellipsoids don't match");
-        }
+    /// Write the region to a file that can be memory-mapped by `open_mapped`
+    pub fn write_mapped(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        mapped::write(
+            path.as_ref(),
+            &self.moc,
+            self.indexing_scheme,
+            &self.ellipsoid,
+        )
+    }
 
-        Self {
-            moc: self.moc.union(&other.moc),
-            ellipsoid: self.ellipsoid.clone(),
-        }
+    /// Open a region written by `write_mapped` without copying its ranges
+    ///
+    /// The file is memory-mapped and must not be modified while the region
+    /// (or any region sharing its ranges) is alive. Operations producing new
+    /// regions return regular, in-memory regions.
+    pub fn open_mapped(path: impl AsRef<Path>) -> io::Result<Self> {
+        let (ranges, indexing_scheme, ellipsoid) = MappedRanges::open(path.as_ref())?;
+
+        Ok(Self {
+            moc: Storage::mapped(ranges),
+            indexing_scheme,
+            ellipsoid,
+        })
     }
 
-    fn intersection(&self, other: &Self) -> Self {
+    /// Whether the ranges are memory-mapped from a file
+    pub fn is_mapped(&self) -> bool {
+        matches!(self.moc.ranges, StoredRanges::Mapped(_))
+    }
+
+    /// Match the cells of two regions with possibly different depths
+    ///
+    /// Every cell of the finer region is paired with the cell of the coarser
+    /// region containing it. Cells without a match are skipped.
+    ///
+    /// Returns pairs of positions, `(position in self, position in other)`,
+    /// sorted by position in `self`.
+    pub fn join(&self, other: &Self) -> Vec<(usize, usize)> {
+        check_same_body(&self.ellipsoid, &other.ellipsoid);
+        check_same_scheme(self.indexing_scheme, other.indexing_scheme);
         if other.ellipsoid != self.ellipsoid {
             // TODO: custom error type
             panic!("ellipsoids don't match");
         }
 
-        Self {
-            moc: self.moc.intersection(&other.moc),
-            ellipsoid: self.ellipsoid.clone(),
+        let pairs: Vec<(usize, usize)> = if self.depth() >= other.depth() {
+            containment_pairs(&self.moc, &other.moc)
+        } else {
+            // the nested order is preserved by the parent relation, so the
+            // swapped pairs are still sorted
+            containment_pairs(&other.moc, &self.moc)
+                .into_iter()
+                .map(|(fine, coarse)| (coarse, fine))
+                .collect()
+        };
+
+        match (self.ring_order(), other.ring_order()) {
+            (Some(order), Some(other_order)) => {
+                let mut pairs: Vec<(usize, usize)> = pairs
+                    .into_iter()
+                    .map(|(first, second)| (order.to_ring(first), other_order.to_ring(second)))
+                    .collect();
+                pairs.sort_unstable();
+
+                pairs
+            }
+            _ => pairs,
         }
     }
 
-    fn difference(&self, other: &Self) -> Self {
-        if other.ellipsoid != self.ellipsoid {
-            // TODO: custom error type
-            panic!("ellipsoids don't match");
+    /// Whether the cells of both regions are within `tolerance` meters
+    ///
+    /// See `Ellipsoid::is_compatible`.
+    pub fn is_compatible(&self, other: &Self, tolerance: f64) -> bool {
+        self.ellipsoid.is_compatible(&other.ellipsoid, tolerance)
+    }
+
+    /// Move the region onto another ellipsoid
+    ///
+    /// A cell of the new ellipsoid is part of the result if its center lies
+    /// within the region when matching the geographic latitudes of both
+    /// ellipsoids through their authalic latitudes. This keeps the covered
+    /// area, without holes where the cells of both layouts don't line up.
+    pub fn resample(&self, ellipsoid: &Ellipsoid) -> Self {
+        check_same_body(&self.ellipsoid, ellipsoid);
+
+        if *ellipsoid == self.ellipsoid {
+            return self.clone();
         }
 
+        let depth = self.depth();
+        let layer = nested::get(depth);
+        let ranges = FixedDepthRanges::new(&self.moc);
+
+        // all points of a cell move by about the same amount, so the cells
+        // containing them are at most one cell away from the cell containing
+        // the moved center
+        let mut candidates: Vec<u64> = self
+            .moc
+            .flatten_to_fixed_depth_cells()
+            .flat_map(|hash| {
+                let geographic = self.ellipsoid.authalic_to_geographic(&layer.center(hash));
+                let (lon, lat) = ellipsoid.geographic_to_authalic(&geographic);
+                let target = layer.hash(lon, lat);
+
+                std::iter::once(target).chain(layer.kth_neighbours(target, 1))
+            })
+            .collect();
+        candidates.sort_unstable();
+        candidates.dedup();
+
+        let hashes = candidates.into_iter().filter(|&hash| {
+            let geographic = ellipsoid.authalic_to_geographic(&layer.center(hash));
+            let (lon, lat) = self.ellipsoid.geographic_to_authalic(&geographic);
+
+            ranges.find(layer.hash(lon, lat)).is_some()
+        });
+
         Self {
-            moc: self.moc.minus(&other.moc),
-            ellipsoid: self.ellipsoid.clone(),
+            moc: Storage::owned(RangeMOC::from_fixed_depth_cells(depth, hashes, None)),
+            indexing_scheme: self.indexing_scheme,
+            ellipsoid: ellipsoid.clone(),
         }
     }
 
-    fn symmetric_difference(&self, other: &Self) -> Self {
-        if other.ellipsoid != self.ellipsoid {
-            // TODO: custom error type
-            panic!("ellipsoids don't match");
+    /// Convert the region to the ellipsoid of `other`, so both can be combined
+    pub fn align_to(&self, other: &Self, alignment: EllipsoidAlignment) -> Self {
+        check_same_body(&self.ellipsoid, &other.ellipsoid);
+
+        if self.ellipsoid == other.ellipsoid {
+            return self.clone();
         }
 
-        Self {
-            moc: self.moc.xor(&other.moc),
-            ellipsoid: self.ellipsoid.clone(),
+        match alignment {
+            EllipsoidAlignment::Exact => {
+                // TODO: custom error type
+                panic!("ellipsoids don't match");
+            }
+            EllipsoidAlignment::Tolerance(tolerance) => {
+                if !self.is_compatible(other, tolerance) {
+                    // TODO: custom error type
+                    panic!("ellipsoids don't match within {tolerance} m");
+                }
+
+                Self {
+                    ellipsoid: other.ellipsoid.clone(),
+                    ..self.clone()
+                }
+            }
+            EllipsoidAlignment::Resample => self.resample(&other.ellipsoid),
         }
     }
-}
 
-impl Indexing for CellRegion {
-    fn sel(&self, indexer: &LabelIndexer) -> (Self, PositionalIndexer) {
-        let (subset, positional_indexer): (RangeMOC<u64, Hpx<u64>>, PositionalIndexer) =
-            match indexer {
-                LabelIndexer::Slice(slice) => {
-                    let concrete_slice = slice.normalize(self.cells_at_depth());
+    /// Split the region into chunks aligned to the cells at `chunk_depth`
+    ///
+    /// Each chunk contains the cells sharing the same parent cell, in
+    /// positional order. Parent cells outside the region are skipped.
+    ///
+    /// The cells sharing a parent cell don't have contiguous positions in
+    /// ring order, so regions labelled with ring ids can't be chunked.
+    pub fn chunk(&self, chunk_depth: u8) -> Vec<RegionChunk> {
+        let depth = self.depth();
+        let shift = self.chunk_shift(chunk_depth);
+        let moc_shift = (29 - depth) << 1;
 
-                    let (subset, positional_slice) = self.moc.label_slice(concrete_slice);
+        let ranges = FixedDepthRanges::new(&self.moc);
+        let offsets = self.moc.range_offsets();
 
-                    (
-                        subset,
-                        PositionalIndexer::Slice(Slice::create(
-                            Some(positional_slice.start as isize),
-                            Some(positional_slice.stop as isize),
-                            Some(positional_slice.step as isize),
-                        )),
-                    )
-                }
-                LabelIndexer::Array(array) => {
-                    let (subset, positional_array) = self.moc.label_index(array);
+        group_by_parent(ranges.iter(), &offsets, shift)
+            .into_iter()
+            .map(|group| {
+                let moc_ranges = group
+                    .ranges
+                    .into_iter()
+                    .map(|r| (r.start << moc_shift)..(r.end << moc_shift))
+                    .collect();
 
-                    (
-                        subset,
-                        PositionalIndexer::Array(Array {
-                            data: positional_array
-                                .data
-                                .into_iter()
-                                .map(|v| v as isize)
-                                .collect::<Vec<_>>(),
-                        }),
-                    )
+                RegionChunk {
+                    depth: chunk_depth,
+                    cell_id: self.indexing_scheme.from_nested(group.parent, chunk_depth),
+                    positions: ConcreteSlice {
+                        start: group.positions.start as isize,
+                        stop: group.positions.end as isize,
+                        step: 1,
+                    },
+                    region: self.with_moc(RangeMOC::new(depth, MocRanges::new_from(moc_ranges))),
                 }
-            };
+            })
+            .collect()
+    }
 
-        let new_region = CellRegion {
-            moc: subset,
-            ellipsoid: self.ellipsoid.clone(),
-        };
+    /// Split the region into chunks of at most `max_size` cells
+    ///
+    /// The chunks are aligned to the coarsest depth whose cells contain at
+    /// most `max_size` cells of the region's depth.
+    pub fn chunk_to_size(&self, max_size: u64) -> Vec<RegionChunk> {
+        if max_size == 0 {
+            // TODO: custom error type
+            panic!("the chunk size must be positive");
+        }
 
-        (new_region, positional_indexer)
+        let depth = self.depth();
+        let depth_difference = (max_size.ilog2() / 2).min(depth as u32) as u8;
+
+        self.chunk(depth - depth_difference)
     }
 
-    fn isel(&self, indexer: &PositionalIndexer) -> Self {
-        let subset = match indexer {
-            PositionalIndexer::Slice(slice) => {
-                let concrete_slice = slice.normalize(self.size());
+    /// Find the chunks intersecting a geometry
+    ///
+    /// Returns the positions of the chunks within the result of
+    /// `chunk(chunk_depth)`.
+    pub fn query_chunks(&self, chunk_depth: u8, geometry: &Geometry) -> Vec<usize> {
+        let shift = self.chunk_shift(chunk_depth);
 
-                self.moc.position_slice(&concrete_slice)
+        let ranges = FixedDepthRanges::new(&self.moc);
+        let offsets = self.moc.range_offsets();
+        let parents: Vec<u64> = group_by_parent(ranges.iter(), &offsets, shift)
+            .into_iter()
+            .map(|group| group.parent)
+            .collect();
+
+        let (_, subset) = self.query(geometry);
+
+        let mut chunks: Vec<usize> = Vec::new();
+        for range in FixedDepthRanges::new(&subset.moc).iter() {
+            for parent in (range.start >> shift)..=((range.end - 1) >> shift) {
+                let Ok(index) = parents.binary_search(&parent) else {
+                    continue;
+                };
+
+                if chunks.last() != Some(&index) {
+                    chunks.push(index);
+                }
             }
-            PositionalIndexer::Array(array) => self.moc.position_index(array),
-        };
+        }
 
-        CellRegion {
-            moc: subset,
-            ellipsoid: self.ellipsoid.clone(),
+        chunks
+    }
+
+    /// Draw uniformly distributed random points from the region
+    ///
+    /// Since all cells have the same area and the HEALPix projection is
+    /// equal-area, a point is drawn by choosing a random cell and then a random
+    /// position within that cell.
+    ///
+    /// Returns longitude and geographic latitude in degrees.
+    pub fn sample_points(&self, n: usize, seed: u64) -> Vec<(f64, f64)> {
+        let size = self.size();
+        if size == 0 {
+            return Vec::new();
         }
+
+        let layer = nested::get(self.depth());
+        let ranges = FixedDepthRanges::new(&self.moc);
+        let offsets = self.moc.range_offsets();
+
+        let mut rng = StdRng::seed_from_u64(seed);
+
+        (0..n)
+            .map(|_| {
+                let position = rng.random_range(0..size);
+                let index = offsets.partition_point(|&offset| offset <= position) - 1;
+                let hash = ranges.get(index).start + (position - offsets[index]) as u64;
+
+                random_point_in_cell(&hash, layer, &self.ellipsoid, &mut rng)
+            })
+            .collect()
     }
-}
 
-impl GeometryQuery for CellRegion {
-    fn query(&self, geometry: &Geometry) -> (Vec<ConcreteSlice<isize>>, Self) {
+    /// Draw `n` uniformly distributed random points within each of the given cells
+    ///
+    /// The cell ids follow the indexing scheme of the region, but don't have to
+    /// be part of the region. Fails if a cell id can't be converted to a cell
+    /// at the depth of the region.
+    pub fn sample_points_in_cells(
+        &self,
+        cell_ids: &[u64],
+        n: usize,
+        seed: u64,
+    ) -> Result<Vec<Vec<(f64, f64)>>, LabelError> {
         let depth = self.depth();
         let layer = nested::get(depth);
 
-        let geometry_moc = match geometry {
-            Geometry::Point(point) => {
-                let (lon, lat) = point.to_tuple();
-                let hash = scalar::nested::coordinates::lonlat_to_healpix(
-                    &lon,
-                    &lat,
-                    layer,
-                    &self.ellipsoid,
-                );
+        let mut rng = StdRng::seed_from_u64(seed);
 
-                RangeMOC::from_fixed_depth_cells(depth, vec![hash].into_iter(), None)
-            }
-            Geometry::BoundingBox(bbox) => {
-                let (lon_min, lat_min, lon_max, lat_max) = bbox.to_tuple();
+        cell_ids
+            .iter()
+            .map(|&label| {
+                let hash = self.indexing_scheme.to_nested(label, depth)?;
 
-                RangeMOC::from_zone(
-                    lon_min.rem_euclid(360.0).to_radians(),
-                    self.ellipsoid
-                        .latitude_geographic_to_authalic(lat_min.to_radians()),
-                    lon_max.rem_euclid(360.0).to_radians(),
-                    self.ellipsoid
-                        .latitude_geographic_to_authalic(lat_max.to_radians()),
-                    depth,
-                    CellSelection::All,
-                )
-            }
-            Geometry::Polygon(polygon) => {
-                let converted: Vec<(f64, f64)> = polygon
-                    .exterior
-                    .iter()
-                    .map(|(lon, lat)| {
-                        (
-                            lon.rem_euclid(360.0).to_radians(),
-                            self.ellipsoid
-                                .latitude_geographic_to_authalic(lat.to_radians()),
-                        )
-                    })
-                    .collect();
+                Ok((0..n)
+                    .map(|_| random_point_in_cell(&hash, layer, &self.ellipsoid, &mut rng))
+                    .collect())
+            })
+            .collect()
+    }
 
-                RangeMOC::from_polygon(&converted, false, depth, CellSelection::All)
-            }
-        };
+    fn chunk_shift(&self, chunk_depth: u8) -> u8 {
+        if self.indexing_scheme == IndexingScheme::Ring {
+            // TODO: custom error type
+            panic!("regions labelled with ring ids can't be chunked");
+        } else if chunk_depth > self.depth() {
+            // TODO: custom error type
+            panic!("the chunk depth must not be greater than the depth of the region");
+        }
 
-        let (slices, moc) = self.moc.join(&geometry_moc, JoinOp::Intersection);
+        (self.depth() - chunk_depth) << 1
+    }
+
+    /// The ring order of the positions, for regions labelled with ring ids
+    fn ring_order(&self) -> Option<RingOrder> {
+        (self.indexing_scheme == IndexingScheme::Ring)
+            .then(|| RingOrder::new(self.moc.flatten_to_fixed_depth_cells(), self.depth()))
+    }
 
-        let new_region = CellRegion {
-            moc,
+    fn with_moc(&self, moc: RangeMOC<u64, Hpx<u64>>) -> Self {
+        Self {
+            moc: Storage::owned(moc),
+            indexing_scheme: self.indexing_scheme,
             ellipsoid: self.ellipsoid.clone(),
+        }
+    }
+
+    /// Translate labels in the region's indexing scheme to nested label indexers
+    fn nested_label_indexer(&self, indexer: &LabelIndexer) -> Result<LabelIndexer, LabelError> {
+        let depth = self.depth();
+
+        let nested_indexer = match (self.indexing_scheme, indexer) {
+            (IndexingScheme::Nested, _) => indexer.clone(),
+            (scheme, LabelIndexer::Array(array)) => LabelIndexer::Array(Array::create(
+                array
+                    .data
+                    .iter()
+                    .map(|&label| scheme.to_nested(label, depth))
+                    .collect::<Result<_, _>>()?,
+            )),
+            (IndexingScheme::Ring, LabelIndexer::Slice(slice)) => {
+                // ring ids are not contiguous in the nested scheme, so the
+                // cells of the region are filtered instead of expanding the slice
+                let ConcreteSlice { start, stop, step } = slice.normalize(self.cells_at_depth());
+
+                LabelIndexer::Array(Array::create(
+                    self.moc
+                        .flatten_to_fixed_depth_cells()
+                        .filter(|&hash| {
+                            let label = IndexingScheme::Ring.from_nested(hash, depth);
+
+                            label >= start && label <= stop && (label - start) % step == 0
+                        })
+                        .collect(),
+                ))
+            }
+            (IndexingScheme::Zuniq, LabelIndexer::Slice(slice)) => {
+                if slice.step != 1 {
+                    // TODO: custom error type
+                    panic!(
+                        "Only step size 1 is supported for zuniq labels, got {}",
+                        slice.step
+                    );
+                }
+
+                // at a fixed depth, zuniq ids preserve the order of the nested ids:
+                // zuniq = (2 * hash + 1) << shift
+                let shift = (29 - depth as u32) << 1;
+                let concrete_slice = slice.normalize(u64::MAX);
+
+                let start = concrete_slice.start.div_ceil(1 << shift) / 2;
+                match (concrete_slice.stop >> shift).checked_sub(1) {
+                    Some(stop) => {
+                        LabelIndexer::Slice(Slice::create(Some(start), Some(stop / 2), Some(1)))
+                    }
+                    // the slice ends before the first cell
+                    None => LabelIndexer::Array(Array::create(Vec::new())),
+                }
+            }
         };
 
-        (slices, new_region)
+        Ok(nested_indexer)
     }
 }
 
-#[cfg(test)]
-mod test {
-    use super::*;
-    use crate::ellipsoid::ReferenceEllipsoid;
-    use geodesy::ellps::Ellipsoid as GeodesyEllipsoid;
+/// The number of decimal digits of `value`
+fn n_digits(value: u64) -> usize {
+    value.checked_ilog10().unwrap_or(0) as usize + 1
+}
 
-    fn named_ellipsoid(name: &str) -> Ellipsoid {
-        Ellipsoid::Ellipsoid(ReferenceEllipsoid::new(
-            GeodesyEllipsoid::named(name).unwrap(),
-        ))
-    }
+fn random_point_in_cell<R: Rng>(
+    hash: &u64,
+    layer: &Layer,
+    ellipsoid: &Ellipsoid,
+    rng: &mut R,
+) -> (f64, f64) {
+    let point = layer.sph_coo(*hash, rng.random::<f64>(), rng.random::<f64>());
+    let (lon, lat) = ellipsoid.authalic_to_geographic(&point);
 
-    #[test]
-    fn test_full_domain() {
-        let depth: u8 = 6;
-        let ellipsoid = named_ellipsoid("WGS84");
+    (lon.to_degrees().rem_euclid(360.0), lat.to_degrees())
+}
 
-        let actual = CellRegion::full_domain(depth, ellipsoid.clone());
+/// Refuse to combine regions describing different celestial bodies
+fn check_same_body(first: &Ellipsoid, second: &Ellipsoid) {
+    if !first.same_body(second) {
+        // TODO: custom error type
+        panic!(
+            "regions are defined on different bodies: {} and {}",
+            first.body().unwrap_or_default(),
+            second.body().unwrap_or_default()
+        );
+    }
+}
 
-        assert_eq!(actual.ellipsoid, ellipsoid);
-        assert_eq!(actual.moc.depth_max(), depth);
-        assert_eq!(actual.moc.n_depth_max_cells(), 12 * 4_u64.pow(depth as u32));
+/// Refuse to combine regions whose labels follow different indexing schemes
+fn check_same_scheme(first: IndexingScheme, second: IndexingScheme) {
+    if first != second {
+        // TODO: custom error type
+        panic!("indexing schemes don't match: {first:?} and {second:?}");
+    }
+}
+
+impl SetOperations for CellRegion {
+    fn union(&self, other: &Self) -> Self {
+        check_same_body(&self.ellipsoid, &other.ellipsoid);
+        check_same_scheme(self.indexing_scheme, other.indexing_scheme);
+        if other.ellipsoid != self.ellipsoid {
+            // TODO: custom error type
+            panic!("ellipsoids don't match");
+        }
+
+        let moc = match (&self.moc.ranges, &other.moc.ranges) {
+            (StoredRanges::Owned(first), StoredRanges::Owned(second)) => first.union(second),
+            (first, second) => set_operation(first, second, |a, b| a || b),
+        };
+
+        self.with_moc(moc)
+    }
+
+    fn intersection(&self, other: &Self) -> Self {
+        check_same_body(&self.ellipsoid, &other.ellipsoid);
+        check_same_scheme(self.indexing_scheme, other.indexing_scheme);
+        if other.ellipsoid != self.ellipsoid {
+            // TODO: custom error type
+            panic!("ellipsoids don't match");
+        }
+
+        let moc = match (&self.moc.ranges, &other.moc.ranges) {
+            (StoredRanges::Owned(first), StoredRanges::Owned(second)) => first.intersection(second),
+            (first, second) => set_operation(first, second, |a, b| a && b),
+        };
+
+        self.with_moc(moc)
+    }
+
+    fn difference(&self, other: &Self) -> Self {
+        check_same_body(&self.ellipsoid, &other.ellipsoid);
+        check_same_scheme(self.indexing_scheme, other.indexing_scheme);
+        if other.ellipsoid != self.ellipsoid {
+            // TODO: custom error type
+            panic!("ellipsoids don't match");
+        }
+
+        let moc = match (&self.moc.ranges, &other.moc.ranges) {
+            (StoredRanges::Owned(first), StoredRanges::Owned(second)) => first.minus(second),
+            (first, second) => set_operation(first, second, |a, b| a && !b),
+        };
+
+        self.with_moc(moc)
+    }
+
+    fn symmetric_difference(&self, other: &Self) -> Self {
+        check_same_body(&self.ellipsoid, &other.ellipsoid);
+        check_same_scheme(self.indexing_scheme, other.indexing_scheme);
+        if other.ellipsoid != self.ellipsoid {
+            // TODO: custom error type
+            panic!("ellipsoids don't match");
+        }
+
+        let moc = match (&self.moc.ranges, &other.moc.ranges) {
+            (StoredRanges::Owned(first), StoredRanges::Owned(second)) => first.xor(second),
+            (first, second) => set_operation(first, second, |a, b| a != b),
+        };
+
+        self.with_moc(moc)
+    }
+}
+
+impl Indexing for CellRegion {
+    fn sel(&self, indexer: &LabelIndexer) -> (Self, PositionalIndexer) {
+        let (new_region, positional_indexer, _) =
+            self.sel_with_method(indexer, &SelectionMethod::Exact);
+
+        (new_region, positional_indexer)
+    }
+
+    fn sel_with_method(
+        &self,
+        indexer: &LabelIndexer,
+        method: &SelectionMethod,
+    ) -> (Self, PositionalIndexer, Array<u64>) {
+        let (subset, positional_indexer, missing): (
+            RangeMOC<u64, Hpx<u64>>,
+            PositionalIndexer,
+            Array<u64>,
+        ) = match self
+            .nested_label_indexer(indexer)
+            // TODO: custom error type
+            .unwrap_or_else(|error| panic!("{error}"))
+        {
+            LabelIndexer::Slice(slice) => {
+                // slices select whatever is present, so there is nothing to drop
+                let concrete_slice = slice.normalize(self.cells_at_depth());
+
+                let (subset, positional_slice) = self.moc.label_slice(concrete_slice);
+
+                (
+                    subset,
+                    PositionalIndexer::Slice(Slice::create(
+                        Some(positional_slice.start as isize),
+                        Some(positional_slice.stop as isize),
+                        Some(positional_slice.step as isize),
+                    )),
+                    Array::create(Vec::new()),
+                )
+            }
+            LabelIndexer::Array(array) => {
+                let (subset, positional_array, missing) = match indexer {
+                    // converted ring slice: select whatever is present
+                    LabelIndexer::Slice(_) => {
+                        let (subset, positional_array, _) = self
+                            .moc
+                            .label_index_with(&array, &SelectionMethod::DropMissing);
+
+                        (subset, positional_array, Array::create(Vec::new()))
+                    }
+                    LabelIndexer::Array(_) => self.moc.label_index_with(&array, method),
+                };
+
+                // report the missing labels in the scheme they were passed in
+                let depth = self.depth();
+                let missing = Array::create(
+                    missing
+                        .data
+                        .into_iter()
+                        .map(|hash| self.indexing_scheme.from_nested(hash, depth))
+                        .collect(),
+                );
+
+                (
+                    subset,
+                    PositionalIndexer::Array(Array {
+                        data: positional_array
+                            .data
+                            .into_iter()
+                            .map(|v| v as isize)
+                            .collect::<Vec<_>>(),
+                    }),
+                    missing,
+                )
+            }
+        };
+
+        // ring slices are converted to arrays, so only arrays have to be
+        // reordered. The sorted ring positions match the cells of the subset.
+        let positional_indexer = match (self.ring_order(), positional_indexer) {
+            (Some(order), PositionalIndexer::Array(array)) => {
+                let mut ring_positions: Vec<isize> = array
+                    .data
+                    .into_iter()
+                    .map(|position| order.to_ring(position as usize) as isize)
+                    .collect();
+                ring_positions.sort_unstable();
+
+                PositionalIndexer::Array(Array::create(ring_positions))
+            }
+            (_, positional_indexer) => positional_indexer,
+        };
+
+        (self.with_moc(subset), positional_indexer, missing)
+    }
+
+    fn isel(&self, indexer: &PositionalIndexer) -> Self {
+        let subset = match (self.ring_order(), indexer) {
+            (None, PositionalIndexer::Slice(slice)) => {
+                let concrete_slice = slice.normalize(self.size());
+
+                self.moc.position_slice(&concrete_slice)
+            }
+            (None, PositionalIndexer::Array(array)) => self.moc.position_index(array),
+            (Some(order), indexer) => {
+                let ring_positions: Vec<usize> = match indexer {
+                    PositionalIndexer::Slice(slice) => {
+                        let ConcreteSlice { start, stop, step } = slice.normalize(self.size());
+                        if step != 1 {
+                            // TODO: custom error type
+                            panic!("Only step size 1 is supported, got {step}");
+                        }
+
+                        (start.max(0) as usize..stop.max(0) as usize).collect()
+                    }
+                    PositionalIndexer::Array(array) => array.normalize(self.size()).data,
+                };
+
+                let nested_positions = ring_positions
+                    .into_iter()
+                    .map(|position| order.to_nested(position) as isize)
+                    .collect();
+
+                self.moc.position_index(&Array::create(nested_positions))
+            }
+        };
+
+        self.with_moc(subset)
+    }
+}
+
+impl GeometryQuery for CellRegion {
+    fn query(&self, geometry: &Geometry) -> (Vec<ConcreteSlice<isize>>, Self) {
+        let depth = self.depth();
+        let layer = nested::get(depth);
+
+        let geometry_moc = match geometry {
+            Geometry::Point(point) => {
+                let (lon, lat) = point.to_tuple();
+                let hash = scalar::nested::coordinates::lonlat_to_healpix(
+                    &lon,
+                    &lat,
+                    layer,
+                    &self.ellipsoid,
+                );
+
+                RangeMOC::from_fixed_depth_cells(depth, vec![hash].into_iter(), None)
+            }
+            Geometry::BoundingBox(bbox) => {
+                let (lon_min, lat_min, lon_max, lat_max) =
+                    scalar::nested::coverage::authalic_zone(bbox.to_tuple(), &self.ellipsoid);
+
+                RangeMOC::from_zone(
+                    lon_min,
+                    lat_min,
+                    lon_max,
+                    lat_max,
+                    depth,
+                    CellSelection::All,
+                )
+            }
+            Geometry::Polygon(polygon) => {
+                let converted: Vec<(f64, f64)> = polygon
+                    .exterior
+                    .iter()
+                    .map(|(lon, lat)| {
+                        self.ellipsoid.geographic_to_authalic(&(
+                            lon.rem_euclid(360.0).to_radians(),
+                            lat.to_radians(),
+                        ))
+                    })
+                    .collect();
+
+                RangeMOC::from_polygon(&converted, false, depth, CellSelection::All)
+            }
+        };
+
+        let (slices, moc) = self.moc.join(&geometry_moc, JoinOp::Intersection);
+
+        let slices = match self.ring_order() {
+            Some(order) => {
+                let mut ring_positions: Vec<usize> = slices
+                    .into_iter()
+                    .flat_map(|slice| slice.start as usize..slice.stop as usize)
+                    .map(|position| order.to_ring(position))
+                    .collect();
+                ring_positions.sort_unstable();
+
+                contiguous_slices(&ring_positions)
+            }
+            None => slices,
+        };
+
+        (slices, self.with_moc(moc))
+    }
+}
+
+/// Group sorted positions into slices of contiguous positions
+fn contiguous_slices(positions: &[usize]) -> Vec<ConcreteSlice<isize>> {
+    positions
+        .chunk_by(|a, b| a + 1 == *b)
+        .map(|run| ConcreteSlice {
+            start: run[0] as isize,
+            stop: (run[run.len() - 1] + 1) as isize,
+            step: 1,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::ellipsoid::ReferenceEllipsoid;
+    use geodesy::ellps::Ellipsoid as GeodesyEllipsoid;
+
+    fn named_ellipsoid(name: &str) -> Ellipsoid {
+        Ellipsoid::Ellipsoid(ReferenceEllipsoid::new(
+            GeodesyEllipsoid::named(name).unwrap(),
+        ))
+    }
+
+    /// The positions of an array indexer
+    fn positions(indexer: PositionalIndexer) -> Vec<isize> {
+        match indexer {
+            PositionalIndexer::Array(array) => array.data,
+            PositionalIndexer::Slice(_) => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn test_full_domain() {
+        let depth: u8 = 6;
+        let ellipsoid = named_ellipsoid("WGS84");
+
+        let actual = CellRegion::full_domain(depth, ellipsoid.clone());
+
+        assert_eq!(actual.ellipsoid, ellipsoid);
+        assert_eq!(actual.moc.depth_max(), depth);
+        assert_eq!(actual.moc.n_depth_max_cells(), 12 * 4_u64.pow(depth as u32));
     }
 
     #[test]
@@ -362,6 +1188,48 @@ mod test {
         }
     }
 
+    mod summary {
+        use super::*;
+
+        #[test]
+        fn test_summary() {
+            let depth: u8 = 1;
+            let region = CellRegion::from_cell_ids(
+                depth,
+                vec![1, 2, 3, 4, 5, 18, 40, 41, 42],
+                named_ellipsoid("WGS84"),
+            );
+
+            let actual = region.summary();
+
+            assert_eq!(actual.depth, depth);
+            assert_eq!(actual.size, 9);
+            assert_eq!(actual.n_ranges, 3);
+            assert_eq!(actual.largest_run, 5);
+            assert_eq!(actual.smallest_run, 1);
+            assert_eq!(actual.coverage_fraction, 9.0 / 48.0);
+            assert_eq!(
+                actual.cells_per_base_cell,
+                [3, 2, 0, 0, 1, 0, 0, 0, 0, 0, 3, 0]
+            );
+            // only an estimate, but close to the actual size
+            assert!(actual.serialized_size.abs_diff(region.to_bytes().len()) <= 16);
+        }
+
+        #[test]
+        fn test_summary_empty() {
+            let region = CellRegion::empty(4, named_ellipsoid("WGS84"));
+
+            let actual = region.summary();
+
+            assert_eq!(actual.size, 0);
+            assert_eq!(actual.n_ranges, 0);
+            assert_eq!(actual.largest_run, 0);
+            assert_eq!(actual.smallest_run, 0);
+            assert_eq!(actual.cells_per_base_cell, [0; 12]);
+        }
+    }
+
     mod set_ops {
         use super::*;
 
@@ -441,6 +1309,629 @@ mod test {
 
             assert_eq!(actual, expected);
         }
+
+        #[test]
+        #[should_panic(expected = "indexing schemes don't match")]
+        fn test_set_different_schemes() {
+            let ellipsoid = named_ellipsoid("WGS84");
+
+            let first = CellRegion::full_domain(1, ellipsoid.clone());
+            let second = CellRegion::full_domain_with_scheme(1, IndexingScheme::Ring, ellipsoid);
+
+            first.union(&second);
+        }
+    }
+
+    mod indexing {
+        use super::*;
+        use crate::index::DistanceMetric;
+
+        #[test]
+        fn test_sel_array() {
+            let ellipsoid = named_ellipsoid("WGS84");
+            let region =
+                CellRegion::from_cell_ids(3, vec![2, 3, 4, 5, 23, 24, 25], ellipsoid.clone());
+
+            let indexer = LabelIndexer::Array(Array::create(vec![3, 24]));
+            let (subset, positional) = region.sel(&indexer);
+
+            assert_eq!(positions(positional), vec![1, 5]);
+            assert_eq!(subset, CellRegion::from_cell_ids(3, vec![3, 24], ellipsoid));
+        }
+
+        #[test]
+        #[should_panic(expected = "Cannot find 10")]
+        fn test_sel_array_missing() {
+            let region =
+                CellRegion::from_cell_ids(3, vec![2, 3, 4, 5, 23], named_ellipsoid("WGS84"));
+
+            let indexer = LabelIndexer::Array(Array::create(vec![3, 10]));
+            region.sel(&indexer);
+        }
+
+        #[test]
+        fn test_sel_drop_missing() {
+            let ellipsoid = named_ellipsoid("WGS84");
+            let region =
+                CellRegion::from_cell_ids(3, vec![2, 3, 4, 5, 23, 24, 25], ellipsoid.clone());
+
+            let indexer = LabelIndexer::Array(Array::create(vec![3, 10, 24]));
+            let (subset, positional, missing) =
+                region.sel_with_method(&indexer, &SelectionMethod::DropMissing);
+
+            assert_eq!(positions(positional), vec![1, 5]);
+            assert_eq!(missing.data, vec![10]);
+            assert_eq!(subset, CellRegion::from_cell_ids(3, vec![3, 24], ellipsoid));
+        }
+
+        #[test]
+        fn test_sel_nearest_cell_id() {
+            let ellipsoid = named_ellipsoid("WGS84");
+            let region =
+                CellRegion::from_cell_ids(3, vec![2, 3, 4, 5, 23, 24, 25], ellipsoid.clone());
+
+            let indexer = LabelIndexer::Array(Array::create(vec![3, 10, 20, 100]));
+
+            let method = SelectionMethod::Nearest {
+                metric: DistanceMetric::CellId,
+                tolerance: None,
+            };
+            let (subset, positional, missing) = region.sel_with_method(&indexer, &method);
+
+            assert_eq!(positions(positional), vec![1, 3, 4, 6]);
+            assert!(missing.data.is_empty());
+            assert_eq!(
+                subset,
+                CellRegion::from_cell_ids(3, vec![3, 5, 23, 25], ellipsoid.clone())
+            );
+
+            let method = SelectionMethod::Nearest {
+                metric: DistanceMetric::CellId,
+                tolerance: Some(4.0),
+            };
+            let (subset, positional, missing) = region.sel_with_method(&indexer, &method);
+
+            assert_eq!(positions(positional), vec![1, 4]);
+            assert_eq!(missing.data, vec![10, 100]);
+            assert_eq!(subset, CellRegion::from_cell_ids(3, vec![3, 23], ellipsoid));
+        }
+
+        #[test]
+        fn test_sel_nearest_angular() {
+            let ellipsoid = named_ellipsoid("WGS84");
+            let region = CellRegion::from_cell_ids(3, vec![5], ellipsoid.clone());
+
+            let indexer = LabelIndexer::Array(Array::create(vec![6]));
+
+            let method = SelectionMethod::Nearest {
+                metric: DistanceMetric::Angular,
+                tolerance: None,
+            };
+            let (subset, positional, missing) = region.sel_with_method(&indexer, &method);
+
+            assert_eq!(positions(positional), vec![0]);
+            assert!(missing.data.is_empty());
+            assert_eq!(subset, CellRegion::from_cell_ids(3, vec![5], ellipsoid));
+
+            let method = SelectionMethod::Nearest {
+                metric: DistanceMetric::Angular,
+                tolerance: Some(0.1),
+            };
+            let (_, positional, missing) = region.sel_with_method(&indexer, &method);
+
+            assert!(positions(positional).is_empty());
+            assert_eq!(missing.data, vec![6]);
+        }
+
+        #[test]
+        fn test_sel_duplicates() {
+            let ellipsoid = named_ellipsoid("WGS84");
+            let region =
+                CellRegion::from_cell_ids(3, vec![2, 3, 4, 5, 23, 24, 25], ellipsoid.clone());
+
+            // 10 and 11 both resolve to 5, and 3 is requested twice
+            let indexer = LabelIndexer::Array(Array::create(vec![10, 11, 3, 3]));
+            let method = SelectionMethod::Nearest {
+                metric: DistanceMetric::CellId,
+                tolerance: None,
+            };
+            let (subset, positional, missing) = region.sel_with_method(&indexer, &method);
+
+            // the positions are sorted like the cells of the subset
+            assert_eq!(positions(positional), vec![1, 3]);
+            assert_eq!(subset.cell_ids(), vec![3, 5]);
+            assert!(missing.data.is_empty());
+            assert_eq!(subset.size(), 2);
+            assert_eq!(subset, CellRegion::from_cell_ids(3, vec![3, 5], ellipsoid));
+        }
+    }
+
+    mod schemes {
+        use super::*;
+
+        #[test]
+        fn test_from_cell_ids_ring() {
+            let depth: u8 = 2;
+            let labels: Vec<u64> = vec![0, 5, 17, 40, 101];
+            let ellipsoid = named_ellipsoid("WGS84");
+
+            let region = CellRegion::from_cell_ids_with_scheme(
+                depth,
+                labels.clone(),
+                IndexingScheme::Ring,
+                ellipsoid,
+            )
+            .unwrap();
+
+            let mut expected_nested: Vec<u64> = labels
+                .iter()
+                .map(|h| scalar::ring::conversion::to_nested(h, &depth))
+                .collect();
+            expected_nested.sort();
+            let actual_nested: Vec<u64> = region.moc.flatten_to_fixed_depth_cells().collect();
+            assert_eq!(actual_nested, expected_nested);
+
+            let mut actual = region.cell_ids();
+            actual.sort();
+            assert_eq!(actual, labels);
+        }
+
+        #[test]
+        fn test_from_cell_ids_zuniq_depth_mismatch() {
+            let label = scalar::nested::conversion::to_zuniq(&3, &2);
+
+            let actual = CellRegion::from_cell_ids_with_scheme(
+                3,
+                vec![label],
+                IndexingScheme::Zuniq,
+                named_ellipsoid("WGS84"),
+            );
+
+            assert!(matches!(actual, Err(LabelError::DepthMismatch { .. })));
+        }
+
+        #[test]
+        fn test_sel_zuniq() {
+            let depth: u8 = 3;
+            let ellipsoid = named_ellipsoid("WGS84");
+            let to_zuniq = |h: u64| scalar::nested::conversion::to_zuniq(&h, &depth);
+
+            let labels: Vec<u64> = [2, 3, 4, 5, 23].into_iter().map(to_zuniq).collect();
+            let region = CellRegion::from_cell_ids_with_scheme(
+                depth,
+                labels,
+                IndexingScheme::Zuniq,
+                ellipsoid.clone(),
+            )
+            .unwrap();
+
+            let indexer = LabelIndexer::Array(Array::create(vec![to_zuniq(3), to_zuniq(23)]));
+            let (subset, positional) = region.sel(&indexer);
+
+            assert_eq!(positions(positional), vec![1, 4]);
+            assert_eq!(subset.cell_ids(), vec![to_zuniq(3), to_zuniq(23)]);
+            assert_eq!(subset.indexing_scheme(), IndexingScheme::Zuniq);
+
+            let indexer =
+                LabelIndexer::Slice(Slice::create(Some(to_zuniq(3)), Some(to_zuniq(5)), None));
+            let (subset, positional) = region.sel(&indexer);
+
+            match positional {
+                PositionalIndexer::Slice(slice) => {
+                    assert_eq!(slice.start, Some(1));
+                    assert_eq!(slice.stop, Some(4));
+                }
+                PositionalIndexer::Array(_) => unreachable!(),
+            }
+            assert_eq!(
+                subset.cell_ids(),
+                vec![to_zuniq(3), to_zuniq(4), to_zuniq(5)]
+            );
+        }
+
+        #[test]
+        fn test_sel_ring_ordered() {
+            let depth: u8 = 1;
+            let labels: Vec<u64> = vec![0, 3, 4, 12, 13, 47];
+            let region = CellRegion::from_cell_ids_with_scheme(
+                depth,
+                labels.clone(),
+                IndexingScheme::Ring,
+                named_ellipsoid("WGS84"),
+            )
+            .unwrap();
+            assert_eq!(region.cell_ids(), labels);
+
+            // an array ordered like the ring ids
+            let values: Vec<u64> = labels.iter().map(|label| label * 10).collect();
+            let take = |positions: Vec<isize>| -> Vec<u64> {
+                positions.into_iter().map(|p| values[p as usize]).collect()
+            };
+
+            let indexer = LabelIndexer::Slice(Slice::create(Some(3), Some(12), None));
+            let (subset, positional) = region.sel(&indexer);
+
+            assert_eq!(take(positions(positional)), vec![30, 40, 120]);
+            assert_eq!(subset.cell_ids(), vec![3, 4, 12]);
+
+            let indexer = LabelIndexer::Array(Array::create(vec![47, 0, 13]));
+            let (subset, positional) = region.sel(&indexer);
+
+            assert_eq!(take(positions(positional)), vec![0, 130, 470]);
+            assert_eq!(subset.cell_ids(), vec![0, 13, 47]);
+        }
+
+        #[test]
+        fn test_isel_ring() {
+            let depth: u8 = 1;
+            let region = CellRegion::from_cell_ids_with_scheme(
+                depth,
+                vec![0, 3, 4, 12, 13, 47],
+                IndexingScheme::Ring,
+                named_ellipsoid("WGS84"),
+            )
+            .unwrap();
+
+            let indexer = PositionalIndexer::Slice(Slice::create(Some(1), Some(4), None));
+            assert_eq!(region.isel(&indexer).cell_ids(), vec![3, 4, 12]);
+
+            let indexer = PositionalIndexer::Array(Array::create(vec![-1, 0]));
+            assert_eq!(region.isel(&indexer).cell_ids(), vec![0, 47]);
+        }
+
+        #[test]
+        fn test_join_ring() {
+            let ellipsoid = named_ellipsoid("WGS84");
+            let fine =
+                CellRegion::full_domain_with_scheme(2, IndexingScheme::Ring, ellipsoid.clone());
+            let coarse = CellRegion::full_domain_with_scheme(1, IndexingScheme::Ring, ellipsoid);
+
+            let fine_ids = fine.cell_ids();
+            let coarse_ids = coarse.cell_ids();
+            let actual = fine.join(&coarse);
+
+            assert_eq!(actual.len(), fine.size());
+            for (fine_position, coarse_position) in actual {
+                let parent = scalar::ring::conversion::to_nested(&fine_ids[fine_position], &2) >> 2;
+
+                assert_eq!(
+                    scalar::ring::conversion::to_nested(&coarse_ids[coarse_position], &1),
+                    parent
+                );
+            }
+        }
+
+        #[test]
+        #[should_panic(expected = "can't be chunked")]
+        fn test_chunk_ring() {
+            let region = CellRegion::full_domain_with_scheme(
+                2,
+                IndexingScheme::Ring,
+                named_ellipsoid("WGS84"),
+            );
+
+            region.chunk(1);
+        }
+
+        #[test]
+        fn test_sel_ring_slice_step() {
+            let depth: u8 = 2;
+            let region = CellRegion::from_cell_ids_with_scheme(
+                depth,
+                (0..40).collect(),
+                IndexingScheme::Ring,
+                named_ellipsoid("WGS84"),
+            )
+            .unwrap();
+
+            let indexer = LabelIndexer::Slice(Slice::create(Some(30), None, Some(4)));
+            let (subset, _) = region.sel(&indexer);
+
+            assert_eq!(subset.cell_ids(), vec![30, 34, 38]);
+        }
+
+        #[test]
+        fn test_sel_zuniq_slice_before_first_cell() {
+            let depth: u8 = 3;
+            let region = CellRegion::full_domain_with_scheme(
+                depth,
+                IndexingScheme::Zuniq,
+                named_ellipsoid("WGS84"),
+            );
+
+            let first = scalar::nested::conversion::to_zuniq(&0, &depth);
+            let indexer = LabelIndexer::Slice(Slice::create(None, Some(first - 1), None));
+            let (subset, positional) = region.sel(&indexer);
+
+            assert!(positions(positional).is_empty());
+            assert_eq!(subset.size(), 0);
+        }
+
+        #[test]
+        #[should_panic(expected = "Only step size 1 is supported")]
+        fn test_sel_zuniq_slice_step() {
+            let depth: u8 = 3;
+            let region = CellRegion::full_domain_with_scheme(
+                depth,
+                IndexingScheme::Zuniq,
+                named_ellipsoid("WGS84"),
+            );
+
+            let indexer = LabelIndexer::Slice(Slice::create(None, None, Some(2)));
+            region.sel(&indexer);
+        }
+    }
+
+    mod join {
+        use super::*;
+
+        #[test]
+        fn test_join_fine_with_coarse() {
+            let ellipsoid = named_ellipsoid("WGS84");
+
+            let fine = CellRegion::from_cell_ids(2, vec![0, 1, 5, 17, 40], ellipsoid.clone());
+            let coarse = CellRegion::from_cell_ids(1, vec![0, 1, 4], ellipsoid);
+
+            let actual = fine.join(&coarse);
+            let expected = vec![(0, 0), (1, 0), (2, 1), (3, 2)];
+
+            assert_eq!(actual, expected);
+        }
+
+        #[test]
+        fn test_join_coarse_with_fine() {
+            let ellipsoid = named_ellipsoid("WGS84");
+
+            let fine = CellRegion::from_cell_ids(2, vec![0, 1, 5, 17, 40], ellipsoid.clone());
+            let coarse = CellRegion::from_cell_ids(1, vec![0, 1, 4], ellipsoid);
+
+            let actual = coarse.join(&fine);
+            let expected = vec![(0, 0), (0, 1), (1, 2), (2, 3)];
+
+            assert_eq!(actual, expected);
+        }
+
+        #[test]
+        fn test_join_same_depth() {
+            let ellipsoid = named_ellipsoid("WGS84");
+
+            let first = CellRegion::from_cell_ids(1, vec![1, 2, 3, 18], ellipsoid.clone());
+            let second = CellRegion::from_cell_ids(1, vec![2, 18, 20], ellipsoid);
+
+            assert_eq!(first.join(&second), vec![(1, 0), (3, 1)]);
+        }
+    }
+
+    mod sampling {
+        use super::*;
+
+        #[test]
+        fn test_sample_points() {
+            let depth: u8 = 4;
+            let ellipsoid = named_ellipsoid("WGS84");
+            let cell_ids: Vec<u64> = vec![3, 4, 5, 6, 700, 701, 2000];
+
+            let region = CellRegion::from_cell_ids(depth, cell_ids.clone(), ellipsoid.clone());
+
+            let points = region.sample_points(200, 42);
+            assert_eq!(points.len(), 200);
+
+            let layer = nested::get(depth);
+            for (lon, lat) in points.iter() {
+                let hash =
+                    scalar::nested::coordinates::lonlat_to_healpix(lon, lat, layer, &ellipsoid);
+
+                assert!(cell_ids.contains(&hash), "{hash} is not in the region");
+            }
+
+            assert_eq!(region.sample_points(200, 42), points);
+            assert_ne!(region.sample_points(200, 7), points);
+        }
+
+        #[test]
+        fn test_sample_points_empty() {
+            let region = CellRegion::empty(3, named_ellipsoid("WGS84"));
+
+            assert!(region.sample_points(10, 0).is_empty());
+        }
+
+        #[test]
+        fn test_sample_points_in_cells() {
+            let depth: u8 = 2;
+            let ellipsoid = named_ellipsoid("WGS84");
+
+            let region =
+                CellRegion::full_domain_with_scheme(depth, IndexingScheme::Ring, ellipsoid.clone());
+
+            let cell_ids: Vec<u64> = vec![0, 37, 191];
+            let points = region.sample_points_in_cells(&cell_ids, 25, 3).unwrap();
+
+            assert_eq!(points.len(), cell_ids.len());
+            let nside = 1 << depth;
+            for (cell_id, cell_points) in cell_ids.iter().zip(points.iter()) {
+                assert_eq!(cell_points.len(), 25);
+
+                for (lon, lat) in cell_points.iter() {
+                    let hash =
+                        scalar::ring::coordinates::lonlat_to_healpix(lon, lat, &nside, &ellipsoid);
+
+                    assert_eq!(hash, *cell_id);
+                }
+            }
+        }
+    }
+
+    mod ellipsoids {
+        use super::*;
+
+        #[test]
+        fn test_is_compatible() {
+            let region1 = CellRegion::full_domain(3, named_ellipsoid("WGS84"));
+            let region2 = CellRegion::full_domain(3, named_ellipsoid("GRS80"));
+            let region3 = CellRegion::full_domain(3, Ellipsoid::default());
+
+            assert!(region1.is_compatible(&region2, 0.01));
+            assert!(!region1.is_compatible(&region3, 0.01));
+        }
+
+        #[test]
+        fn test_align_to_tolerance() {
+            let region1 = CellRegion::from_cell_ids(3, vec![1, 2, 3], named_ellipsoid("WGS84"));
+            let region2 = CellRegion::from_cell_ids(3, vec![3, 4], named_ellipsoid("GRS80"));
+
+            let aligned = region2.align_to(&region1, EllipsoidAlignment::Tolerance(0.01));
+            let actual = region1.union(&aligned);
+
+            assert_eq!(aligned.ellipsoid(), region1.ellipsoid());
+            assert_eq!(actual.cell_ids(), vec![1, 2, 3, 4]);
+        }
+
+        #[test]
+        #[should_panic(expected = "ellipsoids don't match")]
+        fn test_align_to_tolerance_exceeded() {
+            let region1 = CellRegion::full_domain(3, named_ellipsoid("WGS84"));
+            let region2 = CellRegion::full_domain(3, Ellipsoid::default());
+
+            region2.align_to(&region1, EllipsoidAlignment::Tolerance(0.01));
+        }
+
+        #[test]
+        fn test_resample() {
+            let wgs84 = named_ellipsoid("WGS84");
+            let depth = 8;
+            let sphere = CellRegion::full_domain(depth, Ellipsoid::default());
+
+            // the layout is unchanged on the same ellipsoid
+            assert_eq!(sphere.resample(&Ellipsoid::default()), sphere);
+
+            // the full domain stays the full domain
+            let resampled = sphere.resample(&wgs84);
+            assert_eq!(resampled.ellipsoid(), &wgs84);
+            assert_eq!(resampled, CellRegion::full_domain(depth, wgs84.clone()));
+
+            // cells at the equator keep their ids
+            let equator = CellRegion::from_cell_ids(
+                depth,
+                vec![scalar::nested::coordinates::lonlat_to_healpix(
+                    &45.0,
+                    &0.1,
+                    nested::get(depth),
+                    &Ellipsoid::default(),
+                )],
+                Ellipsoid::default(),
+            );
+            assert_eq!(equator.resample(&wgs84).cell_ids(), equator.cell_ids());
+        }
+
+        #[test]
+        fn test_resample_coverage() {
+            let wgs84 = named_ellipsoid("WGS84");
+            let depth = 7;
+            let layer = nested::get(depth);
+
+            // a band of cells at mid latitudes, where the latitudes move the most
+            let cell_ids: Vec<u64> = (0..layer.n_hash())
+                .filter(|&hash| {
+                    let (_, lat) = layer.center(hash);
+
+                    (30f64.to_radians()..60f64.to_radians()).contains(&lat)
+                })
+                .collect();
+            let region = CellRegion::from_cell_ids(depth, cell_ids.clone(), Ellipsoid::default());
+
+            let resampled = region.resample(&wgs84);
+            let resampled_ids = resampled.cell_ids();
+
+            // no holes: every cell stays within one cell of the resampled region
+            for hash in cell_ids {
+                let geographic = Ellipsoid::default().authalic_to_geographic(&layer.center(hash));
+                let (lon, lat) = wgs84.geographic_to_authalic(&geographic);
+                let target = layer.hash(lon, lat);
+
+                let covered = std::iter::once(target)
+                    .chain(layer.kth_neighbours(target, 1))
+                    .any(|neighbour| resampled_ids.binary_search(&neighbour).is_ok());
+                assert!(covered, "cell {hash} is not covered");
+            }
+
+            // the covered area is kept
+            let ratio = resampled.size() as f64 / region.size() as f64;
+            assert!((ratio - 1.0).abs() < 0.05, "ratio: {ratio}");
+        }
+
+        #[test]
+        #[should_panic(expected = "different bodies: Mars and Earth")]
+        fn test_different_bodies() {
+            let mars = CellRegion::full_domain(3, Ellipsoid::named("mars").unwrap());
+            let earth = CellRegion::full_domain(3, Ellipsoid::default());
+
+            mars.union(&earth);
+        }
+
+        #[test]
+        #[should_panic(expected = "different bodies")]
+        fn test_resample_different_bodies() {
+            let moon = CellRegion::full_domain(3, Ellipsoid::named("moon").unwrap());
+            let earth = CellRegion::full_domain(3, Ellipsoid::default());
+
+            moon.align_to(&earth, EllipsoidAlignment::Resample);
+        }
+    }
+
+    mod chunking {
+        use super::*;
+        use crate::geometry::{Geometry, Point};
+
+        #[test]
+        fn test_chunk() {
+            let ellipsoid = named_ellipsoid("WGS84");
+            let region = CellRegion::from_cell_ids(2, vec![1, 2, 3, 17, 18, 40], ellipsoid);
+
+            let chunks = region.chunk(1);
+
+            let actual: Vec<_> = chunks
+                .iter()
+                .map(|chunk| {
+                    (
+                        chunk.cell_id,
+                        chunk.positions.start,
+                        chunk.positions.stop,
+                        chunk.region.cell_ids(),
+                    )
+                })
+                .collect();
+            let expected = vec![
+                (0, 0, 3, vec![1, 2, 3]),
+                (4, 3, 5, vec![17, 18]),
+                (10, 5, 6, vec![40]),
+            ];
+
+            assert_eq!(actual, expected);
+            assert!(chunks.iter().all(|chunk| chunk.depth == 1));
+        }
+
+        #[test]
+        fn test_chunk_to_size() {
+            let ellipsoid = named_ellipsoid("WGS84");
+            let region = CellRegion::full_domain(3, ellipsoid);
+
+            let chunks = region.chunk_to_size(20);
+
+            assert_eq!(chunks.len(), 48);
+            assert!(chunks.iter().all(|chunk| chunk.region.size() == 16));
+        }
+
+        #[test]
+        fn test_query_chunks() {
+            let ellipsoid = named_ellipsoid("WGS84");
+            let region = CellRegion::full_domain(6, ellipsoid);
+            let point = Geometry::Point(Point::from_tuple((0.0, 2.0)));
+
+            // the point is in cell 19459 at depth 6
+            let actual = region.query_chunks(2, &point);
+
+            assert_eq!(actual, vec![19459 >> 8]);
+        }
     }
 
     mod query {
@@ -496,6 +1987,32 @@ mod test {
             assert_eq!(subset, expected_subset);
         }
 
+        #[test]
+        fn test_query_bbox_ring() {
+            let ellipsoid = named_ellipsoid("WGS84");
+            let depth: u8 = 1;
+
+            let region =
+                CellRegion::full_domain_with_scheme(depth, IndexingScheme::Ring, ellipsoid);
+            let bbox = Geometry::BoundingBox(BoundingBox::from_tuple((-10.0, 0.0, 20.0, 25.0)));
+
+            let (slices, subset) = region.query(&bbox);
+
+            // the positions of the full domain are the ring ids
+            let selected: Vec<u64> = slices
+                .into_iter()
+                .flat_map(|slice| slice.start as u64..slice.stop as u64)
+                .collect();
+            let mut expected: Vec<u64> = [2, 17, 18, 19]
+                .iter()
+                .map(|hash| scalar::nested::conversion::to_ring(hash, &depth))
+                .collect();
+            expected.sort();
+
+            assert_eq!(selected, expected);
+            assert_eq!(subset.cell_ids(), expected);
+        }
+
         #[test]
         fn test_query_polygon_full_domain() {
             let ellipsoid = named_ellipsoid("WGS84");
@@ -522,6 +2039,143 @@ mod test {
         }
     }
 
+    mod mapped {
+        use super::*;
+        use std::path::{Path, PathBuf};
+
+        /// Temporary file path, removed again when dropped
+        struct TempPath(PathBuf);
+
+        impl TempPath {
+            fn new(name: &str) -> Self {
+                Self(
+                    std::env::temp_dir().join(format!("healpix-geo-{}-{name}", std::process::id())),
+                )
+            }
+        }
+
+        impl AsRef<Path> for TempPath {
+            fn as_ref(&self) -> &Path {
+                &self.0
+            }
+        }
+
+        impl Drop for TempPath {
+            fn drop(&mut self) {
+                let _ = std::fs::remove_file(&self.0);
+            }
+        }
+
+        fn mapped_region(name: &str, region: &CellRegion) -> (TempPath, CellRegion) {
+            let path = TempPath::new(name);
+            region.write_mapped(&path).unwrap();
+            let mapped = CellRegion::open_mapped(&path).unwrap();
+
+            (path, mapped)
+        }
+
+        #[test]
+        fn test_roundtrip() {
+            let ellipsoid = named_ellipsoid("WGS84");
+            let region = CellRegion::from_cell_ids_with_scheme(
+                6,
+                vec![3, 4, 5, 17, 2000, 2001],
+                IndexingScheme::Ring,
+                ellipsoid,
+            )
+            .unwrap();
+
+            let (_path, mapped) = mapped_region("roundtrip", &region);
+
+            assert!(mapped.is_mapped());
+            assert_eq!(mapped, region);
+            assert_eq!(mapped.cell_ids(), region.cell_ids());
+            assert_eq!(mapped.nbytes(), region.nbytes());
+        }
+
+        #[test]
+        fn test_set_operations() {
+            let ellipsoid = named_ellipsoid("WGS84");
+            let depth = 4;
+            let region1 = CellRegion::from_cell_ids(depth, (0..20).collect(), ellipsoid.clone());
+            let region2 = CellRegion::from_cell_ids(depth, (10..30).collect(), ellipsoid);
+
+            let (_path, mapped) = mapped_region("set-operations", &region1);
+
+            assert_eq!(mapped.union(&region2), region1.union(&region2));
+            assert_eq!(
+                mapped.intersection(&region2),
+                region1.intersection(&region2)
+            );
+            assert_eq!(region2.difference(&mapped), region2.difference(&region1));
+            assert_eq!(
+                mapped.symmetric_difference(&region2),
+                region1.symmetric_difference(&region2)
+            );
+        }
+
+        #[test]
+        fn test_indexing() {
+            let ellipsoid = named_ellipsoid("WGS84");
+            let region = CellRegion::from_cell_ids(4, vec![1, 2, 3, 10, 11, 12, 100], ellipsoid);
+
+            let (_path, mapped) = mapped_region("indexing", &region);
+
+            let indexer = LabelIndexer::Array(Array::create(vec![2, 11, 100]));
+            let (actual, actual_positions) = mapped.sel(&indexer);
+            let (expected, expected_positions) = region.sel(&indexer);
+            assert_eq!(actual, expected);
+            assert_eq!(actual_positions, expected_positions);
+
+            let indexer = PositionalIndexer::Slice(Slice::create(Some(1), Some(5), None));
+            assert_eq!(mapped.isel(&indexer), region.isel(&indexer));
+
+            let indexer = PositionalIndexer::Array(Array::create(vec![6, 0, 4]));
+            assert_eq!(mapped.isel(&indexer), region.isel(&indexer));
+        }
+
+        #[test]
+        fn test_offsets_cached() {
+            let ellipsoid = named_ellipsoid("WGS84");
+            let region = CellRegion::from_cell_ids(4, vec![1, 2, 3, 10, 11, 12, 100], ellipsoid);
+
+            let (_path, mapped) = mapped_region("offsets", &region);
+            let cloned = mapped.clone();
+
+            assert_eq!(mapped.moc.range_offsets().as_ref(), &[0, 3, 6]);
+            assert!(matches!(cloned.moc.range_offsets(), Cow::Borrowed(_)));
+            assert!(Arc::ptr_eq(&mapped.moc.offsets, &cloned.moc.offsets));
+        }
+
+        #[test]
+        fn test_open_invalid() {
+            let path = TempPath::new("invalid");
+            std::fs::write(&path, b"not a region").unwrap();
+
+            let err = CellRegion::open_mapped(&path).unwrap_err();
+            assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+        }
+
+        #[test]
+        fn test_open_unsorted_ranges() {
+            let ellipsoid = named_ellipsoid("WGS84");
+            let region = CellRegion::from_cell_ids(4, vec![1, 10], ellipsoid);
+
+            let path = TempPath::new("unsorted");
+            region.write_mapped(&path).unwrap();
+
+            // swap the two ranges at the end of the file
+            let mut bytes = std::fs::read(&path).unwrap();
+            let n = bytes.len();
+            let (first, second) = bytes[n - 32..].split_at_mut(16);
+            first.swap_with_slice(second);
+            std::fs::write(&path, bytes).unwrap();
+
+            let err = CellRegion::open_mapped(&path).unwrap_err();
+            assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+        }
+    }
+
     mod io {
         use super::*;
 
@@ -536,5 +2190,45 @@ mod test {
 
             assert_eq!(region, roundtripped);
         }
+
+        #[test]
+        fn test_roundtrip_indexing_scheme() {
+            let ellipsoid = named_ellipsoid("WGS84");
+            let region = CellRegion::from_cell_ids_with_scheme(
+                4,
+                vec![3, 17, 2000],
+                IndexingScheme::Ring,
+                ellipsoid.clone(),
+            )
+            .unwrap();
+
+            let bytes = region.to_bytes();
+            let roundtripped = CellRegion::from_bytes(&bytes);
+
+            assert_eq!(region, roundtripped);
+        }
+
+        #[test]
+        fn test_roundtrip_ellipsoid_name() {
+            let ellipsoid = Ellipsoid::named("WGS84").unwrap();
+            let region = CellRegion::full_domain(3, ellipsoid);
+
+            let roundtripped = CellRegion::from_bytes(&region.to_bytes());
+
+            assert_eq!(roundtripped.ellipsoid().name(), Some("WGS84"));
+        }
+
+        #[test]
+        fn test_from_bytes_without_indexing_scheme() {
+            let ellipsoid = named_ellipsoid("WGS84");
+            let region = CellRegion::from_cell_ids(4, vec![3, 17, 2000], ellipsoid);
+
+            // data written before the indexing scheme was serialized
+            let scheme_size = serde_json::to_vec(&IndexingScheme::Nested).unwrap().len();
+            let bytes = region.to_bytes();
+            let legacy = &bytes[..bytes.len() - scheme_size];
+
+            assert_eq!(CellRegion::from_bytes(legacy), region);
+        }
     }
 }
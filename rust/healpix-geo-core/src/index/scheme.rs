@@ -0,0 +1,183 @@
+use crate::scalar;
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+use std::fmt;
+
+/// The indexing scheme used for the labels of a region
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum IndexingScheme {
+    #[default]
+    Nested,
+    Ring,
+    Zuniq,
+}
+
+/// A label that can't be converted to a cell at the depth of a region
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LabelError {
+    /// The zuniq label encodes a cell at another depth
+    DepthMismatch {
+        label: u64,
+        label_depth: u8,
+        depth: u8,
+    },
+}
+
+impl fmt::Display for LabelError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::DepthMismatch {
+                label,
+                label_depth,
+                depth,
+            } => write!(
+                f,
+                "label {label} has depth {label_depth}, expected depth {depth}"
+            ),
+        }
+    }
+}
+
+impl Error for LabelError {}
+
+impl IndexingScheme {
+    /// Convert a label to the nested cell id at the given depth
+    pub fn to_nested(&self, label: u64, depth: u8) -> Result<u64, LabelError> {
+        match self {
+            Self::Nested => Ok(label),
+            Self::Ring => Ok(scalar::ring::conversion::to_nested(&label, &depth)),
+            Self::Zuniq => {
+                let (hash, label_depth) = scalar::zuniq::conversion::to_nested(&label);
+                if label_depth != depth {
+                    return Err(LabelError::DepthMismatch {
+                        label,
+                        label_depth,
+                        depth,
+                    });
+                }
+
+                Ok(hash)
+            }
+        }
+    }
+
+    /// Check that a label can be converted to a cell at the given depth
+    pub fn check_label(&self, label: u64, depth: u8) -> Result<(), LabelError> {
+        match self {
+            Self::Nested | Self::Ring => Ok(()),
+            Self::Zuniq => self.to_nested(label, depth).map(|_| ()),
+        }
+    }
+
+    /// Convert a nested cell id at the given depth to a label
+    pub fn from_nested(&self, hash: u64, depth: u8) -> u64 {
+        match self {
+            Self::Nested => hash,
+            Self::Ring => scalar::nested::conversion::to_ring(&hash, &depth),
+            Self::Zuniq => scalar::nested::conversion::to_zuniq(&hash, &depth),
+        }
+    }
+}
+
+/// The positions of the cells of a region labelled with ring ids
+///
+/// The ranges are stored in nested order, while the positions of such a region
+/// follow its sorted ring ids.
+pub(crate) struct RingOrder {
+    /// The nested position of the cell at each ring position
+    nested_positions: Vec<usize>,
+    /// The ring position of the cell at each nested position
+    ring_positions: Vec<usize>,
+}
+
+impl RingOrder {
+    /// Compute the order of nested cells at the given depth
+    pub(crate) fn new(cells: impl Iterator<Item = u64>, depth: u8) -> Self {
+        let labels: Vec<u64> = cells
+            .map(|hash| IndexingScheme::Ring.from_nested(hash, depth))
+            .collect();
+
+        let mut nested_positions: Vec<usize> = (0..labels.len()).collect();
+        nested_positions.sort_unstable_by_key(|&position| labels[position]);
+
+        let mut ring_positions = vec![0; labels.len()];
+        for (ring_position, &nested_position) in nested_positions.iter().enumerate() {
+            ring_positions[nested_position] = ring_position;
+        }
+
+        Self {
+            nested_positions,
+            ring_positions,
+        }
+    }
+
+    /// The ring position of the cell at the given nested position
+    pub(crate) fn to_ring(&self, nested_position: usize) -> usize {
+        self.ring_positions[nested_position]
+    }
+
+    /// The nested position of the cell at the given ring position
+    pub(crate) fn to_nested(&self, ring_position: usize) -> usize {
+        *self
+            .nested_positions
+            .get(ring_position)
+            .unwrap_or_else(|| panic!("{ring_position} is out of bounds"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_roundtrip() {
+        let depth: u8 = 4;
+
+        for scheme in [
+            IndexingScheme::Nested,
+            IndexingScheme::Ring,
+            IndexingScheme::Zuniq,
+        ] {
+            for hash in [0u64, 17, 1024, 3071] {
+                let label = scheme.from_nested(hash, depth);
+
+                assert_eq!(scheme.to_nested(label, depth), Ok(hash));
+            }
+        }
+    }
+
+    #[test]
+    fn test_zuniq_depth_mismatch() {
+        let label = IndexingScheme::Zuniq.from_nested(3, 2);
+
+        let error = IndexingScheme::Zuniq.to_nested(label, 4).unwrap_err();
+
+        assert_eq!(
+            error,
+            LabelError::DepthMismatch {
+                label,
+                label_depth: 2,
+                depth: 4
+            }
+        );
+        assert!(error.to_string().ends_with("expected depth 4"));
+    }
+
+    #[test]
+    fn test_ring_order() {
+        let depth: u8 = 1;
+        let cells = [0u64, 4, 5, 19];
+
+        let order = RingOrder::new(cells.into_iter(), depth);
+
+        let labels: Vec<u64> = (0..cells.len())
+            .map(|position| cells[order.to_nested(position)])
+            .map(|hash| IndexingScheme::Ring.from_nested(hash, depth))
+            .collect();
+        assert!(labels.is_sorted());
+
+        for position in 0..cells.len() {
+            assert_eq!(order.to_nested(order.to_ring(position)), position);
+        }
+    }
+}
@@ -4,3 +4,14 @@ pub trait SetOperations {
     fn difference(&self, other: &Self) -> Self;
     fn symmetric_difference(&self, other: &Self) -> Self;
 }
+
+/// How to handle regions defined on different ellipsoids
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum EllipsoidAlignment {
+    /// Require identical ellipsoids
+    Exact,
+    /// Accept ellipsoids whose cells are at most this many meters apart
+    Tolerance(f64),
+    /// Resample onto the other ellipsoid
+    Resample,
+}
@@ -0,0 +1,40 @@
+use std::fmt;
+
+/// Statistics describing the layout of a region
+#[derive(Debug, Clone, PartialEq)]
+pub struct RegionSummary {
+    pub depth: u8,
+    /// The number of cells in the region
+    pub size: u64,
+    /// The number of contiguous ranges of cells
+    pub n_ranges: usize,
+    /// The number of cells in the longest contiguous range
+    pub largest_run: u64,
+    /// The number of cells in the shortest contiguous range
+    pub smallest_run: u64,
+    /// The fraction of the sphere covered by the region
+    pub coverage_fraction: f64,
+    /// The number of cells within each of the 12 base cells
+    pub cells_per_base_cell: [u64; 12],
+    /// An estimate of the size of the serialized region in bytes
+    pub serialized_size: usize,
+}
+
+impl fmt::Display for RegionSummary {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "depth: {}", self.depth)?;
+        writeln!(
+            f,
+            "size: {} cells ({:.4}% of the sphere)",
+            self.size,
+            self.coverage_fraction * 100.0
+        )?;
+        writeln!(
+            f,
+            "ranges: {} (largest run: {} cells, smallest run: {} cells)",
+            self.n_ranges, self.largest_run, self.smallest_run
+        )?;
+        writeln!(f, "cells per base cell: {:?}", self.cells_per_base_cell)?;
+        write!(f, "serialized size: ~{} bytes", self.serialized_size)
+    }
+}
@@ -1,5 +1,7 @@
 pub mod ellipsoid;
 pub mod geometry;
 pub mod index;
+pub mod resolution;
 pub mod scalar;
+pub mod transform;
 pub mod vectorized;
@@ -0,0 +1,271 @@
+use crate::ellipsoid::Ellipsoid;
+use crate::scalar::geometry::{geodesic, geodesic_distance};
+use crate::scalar::nested::coordinates::vertices;
+
+use cdshealpix::nested::Layer;
+use geographiclib_rs::{Geodesic, PolygonArea, Winding};
+use std::f64::consts::FRAC_PI_2;
+
+/// Deepest depth at which every cell is measured
+const MAX_EXHAUSTIVE_DEPTH: u8 = 5;
+/// Number of sample points along each axis of the sampled octant
+const SAMPLES_PER_AXIS: u32 = 128;
+/// Number of points per cell edge used to compute the area
+const AREA_STEP: usize = 8;
+
+/// Minimum, mean and maximum of a quantity over the cells of a depth
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Statistics {
+    pub min: f64,
+    pub mean: f64,
+    pub max: f64,
+}
+
+impl Statistics {
+    fn from_values(values: &[f64]) -> Self {
+        let min = values.iter().copied().fold(f64::INFINITY, f64::min);
+        let max = values.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+        let mean = values.iter().sum::<f64>() / values.len() as f64;
+
+        Self { min, mean, max }
+    }
+}
+
+/// Size of the cells of a depth on an ellipsoid
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Resolution {
+    pub depth: u8,
+    pub n_cells: u64,
+    /// The cell area in square meters
+    pub area: Statistics,
+    /// The geodesic length of the cell edges in meters
+    pub edge_length: Statistics,
+    /// The geodesic length of the cell diagonals in meters
+    pub diagonal: Statistics,
+}
+
+impl Resolution {
+    /// The statistics of the given quantity
+    pub fn statistics(&self, measure: Measure) -> Statistics {
+        match measure {
+            Measure::Area => self.area,
+            Measure::EdgeLength => self.edge_length,
+            Measure::Diagonal => self.diagonal,
+        }
+    }
+}
+
+/// The quantity used to describe the size of cells
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Measure {
+    /// In square meters
+    Area,
+    /// In meters
+    EdgeLength,
+    /// In meters
+    Diagonal,
+}
+
+/// Cells at the corners of the base cells and their direct neighbours
+///
+/// This is where the HEALPix cells are the most distorted, including the
+/// poles and the points where three base cells meet.
+fn corner_cells(layer: &Layer) -> Vec<u64> {
+    let depth = layer.depth();
+    let last = (1u32 << depth) - 1;
+    let zoc = cdshealpix::nested::zordercurve::get_zoc(depth);
+
+    let corners: Vec<u64> = (0..12u64)
+        .flat_map(|base| {
+            [(0, 0), (last, 0), (last, last), (0, last)]
+                .map(|(i, j)| (base << (2 * depth)) | zoc.ij2h(i, j))
+        })
+        .collect();
+
+    corners
+        .iter()
+        .flat_map(|&hash| layer.kth_neighbours(hash, 1))
+        .chain(corners.iter().copied())
+        .collect()
+}
+
+/// Cells representative of the whole sphere
+///
+/// Both the grid and the ellipsoid are symmetric with respect to the equator
+/// and to rotations of 90° in longitude, so for deeper depths it is enough to
+/// sample the first octant. The sample points are uniformly distributed in
+/// area such that every cell is equally likely to be picked. The corner cells
+/// are always included, so that the extremes are not missed.
+fn sample_cells(layer: &Layer) -> Vec<u64> {
+    let depth = layer.depth();
+    if depth <= MAX_EXHAUSTIVE_DEPTH {
+        return (0..12 * 4u64.pow(depth as u32)).collect();
+    }
+
+    let n = SAMPLES_PER_AXIS as f64;
+    let mut cells: Vec<u64> = (0..SAMPLES_PER_AXIS)
+        .flat_map(|i| (0..=SAMPLES_PER_AXIS).map(move |j| (i, j)))
+        .map(|(i, j)| {
+            let lon = i as f64 / n * FRAC_PI_2;
+            let lat = (j as f64 / n).asin();
+
+            layer.hash(lon, lat)
+        })
+        .chain(corner_cells(layer))
+        .collect();
+
+    cells.sort_unstable();
+    cells.dedup();
+
+    cells
+}
+
+/// Area, edge lengths and diagonals of a single cell
+fn measure_cell(
+    hash: &u64,
+    layer: &Layer,
+    ellipsoid: &Ellipsoid,
+    geodesic: &Geodesic,
+) -> (f64, [f64; 4], [f64; 2]) {
+    // south, east, north, west
+    let corners = vertices(hash, layer, ellipsoid, &1);
+    let edges = [0, 1, 2, 3]
+        .map(|index| geodesic_distance(&corners[index], &corners[(index + 1) % 4], geodesic));
+    let diagonals =
+        [0, 1].map(|index| geodesic_distance(&corners[index], &corners[index + 2], geodesic));
+
+    // the edges of the cells are not geodesics, so follow them more closely
+    let mut polygon = PolygonArea::new(geodesic, Winding::CounterClockwise);
+    for (lon, lat) in vertices(hash, layer, ellipsoid, &AREA_STEP) {
+        polygon.add_point(lat, lon);
+    }
+    let (_, area, _) = polygon.compute(true);
+
+    (area.abs(), edges, diagonals)
+}
+
+/// Measure the cells of a depth on the given ellipsoid
+///
+/// Up to depth 5 all cells are measured. Deeper depths are estimated from a
+/// sample of cells which includes the cells at the corners of the base cells,
+/// where the extremes are expected; the statistics of these depths are
+/// approximate. Triaxial ellipsoids are not supported.
+pub fn resolution(depth: u8, ellipsoid: &Ellipsoid) -> Resolution {
+    if depth > 29 {
+        // TODO: custom error type
+        panic!("depth must be in the range [0, 29]");
+    }
+
+    let layer = cdshealpix::nested::get(depth);
+    let geodesic = geodesic(ellipsoid);
+
+    let mut areas = Vec::new();
+    let mut edge_lengths = Vec::new();
+    let mut diagonals = Vec::new();
+    for hash in sample_cells(layer) {
+        let (area, edges, diagonals_) = measure_cell(&hash, layer, ellipsoid, &geodesic);
+
+        areas.push(area);
+        edge_lengths.extend(edges);
+        diagonals.extend(diagonals_);
+    }
+
+    Resolution {
+        depth,
+        n_cells: 12 * 4u64.pow(depth as u32),
+        area: Statistics::from_values(&areas),
+        edge_length: Statistics::from_values(&edge_lengths),
+        diagonal: Statistics::from_values(&diagonals),
+    }
+}
+
+/// Measure the cells of every depth on the given ellipsoid
+pub fn resolution_table(ellipsoid: &Ellipsoid) -> Vec<Resolution> {
+    (0..=29).map(|depth| resolution(depth, ellipsoid)).collect()
+}
+
+/// The smallest depth at which the largest measured cell is not larger than
+/// `target`
+///
+/// Beyond depth 5 the largest cell is taken from the sampled cells of
+/// `resolution`, so the result may be off by one depth for targets close to
+/// the size of the largest cells. Since the cells shrink with every depth,
+/// only a few depths need to be measured.
+///
+/// Returns `None` if even the cells of depth 29 are too large.
+pub fn depth_for_resolution(target: f64, measure: Measure, ellipsoid: &Ellipsoid) -> Option<u8> {
+    let depths: Vec<u8> = (0..=29).collect();
+    let index = depths
+        .partition_point(|&depth| resolution(depth, ellipsoid).statistics(measure).max > target);
+
+    depths.get(index).copied()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ellipsoid::{ReferenceEllipsoid, ReferenceSphere};
+    use geodesy::ellps::Ellipsoid as GeodesyEllipsoid;
+    use std::f64::consts::PI;
+
+    #[test]
+    fn test_resolution_sphere() {
+        let radius = 6370997.0;
+        let ellipsoid = Ellipsoid::Sphere(ReferenceSphere::new(GeodesyEllipsoid::new(radius, 0.0)));
+
+        let actual = resolution(3, &ellipsoid);
+        let expected_area = 4.0 * PI * radius.powi(2) / actual.n_cells as f64;
+
+        assert_eq!(actual.n_cells, 768);
+        assert!((actual.area.mean - expected_area).abs() / expected_area < 1e-3);
+        for statistics in [actual.area, actual.edge_length, actual.diagonal] {
+            assert!(statistics.min <= statistics.mean);
+            assert!(statistics.mean <= statistics.max);
+        }
+        assert!(actual.diagonal.mean > actual.edge_length.mean);
+    }
+
+    #[test]
+    fn test_resolution_sampled() {
+        let ellipsoid = Ellipsoid::Ellipsoid(ReferenceEllipsoid::new(
+            GeodesyEllipsoid::named("WGS84").unwrap(),
+        ));
+
+        let coarse = resolution(MAX_EXHAUSTIVE_DEPTH, &ellipsoid);
+        let fine = resolution(MAX_EXHAUSTIVE_DEPTH + 1, &ellipsoid);
+
+        // cells are split into four at each depth
+        let ratio = coarse.area.mean / fine.area.mean;
+        assert!((ratio - 4.0).abs() < 0.05);
+        let ratio = coarse.edge_length.mean / fine.edge_length.mean;
+        assert!((ratio - 2.0).abs() < 0.05);
+    }
+
+    #[test]
+    fn test_resolution_sampled_extremes() {
+        let ellipsoid = Ellipsoid::Ellipsoid(ReferenceEllipsoid::new(
+            GeodesyEllipsoid::named("WGS84").unwrap(),
+        ));
+
+        let coarse = resolution(MAX_EXHAUSTIVE_DEPTH, &ellipsoid);
+        let fine = resolution(MAX_EXHAUSTIVE_DEPTH + 1, &ellipsoid);
+
+        // the largest and smallest cells keep their shape when split
+        let ratio = coarse.edge_length.max / fine.edge_length.max;
+        assert!((ratio - 2.0).abs() < 0.1);
+        let ratio = coarse.edge_length.min / fine.edge_length.min;
+        assert!((ratio - 2.0).abs() < 0.1);
+    }
+
+    #[test]
+    fn test_depth_for_resolution() {
+        let ellipsoid = Ellipsoid::Ellipsoid(ReferenceEllipsoid::new(
+            GeodesyEllipsoid::named("WGS84").unwrap(),
+        ));
+
+        let depth = depth_for_resolution(100_000.0, Measure::EdgeLength, &ellipsoid).unwrap();
+
+        assert!(resolution(depth, &ellipsoid).edge_length.max <= 100_000.0);
+        assert!(resolution(depth - 1, &ellipsoid).edge_length.max > 100_000.0);
+    }
+}
@@ -1,4 +1,8 @@
-use crate::ellipsoid::{Ellipsoid, ReferenceBody};
+use crate::ellipsoid::{Ellipsoid, LatitudeKind, ReferenceBody};
+use cdshealpix as healpix;
+use geodesy::ellps::EllipsoidBase;
+use geographiclib_rs::{Geodesic, InverseGeodesic};
+use num_traits::{AsPrimitive, Float};
 
 pub fn lonlat_to_cartesian(lon: &f64, lat: &f64, ellipsoid: &Ellipsoid) -> (f64, f64, f64) {
     let p = (lon.to_radians(), lat.to_radians());
@@ -13,10 +17,134 @@ pub fn cartesian_to_lonlat(x: &f64, y: &f64, z: &f64, ellipsoid: &Ellipsoid) ->
     (lon.to_degrees(), lat.to_degrees())
 }
 
+/// Convert a point given in degrees and meters above the ellipsoid to cartesian coordinates
+pub fn lonlat_height_to_cartesian(
+    lon: &f64,
+    lat: &f64,
+    height: &f64,
+    ellipsoid: &Ellipsoid,
+) -> (f64, f64, f64) {
+    let p = (lon.to_radians(), lat.to_radians(), *height);
+
+    ellipsoid.geographic_height_to_cartesian(&p)
+}
+
+/// Convert cartesian coordinates to degrees and meters above the ellipsoid
+pub fn cartesian_to_lonlat_height(
+    x: &f64,
+    y: &f64,
+    z: &f64,
+    ellipsoid: &Ellipsoid,
+) -> (f64, f64, f64) {
+    let p = (*x, *y, *z);
+    let (lon, lat, height) = ellipsoid.cartesian_to_geographic_height(&p);
+
+    (lon.to_degrees(), lat.to_degrees(), height)
+}
+
+/// Convert a latitude in degrees between two kinds of latitudes
+pub fn convert_latitude(
+    lat: &f64,
+    from: LatitudeKind,
+    to: LatitudeKind,
+    ellipsoid: &Ellipsoid,
+) -> f64 {
+    ellipsoid
+        .convert_latitude(lat.to_radians(), from, to)
+        .to_degrees()
+}
+
+/// Project a point given in degrees to the HEALPix plane
+///
+/// The plane coordinates are in units of π/4: `x` is in `[0, 8[`, increasing
+/// eastwards from longitude 0, and `y` is in `[-2, 2]`, from the south to the
+/// north pole. The projection is applied to the authalic coordinates, so equal
+/// areas on the ellipsoid map to equal areas on the plane.
+pub fn lonlat_to_projection<T: Float + AsPrimitive<f64>>(
+    lon: &T,
+    lat: &T,
+    ellipsoid: &Ellipsoid,
+) -> (T, T)
+where
+    f64: AsPrimitive<T>,
+{
+    let (lon, lat): (f64, f64) = (lon.as_(), lat.as_());
+    let (lon_, lat_) =
+        ellipsoid.geographic_to_authalic(&(lon.rem_euclid(360.0).to_radians(), lat.to_radians()));
+    let (x, y) = healpix::proj(lon_, lat_);
+
+    (x.as_(), y.as_())
+}
+
+/// Inverse of `lonlat_to_projection`, returning degrees
+///
+/// Returns `None` if the point is not inside the projection of the sphere:
+/// outside of `x` in `[0, 8[` and `y` in `[-2, 2]`, or in the gaps between the
+/// triangles of the polar base cells.
+pub fn projection_to_lonlat<T: Float + AsPrimitive<f64>>(
+    x: &T,
+    y: &T,
+    ellipsoid: &Ellipsoid,
+) -> Option<(T, T)>
+where
+    f64: AsPrimitive<T>,
+{
+    let (x, y): (f64, f64) = (x.as_(), y.as_());
+    if !is_in_projection(x, y) {
+        return None;
+    }
+
+    let position = healpix::unproj(x, y);
+    let (lon, lat) = ellipsoid.authalic_to_geographic(&position);
+
+    Some((
+        lon.to_degrees().rem_euclid(360.0).as_(),
+        lat.to_degrees().as_(),
+    ))
+}
+
+/// Whether the point is covered by the projection of the sphere
+///
+/// Beyond `|y| = 1` only the triangles of the polar base cells are covered,
+/// centered on the odd values of `x` and narrowing towards the poles.
+fn is_in_projection(x: f64, y: f64) -> bool {
+    if !(0.0..8.0).contains(&x) || !(-2.0..=2.0).contains(&y) {
+        return false;
+    }
+
+    let column_center = 2.0 * (x / 2.0).floor() + 1.0;
+
+    // tolerate the rounding of points projected onto the edges of the triangles
+    (x - column_center).abs() <= 2.0 - y.abs() + 1e-12
+}
+
+/// Geodesic solver for the surface of the ellipsoid
+///
+/// Construct it once and reuse it for many distance computations. Triaxial
+/// ellipsoids are not supported.
+pub fn geodesic(ellipsoid: &Ellipsoid) -> Geodesic {
+    if ellipsoid.is_triaxial() {
+        // TODO: custom error type
+        panic!("geodesics are not supported on triaxial ellipsoids");
+    }
+
+    let base = ellipsoid.ellipsoid();
+
+    Geodesic::new(base.semimajor_axis(), base.flattening())
+}
+
+/// Length of the shortest path between two points on the ellipsoid, in meters
+///
+/// The points are given as longitude and latitude in degrees. Uses Karney's
+/// algorithm.
+pub fn geodesic_distance(from: &(f64, f64), to: &(f64, f64), geodesic: &Geodesic) -> f64 {
+    geodesic.inverse(from.1, from.0, to.1, to.0)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::ellipsoid::ReferenceEllipsoid;
+    use crate::ellipsoid::{ReferenceEllipsoid, ReferenceTriaxial};
     use geodesy::ellps::Ellipsoid as GeodesyEllipsoid;
 
     #[test]
@@ -66,4 +194,116 @@ mod tests {
         assert_eq!(lon, -180.0);
         assert_eq!(lat, 75.0);
     }
+
+    #[test]
+    fn test_height_roundtrip() {
+        let base_ellipsoid = GeodesyEllipsoid::named("WGS84").unwrap();
+        let ellipsoid = Ellipsoid::Ellipsoid(ReferenceEllipsoid::new(base_ellipsoid));
+
+        let (lon, lat, height) = (83.57142857, 48.26869833, 35786000.0);
+
+        let (x, y, z) = lonlat_height_to_cartesian(&lon, &lat, &height, &ellipsoid);
+        let surface = lonlat_to_cartesian(&lon, &lat, &ellipsoid);
+
+        // the height is measured along the normal of the ellipsoid
+        let distance =
+            ((x - surface.0).powi(2) + (y - surface.1).powi(2) + (z - surface.2).powi(2)).sqrt();
+        assert!((distance - height).abs() < 1e-6);
+
+        let actual = cartesian_to_lonlat_height(&x, &y, &z, &ellipsoid);
+        assert!((actual.0 - lon).abs() < 1e-8);
+        assert!((actual.1 - lat).abs() < 1e-8);
+        assert!((actual.2 - height).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_projection() {
+        let ellipsoid = Ellipsoid::named("WGS84").unwrap();
+
+        let (x, y) = lonlat_to_projection(&90.0_f64, &0.0, &ellipsoid);
+        assert!((x - 2.0).abs() < 1e-12);
+        assert!(y.abs() < 1e-12);
+
+        let (_, y) = lonlat_to_projection(&10.0_f64, &90.0, &ellipsoid);
+        assert!((y - 2.0).abs() < 1e-12);
+
+        // cell centers project to the centers of the projected cells
+        let layer = healpix::nested::get(4);
+        for hash in [0, 1000, 3071] {
+            let (lon, lat) =
+                crate::scalar::nested::coordinates::healpix_to_lonlat(&hash, layer, &ellipsoid);
+            let (x, y) = lonlat_to_projection(&lon, &lat, &ellipsoid);
+            let expected = layer.center_of_projected_cell(hash);
+
+            assert!((x - expected.0).abs() < 1e-9, "{hash}");
+            assert!((y - expected.1).abs() < 1e-9, "{hash}");
+
+            let actual = projection_to_lonlat(&x, &y, &ellipsoid).unwrap();
+            assert!((actual.0 - lon).abs() < 1e-9, "{hash}");
+            assert!((actual.1 - lat).abs() < 1e-9, "{hash}");
+
+            let (x_, y_) = lonlat_to_projection(&(lon as f32), &(lat as f32), &ellipsoid);
+            assert!((x_ as f64 - x).abs() < 1e-5, "{hash}");
+            assert!((y_ as f64 - y).abs() < 1e-5, "{hash}");
+
+            let (lon_, lat_) = projection_to_lonlat(&x_, &y_, &ellipsoid).unwrap();
+            assert!((lon_ as f64 - lon).abs() < 1e-4, "{hash}");
+            assert!((lat_ as f64 - lat).abs() < 1e-4, "{hash}");
+        }
+
+        // points close to the poles or on the edges of the polar triangles
+        for (lon, lat) in [(10.0, 89.99), (250.0, -89.99), (90.0, 60.0), (180.0, -75.0)] {
+            let (x, y) = lonlat_to_projection(&lon, &lat, &ellipsoid);
+            let (_, lat_) = projection_to_lonlat(&x, &y, &ellipsoid).unwrap();
+            assert!((lat_ - lat).abs() < 1e-9, "{lon}, {lat}");
+        }
+    }
+
+    #[test]
+    fn test_projection_outside() {
+        let ellipsoid = Ellipsoid::named("WGS84").unwrap();
+
+        // gaps between the polar triangles
+        assert_eq!(projection_to_lonlat(&2.0, &1.5, &ellipsoid), None);
+        assert_eq!(projection_to_lonlat(&0.1, &-1.95, &ellipsoid), None);
+        assert_eq!(projection_to_lonlat(&2.0_f32, &-1.5, &ellipsoid), None);
+        // inside the polar triangles
+        assert!(projection_to_lonlat(&1.0, &1.5, &ellipsoid).is_some());
+        assert!(projection_to_lonlat(&6.8, &-1.2, &ellipsoid).is_some());
+
+        // outside of the plane
+        assert_eq!(projection_to_lonlat(&8.0, &0.0, &ellipsoid), None);
+        assert_eq!(projection_to_lonlat(&-0.5, &0.0, &ellipsoid), None);
+        assert_eq!(projection_to_lonlat(&1.0, &2.5, &ellipsoid), None);
+        assert_eq!(projection_to_lonlat(&f64::NAN, &0.0, &ellipsoid), None);
+    }
+
+    #[test]
+    fn test_geodesic_distance() {
+        let base_ellipsoid = GeodesyEllipsoid::named("WGS84").unwrap();
+        let ellipsoid = Ellipsoid::Ellipsoid(ReferenceEllipsoid::new(base_ellipsoid));
+        let geodesic = geodesic(&ellipsoid);
+
+        // a quarter of the equator
+        let actual = geodesic_distance(&(0.0, 0.0), &(90.0, 0.0), &geodesic);
+        assert!((actual - 10018754.171394622).abs() < 1e-6);
+
+        // a quarter of a meridian
+        let actual = geodesic_distance(&(0.0, 0.0), &(0.0, 90.0), &geodesic);
+        assert!((actual - 10001965.729312724).abs() < 1e-6);
+
+        assert_eq!(
+            geodesic_distance(&(10.0, 20.0), &(10.0, 20.0), &geodesic),
+            0.0
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "geodesics are not supported on triaxial ellipsoids")]
+    fn test_geodesic_triaxial() {
+        let ellipsoid =
+            Ellipsoid::Triaxial(ReferenceTriaxial::new(6378171.36, 6378102.0, 6356752.0));
+
+        geodesic(&ellipsoid);
+    }
 }
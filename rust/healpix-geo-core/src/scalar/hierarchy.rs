@@ -0,0 +1,134 @@
+use cdshealpix::compass_point::MainWind;
+use cdshealpix::nested::Layer;
+
+/// The directions of the cells around and including a cell
+const DIRECTIONS: [MainWind; 9] = [
+    MainWind::S,
+    MainWind::SE,
+    MainWind::E,
+    MainWind::SW,
+    MainWind::C,
+    MainWind::NE,
+    MainWind::W,
+    MainWind::NW,
+    MainWind::N,
+];
+
+/// The number of cells in the kth ring around a cell
+///
+/// 4 sides with each 2 r + 1 values, minus 4 joints: 4 * (2r + 1) - 4 = 8r
+pub fn n_neighbours(ring: &u32) -> usize {
+    8 * *ring as usize
+}
+
+/// The number of cells in the neighbourhood of radius k around a cell
+pub fn n_neighbourhood(ring: &u32) -> usize {
+    usize::pow((2 * ring + 1) as usize, 2)
+}
+
+/// Write the nested neighbours in the kth ring around a cell into `row`
+///
+/// The neighbours are converted with `convert` and keep the order of
+/// `Layer::kth_neighbours`. Missing neighbours are set to -1. Rings of
+/// radius 1 are computed without heap allocations, larger rings go through
+/// `Layer::kth_neighbours`.
+pub(crate) fn kth_neighbours_into<F>(
+    hash: u64,
+    layer: &Layer,
+    ring: &u32,
+    row: &mut [i64],
+    convert: F,
+) where
+    F: Fn(u64) -> u64,
+{
+    match ring {
+        0 => {}
+        1 => write_neighbours(hash, layer, false, row, convert),
+        _ => write_converted(layer.kth_neighbours(hash, *ring), row, convert),
+    }
+}
+
+/// Write the nested cells within the kth ring around a cell into `row`
+///
+/// Same as `kth_neighbours_into`, but including the cell itself and the
+/// inner rings.
+pub(crate) fn kth_neighbourhood_into<F>(
+    hash: u64,
+    layer: &Layer,
+    ring: &u32,
+    row: &mut [i64],
+    convert: F,
+) where
+    F: Fn(u64) -> u64,
+{
+    match ring {
+        0 => write_converted([hash], row, convert),
+        1 => write_neighbours(hash, layer, true, row, convert),
+        _ => write_converted(layer.kth_neighbourhood(hash, *ring), row, convert),
+    }
+}
+
+fn write_neighbours<F>(hash: u64, layer: &Layer, include_center: bool, row: &mut [i64], convert: F)
+where
+    F: Fn(u64) -> u64,
+{
+    let neighbours = layer.neighbours(hash, include_center);
+
+    let mut values = [0u64; DIRECTIONS.len()];
+    let mut count = 0;
+    for direction in DIRECTIONS {
+        if let Some(&neighbour) = neighbours.get(direction) {
+            values[count] = neighbour;
+            count += 1;
+        }
+    }
+    values[..count].sort_unstable();
+
+    write_converted(values[..count].iter().copied(), row, convert);
+}
+
+fn write_converted<I, F>(neighbours: I, row: &mut [i64], convert: F)
+where
+    I: IntoIterator<Item = u64>,
+    F: Fn(u64) -> u64,
+{
+    row.fill(-1);
+    for (value, neighbour) in row.iter_mut().zip(neighbours) {
+        *value = convert(neighbour) as i64;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_matches_layer() {
+        let layer = cdshealpix::nested::get(2);
+
+        // includes cells at the corners of base cells, with missing neighbours
+        for hash in [0, 5, 15, 16, 63, 100, 191] {
+            for ring in [0, 1, 2] {
+                let mut row = vec![0i64; n_neighbours(&ring)];
+                kth_neighbours_into(hash, layer, &ring, &mut row, |h| h);
+                let mut expected: Vec<i64> = layer
+                    .kth_neighbours(hash, ring)
+                    .into_iter()
+                    .map(|h| h as i64)
+                    .collect();
+                expected.resize(row.len(), -1);
+                assert_eq!(row, expected, "neighbours of {hash} in ring {ring}");
+
+                let mut row = vec![0i64; n_neighbourhood(&ring)];
+                kth_neighbourhood_into(hash, layer, &ring, &mut row, |h| h);
+                let mut expected: Vec<i64> = layer
+                    .kth_neighbourhood(hash, ring)
+                    .into_iter()
+                    .map(|h| h as i64)
+                    .collect();
+                expected.resize(row.len(), -1);
+                assert_eq!(row, expected, "neighbourhood of {hash} in ring {ring}");
+            }
+        }
+    }
+}
@@ -3,3 +3,4 @@ pub mod ring;
 pub mod zuniq;
 
 pub mod geometry;
+pub mod hierarchy;
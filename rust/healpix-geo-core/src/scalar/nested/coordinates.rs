@@ -2,25 +2,61 @@ use crate::ellipsoid::{Ellipsoid, ReferenceBody};
 
 use cdshealpix::compass_point::Cardinal;
 use cdshealpix::nested::Layer;
+use num_traits::{AsPrimitive, Float};
 
 pub fn healpix_to_lonlat(hash: &u64, layer: &Layer, ellipsoid: &Ellipsoid) -> (f64, f64) {
     let center = layer.center(*hash);
 
-    let lon = center.0.to_degrees().rem_euclid(360.0);
-    let lat = ellipsoid
-        .latitude_authalic_to_geographic(center.1)
-        .to_degrees();
+    let (lon, lat) = ellipsoid.authalic_to_geographic(&center);
 
-    (lon, lat)
+    (lon.to_degrees().rem_euclid(360.0), lat.to_degrees())
 }
 
-pub fn lonlat_to_healpix(lon: &f64, lat: &f64, layer: &Layer, ellipsoid: &Ellipsoid) -> u64 {
-    let lon_ = lon.rem_euclid(360.0).to_radians();
-    let lat_ = ellipsoid.latitude_geographic_to_authalic(lat.to_radians());
+pub fn lonlat_to_healpix<T: Float + AsPrimitive<f64>>(
+    lon: &T,
+    lat: &T,
+    layer: &Layer,
+    ellipsoid: &Ellipsoid,
+) -> u64 {
+    let (lon, lat) = (lon.as_(), lat.as_());
+    let (lon_, lat_) =
+        ellipsoid.geographic_to_authalic(&(lon.rem_euclid(360.0).to_radians(), lat.to_radians()));
 
     layer.hash(lon_, lat_)
 }
 
+/// Cell containing the point, and the position of the point inside the cell
+///
+/// The offsets `dx` and `dy` are between 0 and 1, measured from the south
+/// vertex of the cell along its south-east and south-west edges.
+pub fn lonlat_to_healpix_with_offset<T: Float + AsPrimitive<f64>>(
+    lon: &T,
+    lat: &T,
+    layer: &Layer,
+    ellipsoid: &Ellipsoid,
+) -> (u64, f64, f64) {
+    let (lon, lat) = (lon.as_(), lat.as_());
+    let (lon_, lat_) =
+        ellipsoid.geographic_to_authalic(&(lon.rem_euclid(360.0).to_radians(), lat.to_radians()));
+
+    layer.hash_with_dxdy(lon_, lat_)
+}
+
+/// Inverse of `lonlat_to_healpix_with_offset`
+pub fn healpix_with_offset_to_lonlat(
+    hash: &u64,
+    dx: &f64,
+    dy: &f64,
+    layer: &Layer,
+    ellipsoid: &Ellipsoid,
+) -> (f64, f64) {
+    let position = layer.sph_coo(*hash, *dx, *dy);
+
+    let (lon, lat) = ellipsoid.authalic_to_geographic(&position);
+
+    (lon.to_degrees().rem_euclid(360.0), lat.to_degrees())
+}
+
 pub fn vertices(hash: &u64, layer: &Layer, ellipsoid: &Ellipsoid, step: &usize) -> Vec<(f64, f64)> {
     let vertices: Vec<(f64, f64)> = if *step == 1 {
         layer.vertices(*hash).into()
@@ -32,48 +68,54 @@ pub fn vertices(hash: &u64, layer: &Layer, ellipsoid: &Ellipsoid, step: &usize)
 
     vertices
         .into_iter()
-        .map(|(lon, lat): (f64, f64)| {
-            (
-                lon.to_degrees().rem_euclid(360.0),
-                ellipsoid.latitude_authalic_to_geographic(lat).to_degrees(),
-            )
+        .map(|vertex: (f64, f64)| {
+            let (lon, lat) = ellipsoid.authalic_to_geographic(&vertex);
+
+            (lon.to_degrees().rem_euclid(360.0), lat.to_degrees())
         })
         .collect()
 }
 
 pub fn healpix_to_cartesian(hash: &u64, layer: &Layer, ellipsoid: &Ellipsoid) -> (f64, f64, f64) {
+    healpix_to_cartesian_at_height(hash, layer, &0.0, ellipsoid)
+}
+
+/// Cartesian coordinates of the point `height` meters above the cell center
+pub fn healpix_to_cartesian_at_height(
+    hash: &u64,
+    layer: &Layer,
+    height: &f64,
+    ellipsoid: &Ellipsoid,
+) -> (f64, f64, f64) {
     let center = layer.center(*hash);
-    let p = (
-        center.0,
-        ellipsoid.latitude_authalic_to_geographic(center.1),
-    );
+    let (lon, lat) = ellipsoid.authalic_to_geographic(&center);
+    let p = (lon, lat, *height);
 
-    ellipsoid.geographic_to_cartesian(&p)
+    ellipsoid.geographic_height_to_cartesian(&p)
 }
 
-pub fn cartesian_to_healpix(
-    x: &f64,
-    y: &f64,
-    z: &f64,
+pub fn cartesian_to_healpix<T: Float + AsPrimitive<f64>>(
+    x: &T,
+    y: &T,
+    z: &T,
     layer: &Layer,
     ellipsoid: &Ellipsoid,
 ) -> u64 {
-    let p = (*x, *y, *z);
-    let (lon, lat) = ellipsoid.cartesian_to_geographic(&p);
-
-    let lat_ = ellipsoid.latitude_geographic_to_authalic(lat);
+    let p = (x.as_(), y.as_(), z.as_());
+    let (lon_, lat_) = ellipsoid.geographic_to_authalic(&ellipsoid.cartesian_to_geographic(&p));
 
-    layer.hash(lon, lat_)
+    layer.hash(lon_, lat_)
 }
 
-pub fn bilinear_interpolation(
-    lon: &f64,
-    lat: &f64,
+pub fn bilinear_interpolation<T: Float + AsPrimitive<f64>>(
+    lon: &T,
+    lat: &T,
     layer: &Layer,
     ellipsoid: &Ellipsoid,
 ) -> Vec<(u64, f64)> {
-    let lon_ = lon.rem_euclid(360.0).to_radians();
-    let lat_ = ellipsoid.latitude_geographic_to_authalic(lat.to_radians());
+    let (lon, lat) = (lon.as_(), lat.as_());
+    let (lon_, lat_) =
+        ellipsoid.geographic_to_authalic(&(lon.rem_euclid(360.0).to_radians(), lat.to_radians()));
 
     layer.bilinear_interpolation(lon_, lat_).to_vec()
 }
@@ -172,6 +214,29 @@ mod tests {
         assert_eq!(actual, 0);
     }
 
+    #[test]
+    fn test_offset_roundtrip() {
+        let layer = healpix::nested::get(6);
+        let ellipsoid = Ellipsoid::named("WGS84").unwrap();
+
+        for (lon, lat) in [(0.3, 0.2), (45.0, 60.0), (123.4, -41.7), (359.9, 89.5)] {
+            let (hash, dx, dy) = lonlat_to_healpix_with_offset(&lon, &lat, layer, &ellipsoid);
+            assert_eq!(hash, lonlat_to_healpix(&lon, &lat, layer, &ellipsoid));
+            assert!((0.0..1.0).contains(&dx) && (0.0..1.0).contains(&dy));
+
+            let (actual_lon, actual_lat) =
+                healpix_with_offset_to_lonlat(&hash, &dx, &dy, layer, &ellipsoid);
+            assert!((actual_lon - lon).abs() < 1e-9);
+            assert!((actual_lat - lat).abs() < 1e-9);
+        }
+
+        // the middle of the cell is its center
+        let (lon, lat) = healpix_with_offset_to_lonlat(&100, &0.5, &0.5, layer, &ellipsoid);
+        let expected = healpix_to_lonlat(&100, layer, &ellipsoid);
+        assert!((lon - expected.0).abs() < 1e-12);
+        assert!((lat - expected.1).abs() < 1e-12);
+    }
+
     #[test]
     fn test_bilinear_interpolation() {
         let layer = healpix::nested::get(1);
@@ -41,20 +41,65 @@ fn get_flat_cells(bmoc: BMOC) -> (Vec<u64>, Vec<u8>, Vec<bool>) {
     (ipix, depth, fully_covered)
 }
 
+/// The zone on the authalic sphere containing a geographic zone, in radians
+///
+/// On triaxial bodies the authalic latitude of a parallel depends on the
+/// longitude. It changes monotonically between the quarter meridians, so the
+/// extremes are found at the edges of the zone or on a quarter meridian.
+pub(crate) fn authalic_zone(
+    bbox: (f64, f64, f64, f64),
+    ellipsoid: &Ellipsoid,
+) -> (f64, f64, f64, f64) {
+    let (lon_min, lat_min, lon_max, lat_max) = bbox;
+    let lon_min = lon_min.rem_euclid(360.0);
+    let lon_max = lon_max.rem_euclid(360.0);
+    let width = (lon_max - lon_min).rem_euclid(360.0);
+
+    let longitudes: Vec<f64> = [lon_min, lon_max, 0.0, 90.0, 180.0, 270.0]
+        .into_iter()
+        .filter(|lon| (lon - lon_min).rem_euclid(360.0) <= width)
+        .map(f64::to_radians)
+        .collect();
+    let authalic_latitude = |lon: &f64, lat: f64| {
+        ellipsoid
+            .geographic_to_authalic(&(*lon, lat.to_radians()))
+            .1
+    };
+
+    (
+        ellipsoid
+            .geographic_to_authalic(&(lon_min.to_radians(), 0.0))
+            .0,
+        longitudes
+            .iter()
+            .map(|lon| authalic_latitude(lon, lat_min))
+            .fold(f64::INFINITY, f64::min),
+        ellipsoid
+            .geographic_to_authalic(&(lon_max.to_radians(), 0.0))
+            .0,
+        longitudes
+            .iter()
+            .map(|lon| authalic_latitude(lon, lat_max))
+            .fold(f64::NEG_INFINITY, f64::max),
+    )
+}
+
+/// Convert a geographic point in degrees to the authalic sphere, in radians
+fn to_authalic(point: &(f64, f64), ellipsoid: &Ellipsoid) -> (f64, f64) {
+    let (lon, lat) = point;
+
+    ellipsoid.geographic_to_authalic(&(lon.rem_euclid(360.0).to_radians(), lat.to_radians()))
+}
+
 pub fn zone_coverage(
     bbox: (f64, f64, f64, f64),
     layer: &Layer,
     ellipsoid: &Ellipsoid,
     flat: bool,
 ) -> (Vec<u64>, Vec<u8>, Vec<bool>) {
-    let (lon_min, lat_min, lon_max, lat_max) = bbox;
+    let (lon_min, lat_min, lon_max, lat_max) = authalic_zone(bbox, ellipsoid);
 
-    let bmoc = layer.zone_coverage(
-        lon_min.rem_euclid(360.0).to_radians(),
-        ellipsoid.latitude_geographic_to_authalic(lat_min.to_radians()),
-        lon_max.rem_euclid(360.0).to_radians(),
-        ellipsoid.latitude_geographic_to_authalic(lat_max.to_radians()),
-    );
+    let bmoc = layer.zone_coverage(lon_min, lat_min, lon_max, lat_max);
 
     if flat {
         get_flat_cells(bmoc)
@@ -71,12 +116,12 @@ pub fn box_coverage(
     ellipsoid: &Ellipsoid,
     flat: bool,
 ) -> (Vec<u64>, Vec<u8>, Vec<bool>) {
-    let (lon, lat) = center;
+    let (lon, lat) = to_authalic(&center, ellipsoid);
     let (size_lon, size_lat) = size;
 
     let bmoc = layer.box_coverage(
-        lon.rem_euclid(360.0).to_radians(),
-        ellipsoid.latitude_geographic_to_authalic(lat.to_radians()),
+        lon,
+        lat,
         size_lon.rem_euclid(360.0).to_radians(),
         size_lat.to_radians(),
         angle.to_radians(),
@@ -96,17 +141,8 @@ pub fn polygon_coverage(
     exact: bool,
     flat: bool,
 ) -> (Vec<u64>, Vec<u8>, Vec<bool>) {
-    let converted_vertices: Vec<(f64, f64)> = vertices
-        .iter()
-        .map(|v| {
-            let (lon, lat) = v;
-
-            (
-                lon.rem_euclid(360.0).to_radians(),
-                ellipsoid.latitude_geographic_to_authalic(lat.to_radians()),
-            )
-        })
-        .collect();
+    let converted_vertices: Vec<(f64, f64)> =
+        vertices.iter().map(|v| to_authalic(v, ellipsoid)).collect();
 
     let bmoc = layer.polygon_coverage(&converted_vertices, exact);
 
@@ -130,14 +166,9 @@ pub fn cone_coverage(
         panic!("delta_depth must be chosen such that layer.depth() + delta_depth <= 29");
     }
 
-    let (lon, lat) = center;
+    let (lon, lat) = to_authalic(&center, ellipsoid);
 
-    let bmoc = layer.cone_coverage_approx_custom(
-        delta_depth,
-        lon.rem_euclid(360.0).to_radians(),
-        ellipsoid.latitude_geographic_to_authalic(lat.to_radians()),
-        radius.to_radians(),
-    );
+    let bmoc = layer.cone_coverage_approx_custom(delta_depth, lon, lat, radius.to_radians());
 
     if flat {
         get_flat_cells(bmoc)
@@ -160,14 +191,14 @@ pub fn elliptical_cone_coverage(
         panic!("delta_depth must be chosen such that layer.depth() + delta_depth <= 29");
     }
 
-    let (lon, lat) = center;
+    let (lon, lat) = to_authalic(&center, ellipsoid);
 
     let (a, b) = ellipse_geometry;
 
     let bmoc = layer.elliptical_cone_coverage_custom(
         delta_depth,
-        lon.rem_euclid(360.0).to_radians(),
-        ellipsoid.latitude_geographic_to_authalic(lat.to_radians()),
+        lon,
+        lat,
         a.to_radians(),
         b.to_radians(),
         position_angle.to_radians(),
@@ -1,30 +1,31 @@
 use cdshealpix::nested::Layer;
 
-pub fn kth_neighbours(hash: &u64, layer: &Layer, ring: &u32) -> Vec<i64> {
-    let r = *ring;
-    let mut result = layer
-        .kth_neighbours(*hash, r)
-        .into_iter()
-        .map(|v| v as i64)
-        .collect::<Vec<i64>>();
+use crate::scalar::hierarchy::{self as shared, n_neighbourhood, n_neighbours};
 
-    // 4 sides with each 2 r + 1 values, minus 4 joints: 4 * (2r + 1) - 4 = 8r
-    result.resize(8 * r as usize, -1i64);
+pub fn kth_neighbours(hash: &u64, layer: &Layer, ring: &u32) -> Vec<i64> {
+    let mut result = vec![-1i64; n_neighbours(ring)];
+    kth_neighbours_into(hash, layer, ring, &mut result);
 
     result
 }
 
-pub fn kth_neighbourhood(hash: &u64, layer: &Layer, ring: &u32) -> Vec<i64> {
-    let mut neighbours: Vec<i64> = layer
-        .kth_neighbourhood(*hash, *ring)
-        .into_iter()
-        .map(|v| v as i64)
-        .collect();
+/// Like `kth_neighbours`, but writes into a row of `n_neighbours(ring)` values
+///
+/// Missing neighbours are set to -1.
+pub fn kth_neighbours_into(hash: &u64, layer: &Layer, ring: &u32, row: &mut [i64]) {
+    shared::kth_neighbours_into(*hash, layer, ring, row, |h| h);
+}
 
-    let expected_size = usize::pow((2 * ring + 1) as usize, 2);
-    if neighbours.len() < expected_size {
-        neighbours.resize(expected_size, -1);
-    }
+pub fn kth_neighbourhood(hash: &u64, layer: &Layer, ring: &u32) -> Vec<i64> {
+    let mut neighbours = vec![-1i64; n_neighbourhood(ring)];
+    kth_neighbourhood_into(hash, layer, ring, &mut neighbours);
 
     neighbours
 }
+
+/// Like `kth_neighbourhood`, but writes into a row of `n_neighbourhood(ring)` values
+///
+/// Missing neighbours are set to -1.
+pub fn kth_neighbourhood_into(hash: &u64, layer: &Layer, ring: &u32, row: &mut [i64]) {
+    shared::kth_neighbourhood_into(*hash, layer, ring, row, |h| h);
+}
@@ -3,25 +3,65 @@ use crate::ellipsoid::{Ellipsoid, ReferenceBody};
 use cdshealpix as healpix;
 use cdshealpix::compass_point::Cardinal;
 use cdshealpix::nested::Layer;
+use num_traits::{AsPrimitive, Float};
 
 pub fn healpix_to_lonlat(hash: &u64, nside: &u32, ellipsoid: &Ellipsoid) -> (f64, f64) {
     let center = healpix::ring::center(*nside, *hash);
 
-    let lon = center.0.to_degrees().rem_euclid(360.0);
-    let lat = ellipsoid
-        .latitude_authalic_to_geographic(center.1)
-        .to_degrees();
+    let (lon, lat) = ellipsoid.authalic_to_geographic(&center);
 
-    (lon, lat)
+    (lon.to_degrees().rem_euclid(360.0), lat.to_degrees())
 }
 
-pub fn lonlat_to_healpix(lon: &f64, lat: &f64, nside: &u32, ellipsoid: &Ellipsoid) -> u64 {
-    let lon_ = lon.rem_euclid(360.0).to_radians();
-    let lat_ = ellipsoid.latitude_geographic_to_authalic(lat.to_radians());
+pub fn lonlat_to_healpix<T: Float + AsPrimitive<f64>>(
+    lon: &T,
+    lat: &T,
+    nside: &u32,
+    ellipsoid: &Ellipsoid,
+) -> u64 {
+    let (lon, lat) = (lon.as_(), lat.as_());
+    let (lon_, lat_) =
+        ellipsoid.geographic_to_authalic(&(lon.rem_euclid(360.0).to_radians(), lat.to_radians()));
 
     healpix::ring::hash(*nside, lon_, lat_)
 }
 
+/// Cell containing the point, and the position of the point inside the cell
+///
+/// The offsets are the same as for the nested scheme.
+pub fn lonlat_to_healpix_with_offset<T: Float + AsPrimitive<f64>>(
+    lon: &T,
+    lat: &T,
+    nside: &u32,
+    ellipsoid: &Ellipsoid,
+) -> (u64, f64, f64) {
+    let layer = healpix::nested::get(healpix::depth(*nside));
+    let (hash, dx, dy) = crate::scalar::nested::coordinates::lonlat_to_healpix_with_offset(
+        lon, lat, layer, ellipsoid,
+    );
+
+    (layer.to_ring(hash), dx, dy)
+}
+
+/// Inverse of `lonlat_to_healpix_with_offset`
+pub fn healpix_with_offset_to_lonlat(
+    hash: &u64,
+    dx: &f64,
+    dy: &f64,
+    nside: &u32,
+    ellipsoid: &Ellipsoid,
+) -> (f64, f64) {
+    let layer = healpix::nested::get(healpix::depth(*nside));
+
+    crate::scalar::nested::coordinates::healpix_with_offset_to_lonlat(
+        &layer.from_ring(*hash),
+        dx,
+        dy,
+        layer,
+        ellipsoid,
+    )
+}
+
 pub fn vertices(hash: &u64, nside: &u32, ellipsoid: &Ellipsoid, step: &usize) -> Vec<(f64, f64)> {
     let vertices: Vec<(f64, f64)> = if *step == 1 {
         healpix::ring::vertices(*nside, *hash).into()
@@ -35,42 +75,54 @@ pub fn vertices(hash: &u64, nside: &u32, ellipsoid: &Ellipsoid, step: &usize) ->
 
     vertices
         .into_iter()
-        .map(|(lon, lat): (f64, f64)| {
-            (
-                lon.to_degrees().rem_euclid(360.0),
-                ellipsoid.latitude_authalic_to_geographic(lat).to_degrees(),
-            )
+        .map(|vertex: (f64, f64)| {
+            let (lon, lat) = ellipsoid.authalic_to_geographic(&vertex);
+
+            (lon.to_degrees().rem_euclid(360.0), lat.to_degrees())
         })
         .collect()
 }
 
 pub fn healpix_to_cartesian(hash: &u64, nside: &u32, ellipsoid: &Ellipsoid) -> (f64, f64, f64) {
+    healpix_to_cartesian_at_height(hash, nside, &0.0, ellipsoid)
+}
+
+/// Cartesian coordinates of the point `height` meters above the cell center
+pub fn healpix_to_cartesian_at_height(
+    hash: &u64,
+    nside: &u32,
+    height: &f64,
+    ellipsoid: &Ellipsoid,
+) -> (f64, f64, f64) {
     let center = healpix::ring::center(*nside, *hash);
-    let p = (
-        center.0,
-        ellipsoid.latitude_authalic_to_geographic(center.1),
-    );
+    let (lon, lat) = ellipsoid.authalic_to_geographic(&center);
+    let p = (lon, lat, *height);
 
-    ellipsoid.geographic_to_cartesian(&p)
+    ellipsoid.geographic_height_to_cartesian(&p)
 }
 
-pub fn cartesian_to_healpix(x: &f64, y: &f64, z: &f64, nside: &u32, ellipsoid: &Ellipsoid) -> u64 {
-    let p = (*x, *y, *z);
-    let (lon, lat) = ellipsoid.cartesian_to_geographic(&p);
-
-    let lat_ = ellipsoid.latitude_geographic_to_authalic(lat);
+pub fn cartesian_to_healpix<T: Float + AsPrimitive<f64>>(
+    x: &T,
+    y: &T,
+    z: &T,
+    nside: &u32,
+    ellipsoid: &Ellipsoid,
+) -> u64 {
+    let p = (x.as_(), y.as_(), z.as_());
+    let (lon_, lat_) = ellipsoid.geographic_to_authalic(&ellipsoid.cartesian_to_geographic(&p));
 
-    healpix::ring::hash(*nside, lon, lat_)
+    healpix::ring::hash(*nside, lon_, lat_)
 }
 
-pub fn bilinear_interpolation(
-    lon: &f64,
-    lat: &f64,
+pub fn bilinear_interpolation<T: Float + AsPrimitive<f64>>(
+    lon: &T,
+    lat: &T,
     layer: &Layer,
     ellipsoid: &Ellipsoid,
 ) -> Vec<(u64, f64)> {
-    let lon_ = lon.rem_euclid(360.0).to_radians();
-    let lat_ = ellipsoid.latitude_geographic_to_authalic(lat.to_radians());
+    let (lon, lat) = (lon.as_(), lat.as_());
+    let (lon_, lat_) =
+        ellipsoid.geographic_to_authalic(&(lon.rem_euclid(360.0).to_radians(), lat.to_radians()));
 
     layer
         .bilinear_interpolation(lon_, lat_)
@@ -122,6 +174,26 @@ mod tests {
         assert_eq!(actual, expected);
     }
 
+    #[test]
+    fn test_offset_matches_nested() {
+        let depth = 5;
+        let nside = healpix::nside(depth);
+        let layer = healpix::nested::get(depth);
+        let ellipsoid = Ellipsoid::named("WGS84").unwrap();
+
+        let (lon, lat) = (123.4, -41.7);
+        let (hash, dx, dy) = lonlat_to_healpix_with_offset(&lon, &lat, &nside, &ellipsoid);
+        let expected = crate::scalar::nested::coordinates::lonlat_to_healpix_with_offset(
+            &lon, &lat, layer, &ellipsoid,
+        );
+        assert_eq!((layer.from_ring(hash), dx, dy), expected);
+
+        let (actual_lon, actual_lat) =
+            healpix_with_offset_to_lonlat(&hash, &dx, &dy, &nside, &ellipsoid);
+        assert!((actual_lon - lon).abs() < 1e-9);
+        assert!((actual_lat - lat).abs() < 1e-9);
+    }
+
     #[test]
     fn test_lonlat_to_healpix_edge_cases_lon() {
         let nside = healpix::nside(0);
@@ -2,6 +2,7 @@ use crate::ellipsoid::Ellipsoid;
 
 use cdshealpix as healpix;
 use cdshealpix::nested::Layer;
+use num_traits::{AsPrimitive, Float};
 
 pub fn healpix_to_lonlat(hash: &u64, ellipsoid: &Ellipsoid) -> (f64, f64) {
     let (depth, hash_nested) = healpix::nested::from_zuniq(*hash);
@@ -10,13 +11,57 @@ pub fn healpix_to_lonlat(hash: &u64, ellipsoid: &Ellipsoid) -> (f64, f64) {
     crate::scalar::nested::coordinates::healpix_to_lonlat(&hash_nested, layer, ellipsoid)
 }
 
-pub fn lonlat_to_healpix(lon: &f64, lat: &f64, layer: &Layer, ellipsoid: &Ellipsoid) -> u64 {
+pub fn lonlat_to_healpix<T: Float + AsPrimitive<f64>>(
+    lon: &T,
+    lat: &T,
+    layer: &Layer,
+    ellipsoid: &Ellipsoid,
+) -> u64 {
     let hash_nested =
         crate::scalar::nested::coordinates::lonlat_to_healpix(lon, lat, layer, ellipsoid);
 
     healpix::nested::to_zuniq_unsafe(layer.depth(), hash_nested)
 }
 
+/// Cell containing the point, and the position of the point inside the cell
+///
+/// The offsets are the same as for the nested scheme.
+pub fn lonlat_to_healpix_with_offset<T: Float + AsPrimitive<f64>>(
+    lon: &T,
+    lat: &T,
+    layer: &Layer,
+    ellipsoid: &Ellipsoid,
+) -> (u64, f64, f64) {
+    let (hash_nested, dx, dy) = crate::scalar::nested::coordinates::lonlat_to_healpix_with_offset(
+        lon, lat, layer, ellipsoid,
+    );
+
+    (
+        healpix::nested::to_zuniq_unsafe(layer.depth(), hash_nested),
+        dx,
+        dy,
+    )
+}
+
+/// Inverse of `lonlat_to_healpix_with_offset`
+pub fn healpix_with_offset_to_lonlat(
+    hash: &u64,
+    dx: &f64,
+    dy: &f64,
+    ellipsoid: &Ellipsoid,
+) -> (f64, f64) {
+    let (depth, hash_nested) = healpix::nested::from_zuniq(*hash);
+    let layer = healpix::nested::get(depth);
+
+    crate::scalar::nested::coordinates::healpix_with_offset_to_lonlat(
+        &hash_nested,
+        dx,
+        dy,
+        layer,
+        ellipsoid,
+    )
+}
+
 pub fn vertices(hash: &u64, ellipsoid: &Ellipsoid, step: &usize) -> Vec<(f64, f64)> {
     let (depth, hash_nested) = healpix::nested::from_zuniq(*hash);
     let layer = healpix::nested::get(depth);
@@ -31,10 +76,27 @@ pub fn healpix_to_cartesian(hash: &u64, ellipsoid: &Ellipsoid) -> (f64, f64, f64
     crate::scalar::nested::coordinates::healpix_to_cartesian(&hash_nested, layer, ellipsoid)
 }
 
-pub fn cartesian_to_healpix(
-    x: &f64,
-    y: &f64,
-    z: &f64,
+/// Cartesian coordinates of the point `height` meters above the cell center
+pub fn healpix_to_cartesian_at_height(
+    hash: &u64,
+    height: &f64,
+    ellipsoid: &Ellipsoid,
+) -> (f64, f64, f64) {
+    let (depth, hash_nested) = healpix::nested::from_zuniq(*hash);
+    let layer = healpix::nested::get(depth);
+
+    crate::scalar::nested::coordinates::healpix_to_cartesian_at_height(
+        &hash_nested,
+        layer,
+        height,
+        ellipsoid,
+    )
+}
+
+pub fn cartesian_to_healpix<T: Float + AsPrimitive<f64>>(
+    x: &T,
+    y: &T,
+    z: &T,
     layer: &Layer,
     ellipsoid: &Ellipsoid,
 ) -> u64 {
@@ -43,9 +105,9 @@ pub fn cartesian_to_healpix(
     healpix::nested::to_zuniq_unsafe(layer.depth(), hash)
 }
 
-pub fn bilinear_interpolation(
-    lon: &f64,
-    lat: &f64,
+pub fn bilinear_interpolation<T: Float + AsPrimitive<f64>>(
+    lon: &T,
+    lat: &T,
     layer: &Layer,
     ellipsoid: &Ellipsoid,
 ) -> Vec<(u64, f64)> {
@@ -0,0 +1,326 @@
+#[cfg(not(target_arch = "wasm32"))]
+use rayon::prelude::*;
+
+use geodesy::prelude::{Context, Coor4D, CoordinateTuple, Direction, Minimal, OpHandle};
+use std::error::Error;
+use std::fmt;
+use std::sync::Arc;
+
+/// Number of coordinates handed to the operator at once
+const CHUNK_SIZE: usize = 4096;
+
+/// Error raised when transforming input coordinates
+#[derive(Debug, Clone, PartialEq)]
+pub enum TransformError {
+    /// The operator could not be parsed or applied
+    Operator(String),
+    /// The operator could not be applied to some of the coordinates
+    Failed { count: usize },
+}
+
+impl fmt::Display for TransformError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Operator(message) => write!(f, "Invalid operator: {message}"),
+            Self::Failed { count } => {
+                write!(f, "Could not transform {count} of the input coordinates.")
+            }
+        }
+    }
+}
+
+impl Error for TransformError {}
+
+/// The kind of coordinates the operator expects
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InputCoordinates {
+    /// Longitude and latitude in degrees, converted to radians for the operator
+    Geographic,
+    /// Projected or cartesian coordinates, passed to the operator unchanged
+    Projected,
+}
+
+/// A geodesy operator applied to coordinates before they are converted to cells
+///
+/// The operator can be any geodesy pipeline, for example a Helmert
+/// transformation between datums or the inverse of a UTM projection. It must
+/// produce geographic coordinates on the ellipsoid used for the HEALPix grid.
+///
+/// The definition is parsed once, and the parsed operator is shared by all
+/// clones of the transform.
+#[derive(Clone)]
+pub struct InputTransform {
+    definition: String,
+    direction: Direction,
+    input: InputCoordinates,
+    context: Arc<Minimal>,
+    op: OpHandle,
+}
+
+impl InputTransform {
+    /// Create the transform from a geodesy operator definition
+    pub fn new(definition: &str, input: InputCoordinates) -> Result<Self, TransformError> {
+        let mut context = Minimal::new();
+        let op = context
+            .op(definition)
+            .map_err(|e| TransformError::Operator(e.to_string()))?;
+
+        Ok(Self {
+            definition: definition.to_string(),
+            direction: Direction::Fwd,
+            input,
+            context: Arc::new(context),
+            op,
+        })
+    }
+
+    /// Apply the operator in the inverse direction
+    ///
+    /// This is useful for projections, which are defined from geographic to
+    /// projected coordinates.
+    pub fn inverted(mut self) -> Self {
+        self.direction = match self.direction {
+            Direction::Fwd => Direction::Inv,
+            Direction::Inv => Direction::Fwd,
+        };
+        self
+    }
+
+    pub fn definition(&self) -> &str {
+        &self.definition
+    }
+
+    pub fn input(&self) -> InputCoordinates {
+        self.input
+    }
+
+    /// Apply the operator to a chunk of operands, returning the number of failures
+    fn apply_chunk(&self, operands: &mut Vec<Coor4D>) -> Result<usize, TransformError> {
+        let successful = self
+            .context
+            .apply(self.op, self.direction, operands)
+            .map_err(|e| TransformError::Operator(e.to_string()))?;
+
+        Ok(operands.len() - successful)
+    }
+
+    /// Apply the operator to all operands, in chunks distributed over the threads
+    fn apply_operands(
+        &self,
+        operands: Vec<Coor4D>,
+        nthreads: usize,
+    ) -> Result<Vec<Coor4D>, TransformError> {
+        let transform_chunk = |chunk: &[Coor4D]| {
+            let mut chunk = chunk.to_vec();
+            self.apply_chunk(&mut chunk).map(|failed| (chunk, failed))
+        };
+
+        #[cfg(not(target_arch = "wasm32"))]
+        let chunks: Vec<(Vec<Coor4D>, usize)> = crate::vectorized::execution::thread_pool(nthreads)
+            .install(|| {
+                operands
+                    .par_chunks(CHUNK_SIZE)
+                    .map(transform_chunk)
+                    .collect::<Result<_, _>>()
+            })?;
+        #[cfg(target_arch = "wasm32")]
+        let chunks: Vec<(Vec<Coor4D>, usize)> = {
+            let _ = &nthreads; // no-op
+            operands
+                .chunks(CHUNK_SIZE)
+                .map(transform_chunk)
+                .collect::<Result<_, _>>()?
+        };
+
+        let count: usize = chunks.iter().map(|(_, failed)| failed).sum();
+        if count > 0 {
+            return Err(TransformError::Failed { count });
+        }
+
+        Ok(chunks.into_iter().flat_map(|(chunk, _)| chunk).collect())
+    }
+
+    /// Transform the coordinates to geographic longitude and latitude in degrees
+    pub fn apply(
+        &self,
+        coords: &[(f64, f64)],
+        nthreads: usize,
+    ) -> Result<Vec<(f64, f64)>, TransformError> {
+        let operands: Vec<Coor4D> = coords
+            .iter()
+            .map(|&(x, y)| match self.input {
+                InputCoordinates::Geographic => {
+                    Coor4D::raw(x.to_radians(), y.to_radians(), 0.0, 0.0)
+                }
+                InputCoordinates::Projected => Coor4D::raw(x, y, 0.0, 0.0),
+            })
+            .collect();
+
+        Ok(self
+            .apply_operands(operands, nthreads)?
+            .iter()
+            .map(|coord| {
+                let (lon, lat) = coord.xy();
+
+                (lon.to_degrees(), lat.to_degrees())
+            })
+            .collect())
+    }
+
+    /// Transform cartesian coordinates, for example between the cartesian
+    /// frames of two datums
+    ///
+    /// The coordinates are passed to the operator unchanged, and it must
+    /// produce cartesian coordinates on the ellipsoid used for the HEALPix grid.
+    pub fn apply_cartesian(
+        &self,
+        coords: &[(f64, f64, f64)],
+        nthreads: usize,
+    ) -> Result<Vec<(f64, f64, f64)>, TransformError> {
+        let operands: Vec<Coor4D> = coords
+            .iter()
+            .map(|&(x, y, z)| Coor4D::raw(x, y, z, 0.0))
+            .collect();
+
+        Ok(self
+            .apply_operands(operands, nthreads)?
+            .iter()
+            .map(|coord| coord.xyz())
+            .collect())
+    }
+}
+
+impl fmt::Debug for InputTransform {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("InputTransform")
+            .field("definition", &self.definition)
+            .field("direction", &self.direction)
+            .field("input", &self.input)
+            .finish()
+    }
+}
+
+impl PartialEq for InputTransform {
+    fn eq(&self, other: &Self) -> bool {
+        self.definition == other.definition
+            && self.direction == other.direction
+            && self.input == other.input
+    }
+}
+
+/// Apply an optional transform to geographic coordinates before passing them on
+///
+/// This composes a transform with any function taking longitudes and
+/// latitudes in degrees, like `lonlat_to_healpix`, `bilinear_interpolation`
+/// or the coverage functions. Without a transform the coordinates are passed
+/// on unchanged.
+pub fn with_transform<R>(
+    transform: Option<&InputTransform>,
+    coords: &[(f64, f64)],
+    nthreads: usize,
+    func: impl FnOnce(&[(f64, f64)]) -> R,
+) -> Result<R, TransformError> {
+    match transform {
+        Some(transform) => Ok(func(&transform.apply(coords, nthreads)?)),
+        None => Ok(func(coords)),
+    }
+}
+
+/// Like `with_transform`, for cartesian coordinates and `cartesian_to_healpix`
+pub fn with_cartesian_transform<R>(
+    transform: Option<&InputTransform>,
+    coords: &[(f64, f64, f64)],
+    nthreads: usize,
+    func: impl FnOnce(&[(f64, f64, f64)]) -> R,
+) -> Result<R, TransformError> {
+    match transform {
+        Some(transform) => Ok(func(&transform.apply_cartesian(coords, nthreads)?)),
+        None => Ok(func(coords)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_utm_inverse() {
+        let transform = InputTransform::new("utm zone=32", InputCoordinates::Projected)
+            .unwrap()
+            .inverted();
+
+        // the origin of the zone: the central meridian on the equator
+        let actual = transform.apply(&[(500000.0, 0.0)], 1).unwrap();
+
+        assert!((actual[0].0 - 9.0).abs() < 1e-9);
+        assert!(actual[0].1.abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_apply_chunks() {
+        let transform = InputTransform::new("utm zone=32", InputCoordinates::Projected)
+            .unwrap()
+            .inverted();
+
+        // more than one chunk, distributed over several threads
+        let coords: Vec<(f64, f64)> = (0..10_000)
+            .map(|index| (400000.0 + index as f64 * 10.0, index as f64 * 100.0))
+            .collect();
+
+        let actual = transform.apply(&coords, 4).unwrap();
+        let expected: Vec<(f64, f64)> = coords
+            .iter()
+            .flat_map(|coord| transform.apply(&[*coord], 1).unwrap())
+            .collect();
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_with_transform() {
+        use crate::ellipsoid::Ellipsoid;
+        use crate::vectorized::nested::coordinates::{bilinear_interpolation, lonlat_to_healpix};
+
+        let transform = InputTransform::new("utm zone=32", InputCoordinates::Projected)
+            .unwrap()
+            .inverted();
+        let layer = cdshealpix::nested::get(6);
+        let ellipsoid = Ellipsoid::named("WGS84").unwrap();
+        let coords = [(500000.0, 0.0), (600000.0, 5000000.0)];
+
+        let actual = with_transform(Some(&transform), &coords, 0, |coords| {
+            lonlat_to_healpix(coords, layer, &ellipsoid, 0)
+        })
+        .unwrap();
+        let geographic = transform.apply(&coords, 0).unwrap();
+        assert_eq!(actual, lonlat_to_healpix(&geographic, layer, &ellipsoid, 0));
+
+        let actual = with_transform(None, &geographic, 0, |coords| {
+            bilinear_interpolation(coords, layer, &ellipsoid, 0)
+        })
+        .unwrap();
+        assert_eq!(
+            actual,
+            bilinear_interpolation(&geographic, layer, &ellipsoid, 0)
+        );
+    }
+
+    #[test]
+    fn test_cartesian() {
+        // a pure translation
+        let transform =
+            InputTransform::new("helmert x=1 y=2 z=3", InputCoordinates::Geographic).unwrap();
+
+        let actual = transform.apply_cartesian(&[(10.0, 20.0, 30.0)], 1).unwrap();
+
+        assert_eq!(actual, vec![(11.0, 22.0, 33.0)]);
+    }
+
+    #[test]
+    fn test_invalid_definition() {
+        assert!(matches!(
+            InputTransform::new("not-an-operator", InputCoordinates::Geographic),
+            Err(TransformError::Operator(_))
+        ));
+    }
+}
@@ -1,13 +1,73 @@
+#[cfg(not(target_arch = "wasm32"))]
+use std::sync::{Arc, Mutex, OnceLock};
+
+/// Number of thread pools kept alive at the same time
+#[cfg(not(target_arch = "wasm32"))]
+const MAX_CACHED_POOLS: usize = 4;
+
+#[cfg(not(target_arch = "wasm32"))]
+static THREAD_POOLS: OnceLock<Mutex<PoolCache>> = OnceLock::new();
+
+/// The most recently used thread pools, keyed by their number of threads
+///
+/// Once full, the least recently used pool is evicted. Its threads exit as
+/// soon as the computations still using it are done.
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(Debug)]
+struct PoolCache {
+    capacity: usize,
+    pools: Vec<(usize, Arc<rayon::ThreadPool>)>,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl PoolCache {
+    fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            pools: Vec::with_capacity(capacity + 1),
+        }
+    }
+
+    fn get(&mut self, nthreads: usize) -> Arc<rayon::ThreadPool> {
+        let pool = match self.pools.iter().position(|(n, _)| *n == nthreads) {
+            Some(index) => self.pools.remove(index).1,
+            None => Arc::new(
+                rayon::ThreadPoolBuilder::new()
+                    .num_threads(nthreads)
+                    .build()
+                    .unwrap(),
+            ),
+        };
+
+        self.pools.push((nthreads, pool.clone()));
+        if self.pools.len() > self.capacity {
+            self.pools.remove(0);
+        }
+
+        pool
+    }
+}
+
+/// The shared thread pool with the given number of threads
+///
+/// Building a pool costs more than converting a small batch of cells, so
+/// the pools of the last few thread counts are kept and reused by later
+/// calls. A value of 0 selects the default number of threads of rayon.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn thread_pool(nthreads: usize) -> Arc<rayon::ThreadPool> {
+    THREAD_POOLS
+        .get_or_init(|| Mutex::new(PoolCache::new(MAX_CACHED_POOLS)))
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .get(nthreads)
+}
+
 #[macro_export]
 macro_rules! maybe_parallelize {
     ($nthreads:ident, $iterable:expr, $buffer:ident, $func:expr $(,)?) => {
         #[cfg(not(target_arch = "wasm32"))]
         {
-            let pool = rayon::ThreadPoolBuilder::new()
-                .num_threads($nthreads as usize)
-                .build()
-                .unwrap();
-            pool.install(|| {
+            $crate::vectorized::execution::thread_pool($nthreads as usize).install(|| {
                 $iterable
                     .par_iter()
                     .map($func)
@@ -22,3 +82,93 @@ macro_rules! maybe_parallelize {
         $buffer.shrink_to_fit();
     };
 }
+
+/// Like `maybe_parallelize`, but writes into caller-provided buffers
+///
+/// The input and output slices are iterated in lockstep, and the closure is
+/// called with a flat tuple of one item of each.
+#[macro_export]
+macro_rules! maybe_parallelize_into {
+    ($nthreads:ident, ($($input:expr),+ $(,)?), ($($output:expr),+ $(,)?), $func:expr $(,)?) => {
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            $crate::vectorized::execution::thread_pool($nthreads as usize).install(|| {
+                ($($input.par_iter(),)+ $($output.par_iter_mut(),)+)
+                    .into_par_iter()
+                    .for_each($func)
+            });
+        }
+        #[cfg(target_arch = "wasm32")]
+        {
+            let _ = &$nthreads; // no-op
+            itertools::multizip(($($input.iter(),)+ $($output.iter_mut(),)+)).for_each($func);
+        }
+    };
+}
+
+/// Like `maybe_parallelize_into`, but each item fills a row of a flat buffer
+///
+/// The buffer must hold `stride` values per item, and the closure is called
+/// with the item and its row.
+#[macro_export]
+macro_rules! maybe_parallelize_rows {
+    ($nthreads:ident, $iterable:expr, $buffer:expr, $stride:expr, $func:expr $(,)?) => {
+        // chunks of size 0 are not allowed
+        if $stride > 0 {
+            #[cfg(not(target_arch = "wasm32"))]
+            {
+                $crate::vectorized::execution::thread_pool($nthreads as usize).install(|| {
+                    $iterable
+                        .par_iter()
+                        .zip($buffer.par_chunks_mut($stride))
+                        .for_each($func)
+                });
+            }
+            #[cfg(target_arch = "wasm32")]
+            {
+                let _ = &$nthreads; // no-op
+                $iterable
+                    .iter()
+                    .zip($buffer.chunks_mut($stride))
+                    .for_each($func);
+            }
+        }
+    };
+}
+
+/// Refuse input or output buffers that don't match the length of the first input
+pub(crate) fn check_lengths(length: usize, others: &[usize]) {
+    if others.iter().any(|&other| other != length) {
+        // TODO: custom error type
+        panic!("the buffers must all have the same length: {length} != {others:?}");
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_thread_pool_is_cached() {
+        let pool = thread_pool(2);
+
+        assert_eq!(pool.current_num_threads(), 2);
+        assert!(Arc::ptr_eq(&pool, &thread_pool(2)));
+        assert!(!Arc::ptr_eq(&pool, &thread_pool(3)));
+    }
+
+    #[test]
+    fn test_pool_cache_is_bounded() {
+        let mut cache = PoolCache::new(2);
+
+        let one = cache.get(1);
+        let two = cache.get(2);
+        assert!(Arc::ptr_eq(&one, &cache.get(1)));
+
+        // 2 is now the least recently used pool
+        cache.get(3);
+        assert_eq!(cache.pools.len(), 2);
+        assert!(Arc::ptr_eq(&one, &cache.get(1)));
+        assert!(!Arc::ptr_eq(&two, &cache.get(2)));
+    }
+}
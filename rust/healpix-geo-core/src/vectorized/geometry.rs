@@ -1,7 +1,9 @@
 #[cfg(not(target_arch = "wasm32"))]
 use rayon::prelude::*;
 
-use crate::ellipsoid::Ellipsoid;
+use num_traits::{AsPrimitive, Float};
+
+use crate::ellipsoid::{Ellipsoid, LatitudeKind};
 use crate::maybe_parallelize;
 use crate::scalar::geometry as scalar;
 
@@ -32,3 +34,110 @@ pub fn cartesian_to_lonlat(
 
     result
 }
+
+pub fn lonlat_height_to_cartesian(
+    coords: &[(f64, f64, f64)],
+    ellipsoid: &Ellipsoid,
+    nthreads: usize,
+) -> Vec<(f64, f64, f64)> {
+    let mut result = Vec::<(f64, f64, f64)>::with_capacity(coords.len());
+
+    maybe_parallelize!(nthreads, coords, result, |(lon, lat, height)| {
+        scalar::lonlat_height_to_cartesian(lon, lat, height, ellipsoid)
+    });
+
+    result
+}
+
+pub fn cartesian_to_lonlat_height(
+    coords: &[(f64, f64, f64)],
+    ellipsoid: &Ellipsoid,
+    nthreads: usize,
+) -> Vec<(f64, f64, f64)> {
+    let mut result = Vec::<(f64, f64, f64)>::with_capacity(coords.len());
+
+    maybe_parallelize!(nthreads, coords, result, |(x, y, z)| {
+        scalar::cartesian_to_lonlat_height(x, y, z, ellipsoid)
+    });
+
+    result
+}
+
+/// Project points given in degrees to the HEALPix plane
+pub fn lonlat_to_projection<T: Float + AsPrimitive<f64> + Send + Sync>(
+    coords: &[(T, T)],
+    ellipsoid: &Ellipsoid,
+    nthreads: usize,
+) -> Vec<(T, T)>
+where
+    f64: AsPrimitive<T>,
+{
+    let mut result = Vec::<(T, T)>::with_capacity(coords.len());
+
+    maybe_parallelize!(nthreads, coords, result, |(lon, lat)| {
+        scalar::lonlat_to_projection(lon, lat, ellipsoid)
+    });
+
+    result
+}
+
+/// Inverse of `lonlat_to_projection`, returning degrees
+///
+/// Points outside of the projection of the sphere map to `None`.
+pub fn projection_to_lonlat<T: Float + AsPrimitive<f64> + Send + Sync>(
+    coords: &[(T, T)],
+    ellipsoid: &Ellipsoid,
+    nthreads: usize,
+) -> Vec<Option<(T, T)>>
+where
+    f64: AsPrimitive<T>,
+{
+    let mut result = Vec::<Option<(T, T)>>::with_capacity(coords.len());
+
+    maybe_parallelize!(nthreads, coords, result, |(x, y)| {
+        scalar::projection_to_lonlat(x, y, ellipsoid)
+    });
+
+    result
+}
+
+/// Convert latitudes in degrees between two kinds of latitudes
+pub fn convert_latitudes(
+    latitudes: &[f64],
+    from: LatitudeKind,
+    to: LatitudeKind,
+    ellipsoid: &Ellipsoid,
+    nthreads: usize,
+) -> Vec<f64> {
+    let mut result = Vec::<f64>::with_capacity(latitudes.len());
+
+    maybe_parallelize!(nthreads, latitudes, result, |lat| {
+        scalar::convert_latitude(lat, from, to, ellipsoid)
+    });
+
+    result
+}
+
+/// Geodesic distances in meters between points
+///
+/// Each point in `from` is compared to the corresponding row of `chunks`
+/// points in `to`. Coordinates are longitude and latitude in degrees.
+pub fn geodesic_distances(
+    from: &[(f64, f64)],
+    to: &[(f64, f64)],
+    chunks: usize,
+    ellipsoid: &Ellipsoid,
+    nthreads: usize,
+) -> Vec<Vec<f64>> {
+    let geodesic = scalar::geodesic(ellipsoid);
+    let data: Vec<(&(f64, f64), &[(f64, f64)])> = from.iter().zip(to.chunks(chunks)).collect();
+
+    let mut result = Vec::<Vec<f64>>::with_capacity(from.len());
+    maybe_parallelize!(nthreads, data, result, |(from_, to_)| {
+        to_.iter()
+            .map(|point| scalar::geodesic_distance(from_, point, &geodesic))
+            .collect::<Vec<f64>>()
+    });
+
+    result
+}
@@ -1,5 +1,5 @@
 pub mod depth;
-mod execution;
+pub mod execution;
 pub mod nested;
 pub mod ring;
 pub mod zuniq;
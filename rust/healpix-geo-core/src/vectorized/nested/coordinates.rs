@@ -2,10 +2,12 @@
 use rayon::prelude::*;
 
 use cdshealpix::nested::Layer;
+use num_traits::{AsPrimitive, Float};
 
 use crate::ellipsoid::Ellipsoid;
-use crate::maybe_parallelize;
 use crate::scalar::nested::coordinates as scalar;
+use crate::vectorized::execution::check_lengths;
+use crate::{maybe_parallelize, maybe_parallelize_into};
 
 pub fn healpix_to_lonlat(
     ipix: &[u64],
@@ -22,8 +24,8 @@ pub fn healpix_to_lonlat(
     result
 }
 
-pub fn lonlat_to_healpix(
-    coords: &[(f64, f64)],
+pub fn lonlat_to_healpix<T: Float + AsPrimitive<f64> + Sync>(
+    coords: &[(T, T)],
     layer: &Layer,
     ellipsoid: &Ellipsoid,
     nthreads: usize,
@@ -37,6 +39,38 @@ pub fn lonlat_to_healpix(
     result
 }
 
+/// Cells containing the points, and the positions of the points inside the cells
+pub fn lonlat_to_healpix_with_offset<T: Float + AsPrimitive<f64> + Sync>(
+    coords: &[(T, T)],
+    layer: &Layer,
+    ellipsoid: &Ellipsoid,
+    nthreads: usize,
+) -> Vec<(u64, f64, f64)> {
+    let mut result = Vec::<(u64, f64, f64)>::with_capacity(coords.len());
+
+    maybe_parallelize!(nthreads, coords, result, |(lon, lat)| {
+        scalar::lonlat_to_healpix_with_offset(lon, lat, layer, ellipsoid)
+    });
+
+    result
+}
+
+/// Inverse of `lonlat_to_healpix_with_offset`
+pub fn healpix_with_offset_to_lonlat(
+    cells: &[(u64, f64, f64)],
+    layer: &Layer,
+    ellipsoid: &Ellipsoid,
+    nthreads: usize,
+) -> Vec<(f64, f64)> {
+    let mut result = Vec::<(f64, f64)>::with_capacity(cells.len());
+
+    maybe_parallelize!(nthreads, cells, result, |(hash, dx, dy)| {
+        scalar::healpix_with_offset_to_lonlat(hash, dx, dy, layer, ellipsoid)
+    });
+
+    result
+}
+
 pub fn vertices(
     ipix: &[u64],
     layer: &Layer,
@@ -68,8 +102,25 @@ pub fn healpix_to_cartesian(
     result
 }
 
-pub fn cartesian_to_healpix(
-    coords: &[(f64, f64, f64)],
+/// Cartesian coordinates of the points `height` meters above the cell centers
+pub fn healpix_to_cartesian_at_height(
+    ipix: &[u64],
+    layer: &Layer,
+    height: f64,
+    ellipsoid: &Ellipsoid,
+    nthreads: usize,
+) -> Vec<(f64, f64, f64)> {
+    let mut result = Vec::<(f64, f64, f64)>::with_capacity(ipix.len());
+
+    maybe_parallelize!(nthreads, ipix, result, |hash| {
+        scalar::healpix_to_cartesian_at_height(hash, layer, &height, ellipsoid)
+    });
+
+    result
+}
+
+pub fn cartesian_to_healpix<T: Float + AsPrimitive<f64> + Sync>(
+    coords: &[(T, T, T)],
     layer: &Layer,
     ellipsoid: &Ellipsoid,
     nthreads: usize,
@@ -83,8 +134,80 @@ pub fn cartesian_to_healpix(
     result
 }
 
-pub fn bilinear_interpolation(
-    coords: &[(f64, f64)],
+/// Struct-of-arrays variant of `healpix_to_lonlat`, writing into `lon` and `lat`
+pub fn healpix_to_lonlat_into<T: Float + AsPrimitive<f64> + Send>(
+    ipix: &[u64],
+    layer: &Layer,
+    ellipsoid: &Ellipsoid,
+    lon: &mut [T],
+    lat: &mut [T],
+    nthreads: usize,
+) where
+    f64: AsPrimitive<T>,
+{
+    check_lengths(ipix.len(), &[lon.len(), lat.len()]);
+
+    maybe_parallelize_into!(nthreads, (ipix), (lon, lat), |(hash, lon, lat)| {
+        let (lon_, lat_) = scalar::healpix_to_lonlat(hash, layer, ellipsoid);
+        (*lon, *lat) = (lon_.as_(), lat_.as_());
+    });
+}
+
+/// Struct-of-arrays variant of `lonlat_to_healpix`, writing into `ipix`
+pub fn lonlat_to_healpix_into<T: Float + AsPrimitive<f64> + Sync>(
+    lon: &[T],
+    lat: &[T],
+    layer: &Layer,
+    ellipsoid: &Ellipsoid,
+    ipix: &mut [u64],
+    nthreads: usize,
+) {
+    check_lengths(lon.len(), &[lat.len(), ipix.len()]);
+
+    maybe_parallelize_into!(nthreads, (lon, lat), (ipix), |(lon, lat, hash)| {
+        *hash = scalar::lonlat_to_healpix(lon, lat, layer, ellipsoid);
+    });
+}
+
+/// Struct-of-arrays variant of `healpix_to_cartesian`, writing into `x`, `y` and `z`
+pub fn healpix_to_cartesian_into<T: Float + AsPrimitive<f64> + Send>(
+    ipix: &[u64],
+    layer: &Layer,
+    ellipsoid: &Ellipsoid,
+    x: &mut [T],
+    y: &mut [T],
+    z: &mut [T],
+    nthreads: usize,
+) where
+    f64: AsPrimitive<T>,
+{
+    check_lengths(ipix.len(), &[x.len(), y.len(), z.len()]);
+
+    maybe_parallelize_into!(nthreads, (ipix), (x, y, z), |(hash, x, y, z)| {
+        let (x_, y_, z_) = scalar::healpix_to_cartesian(hash, layer, ellipsoid);
+        (*x, *y, *z) = (x_.as_(), y_.as_(), z_.as_());
+    });
+}
+
+/// Struct-of-arrays variant of `cartesian_to_healpix`, writing into `ipix`
+pub fn cartesian_to_healpix_into<T: Float + AsPrimitive<f64> + Sync>(
+    x: &[T],
+    y: &[T],
+    z: &[T],
+    layer: &Layer,
+    ellipsoid: &Ellipsoid,
+    ipix: &mut [u64],
+    nthreads: usize,
+) {
+    check_lengths(x.len(), &[y.len(), z.len(), ipix.len()]);
+
+    maybe_parallelize_into!(nthreads, (x, y, z), (ipix), |(x, y, z, hash)| {
+        *hash = scalar::cartesian_to_healpix(x, y, z, layer, ellipsoid);
+    });
+}
+
+pub fn bilinear_interpolation<T: Float + AsPrimitive<f64> + Sync>(
+    coords: &[(T, T)],
     layer: &Layer,
     ellipsoid: &Ellipsoid,
     nthreads: usize,
@@ -105,6 +228,118 @@ mod tests {
     use cdshealpix as healpix;
     use geodesy::ellps::Ellipsoid as GeodesyEllipsoid;
 
+    #[test]
+    fn test_healpix_to_cartesian_at_height() {
+        let layer = healpix::nested::get(3);
+        let ellipsoid = Ellipsoid::Ellipsoid(ReferenceEllipsoid::new(
+            GeodesyEllipsoid::named("WGS84").unwrap(),
+        ));
+        let ipix = vec![0, 100, 767];
+        let height = 10_000.0;
+
+        let actual = healpix_to_cartesian_at_height(&ipix, layer, height, &ellipsoid, 0);
+        let surface = healpix_to_cartesian(&ipix, layer, &ellipsoid, 0);
+        let centers = healpix_to_lonlat(&ipix, layer, &ellipsoid, 0);
+
+        for ((point, surface), center) in actual.iter().zip(surface).zip(centers) {
+            let (lon, lat, h) = crate::scalar::geometry::cartesian_to_lonlat_height(
+                &point.0, &point.1, &point.2, &ellipsoid,
+            );
+
+            let lon_difference = (lon - center.0).rem_euclid(360.0);
+            assert!(lon_difference.min(360.0 - lon_difference) < 1e-8);
+            assert!((lat - center.1).abs() < 1e-8);
+            assert!((h - height).abs() < 1e-4);
+            assert_ne!(*point, surface);
+        }
+    }
+
+    #[test]
+    fn test_struct_of_arrays() {
+        let layer = healpix::nested::get(3);
+        let ellipsoid = Ellipsoid::Ellipsoid(ReferenceEllipsoid::new(
+            GeodesyEllipsoid::named("WGS84").unwrap(),
+        ));
+        let ipix = vec![0, 100, 767];
+
+        let mut lon = vec![0.0; ipix.len()];
+        let mut lat = vec![0.0; ipix.len()];
+        healpix_to_lonlat_into(&ipix, layer, &ellipsoid, &mut lon, &mut lat, 0);
+        let expected = healpix_to_lonlat(&ipix, layer, &ellipsoid, 0);
+        assert_eq!(
+            lon.iter()
+                .copied()
+                .zip(lat.iter().copied())
+                .collect::<Vec<_>>(),
+            expected
+        );
+
+        let mut actual = vec![0; ipix.len()];
+        lonlat_to_healpix_into(&lon, &lat, layer, &ellipsoid, &mut actual, 0);
+        assert_eq!(actual, ipix);
+
+        let (mut x, mut y, mut z) = (vec![0.0; 3], vec![0.0; 3], vec![0.0; 3]);
+        healpix_to_cartesian_into(&ipix, layer, &ellipsoid, &mut x, &mut y, &mut z, 0);
+        let expected = healpix_to_cartesian(&ipix, layer, &ellipsoid, 0);
+        for (index, point) in expected.iter().enumerate() {
+            assert_eq!((x[index], y[index], z[index]), *point);
+        }
+
+        let mut actual = vec![0; ipix.len()];
+        cartesian_to_healpix_into(&x, &y, &z, layer, &ellipsoid, &mut actual, 0);
+        assert_eq!(actual, ipix);
+    }
+
+    #[test]
+    #[should_panic(expected = "the buffers must all have the same length")]
+    fn test_struct_of_arrays_lengths() {
+        let layer = healpix::nested::get(3);
+        let ellipsoid = Ellipsoid::default();
+
+        let mut lon = vec![0.0; 2];
+        let mut lat = vec![0.0; 3];
+        healpix_to_lonlat_into(&[0, 1, 2], layer, &ellipsoid, &mut lon, &mut lat, 0);
+    }
+
+    #[test]
+    fn test_single_precision() {
+        let layer = healpix::nested::get(8);
+        let ellipsoid = Ellipsoid::named("WGS84").unwrap();
+        let ipix = vec![0, 1000, 786431];
+
+        let mut lon = vec![0f32; ipix.len()];
+        let mut lat = vec![0f32; ipix.len()];
+        healpix_to_lonlat_into(&ipix, layer, &ellipsoid, &mut lon, &mut lat, 0);
+        let expected = healpix_to_lonlat(&ipix, layer, &ellipsoid, 0);
+        for (index, (lon_, lat_)) in expected.iter().enumerate() {
+            assert!((lon[index] as f64 - lon_).abs() < 1e-4);
+            assert!((lat[index] as f64 - lat_).abs() < 1e-4);
+        }
+
+        // cell centers are far enough from the edges to survive the rounding
+        let mut actual = vec![0; ipix.len()];
+        lonlat_to_healpix_into(&lon, &lat, layer, &ellipsoid, &mut actual, 0);
+        assert_eq!(actual, ipix);
+
+        let coords: Vec<(f32, f32)> = lon.iter().copied().zip(lat.iter().copied()).collect();
+        assert_eq!(lonlat_to_healpix(&coords, layer, &ellipsoid, 0), ipix);
+    }
+
+    #[test]
+    fn test_offset_roundtrip() {
+        let layer = healpix::nested::get(10);
+        let ellipsoid = Ellipsoid::named("WGS84").unwrap();
+        let coords = vec![(10.0, 30.0), (45.0, 50.0), (280.0, -70.0)];
+
+        let cells = lonlat_to_healpix_with_offset(&coords, layer, &ellipsoid, 0);
+        let actual = healpix_with_offset_to_lonlat(&cells, layer, &ellipsoid, 0);
+
+        for ((lon, lat), (expected_lon, expected_lat)) in actual.into_iter().zip(coords) {
+            assert!((lon - expected_lon).abs() < 1e-9);
+            assert!((lat - expected_lat).abs() < 1e-9);
+        }
+    }
+
     #[test]
     fn test_bilinear_interpolation() {
         let layer = healpix::nested::get(3);
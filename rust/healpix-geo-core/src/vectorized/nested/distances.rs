@@ -1,4 +1,7 @@
+use crate::ellipsoid::Ellipsoid;
 use crate::maybe_parallelize;
+use crate::scalar::geometry::{geodesic, geodesic_distance};
+use crate::scalar::nested::coordinates::healpix_to_lonlat;
 use cdshealpix::nested::Layer;
 use cdshealpix::sph_geom::coo3d::{UnitVec3, UnitVect3, vec3_of};
 
@@ -31,3 +34,86 @@ pub fn angular_distances(
 
     result
 }
+
+/// Geodesic distances in meters between cell centers on the ellipsoid
+///
+/// Slower, but more accurate than `angular_distances`.
+pub fn geodesic_distances(
+    from: &[u64],
+    to: &[u64],
+    chunks: usize,
+    layer: &Layer,
+    ellipsoid: &Ellipsoid,
+    nthreads: usize,
+) -> Vec<Vec<f64>> {
+    let geodesic = geodesic(ellipsoid);
+    let data: Vec<(&u64, &[u64])> = from.iter().zip(to.chunks(chunks)).collect();
+
+    let mut result = Vec::<Vec<f64>>::with_capacity(from.len());
+    maybe_parallelize!(nthreads, data, result, |(from_, to_)| {
+        let first = healpix_to_lonlat(from_, layer, ellipsoid);
+        to_.iter()
+            .map(|h| healpix_to_lonlat(h, layer, ellipsoid))
+            .map(|center| geodesic_distance(&first, &center, &geodesic))
+            .collect::<Vec<f64>>()
+    });
+
+    result
+}
+
+/// Geodesic distances in meters between points and cell centers
+///
+/// The points are given as longitude and latitude in degrees. Each point is
+/// compared to the corresponding row of `chunks` cells in `to`.
+pub fn point_geodesic_distances(
+    points: &[(f64, f64)],
+    to: &[u64],
+    chunks: usize,
+    layer: &Layer,
+    ellipsoid: &Ellipsoid,
+    nthreads: usize,
+) -> Vec<Vec<f64>> {
+    let geodesic = geodesic(ellipsoid);
+    let data: Vec<(&(f64, f64), &[u64])> = points.iter().zip(to.chunks(chunks)).collect();
+
+    let mut result = Vec::<Vec<f64>>::with_capacity(points.len());
+    maybe_parallelize!(nthreads, data, result, |(point, to_)| {
+        to_.iter()
+            .map(|h| healpix_to_lonlat(h, layer, ellipsoid))
+            .map(|center| geodesic_distance(point, &center, &geodesic))
+            .collect::<Vec<f64>>()
+    });
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ellipsoid::ReferenceEllipsoid;
+    use geodesy::ellps::Ellipsoid as GeodesyEllipsoid;
+
+    #[test]
+    fn test_geodesic_distances() {
+        let ellipsoid = Ellipsoid::Ellipsoid(ReferenceEllipsoid::new(
+            GeodesyEllipsoid::named("WGS84").unwrap(),
+        ));
+        let layer = cdshealpix::nested::get(2);
+
+        let from = [0, 100];
+        let to = [0, 1, 100, 0];
+
+        let actual = geodesic_distances(&from, &to, 2, layer, &ellipsoid, 1);
+
+        let points: Vec<_> = from
+            .iter()
+            .map(|h| healpix_to_lonlat(h, layer, &ellipsoid))
+            .collect();
+        let expected = point_geodesic_distances(&points, &to, 2, layer, &ellipsoid, 1);
+
+        assert_eq!(actual, expected);
+        assert_eq!(actual[0][0], 0.0);
+        assert!(actual[0][1] > 0.0);
+        assert!((actual[1][1] - actual[0][2]).abs() < 1e-6);
+    }
+}
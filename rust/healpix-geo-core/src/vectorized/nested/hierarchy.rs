@@ -4,8 +4,9 @@ use rayon::prelude::*;
 use cdshealpix as healpix;
 use cdshealpix::nested::Layer;
 
-use crate::maybe_parallelize;
+use crate::scalar::hierarchy::{n_neighbourhood, n_neighbours};
 use crate::scalar::nested::hierarchy as scalar;
+use crate::{maybe_parallelize, maybe_parallelize_rows};
 
 pub fn kth_neighbours(ipix: &[u64], layer: &Layer, ring: &u32, nthreads: usize) -> Vec<Vec<i64>> {
     let mut result = Vec::<Vec<i64>>::with_capacity(ipix.len());
@@ -32,6 +33,41 @@ pub fn kth_neighbourhood(
     result
 }
 
+/// Like `kth_neighbours`, but returns a flat buffer with `8 * ring` values per cell
+///
+/// Missing neighbours are set to -1. The buffer can be viewed as a 2-D array
+/// of shape `(ipix.len(), 8 * ring)`.
+pub fn kth_neighbours_flat(ipix: &[u64], layer: &Layer, ring: &u32, nthreads: usize) -> Vec<i64> {
+    let stride = n_neighbours(ring);
+    let mut result = vec![-1i64; ipix.len() * stride];
+
+    maybe_parallelize_rows!(nthreads, ipix, result, stride, |(hash, row)| {
+        scalar::kth_neighbours_into(hash, layer, ring, row)
+    });
+
+    result
+}
+
+/// Like `kth_neighbourhood`, but returns a flat buffer with `(2 * ring + 1)²` values per cell
+///
+/// Missing neighbours are set to -1. The buffer can be viewed as a 2-D array
+/// of shape `(ipix.len(), (2 * ring + 1)²)`.
+pub fn kth_neighbourhood_flat(
+    ipix: &[u64],
+    layer: &Layer,
+    ring: &u32,
+    nthreads: usize,
+) -> Vec<i64> {
+    let stride = n_neighbourhood(ring);
+    let mut result = vec![-1i64; ipix.len() * stride];
+
+    maybe_parallelize_rows!(nthreads, ipix, result, stride, |(hash, row)| {
+        scalar::kth_neighbourhood_into(hash, layer, ring, row)
+    });
+
+    result
+}
+
 pub fn parents(ipix: &[u64], delta_depth: u8, nthreads: usize) -> Vec<u64> {
     let mut result = Vec::<u64>::with_capacity(ipix.len());
     if delta_depth > 0 {
@@ -71,3 +107,25 @@ pub fn siblings(ipix: &[u64], layer: &Layer, nthreads: usize) -> Vec<Vec<u64>> {
 
     result
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_flat_neighbours() {
+        let layer = healpix::nested::get(2);
+        // includes cells at the corners of base cells, with missing neighbours
+        let ipix = vec![0, 5, 15, 16, 100];
+
+        assert!(kth_neighbours_flat(&ipix, layer, &0, 0).is_empty());
+
+        for ring in [1, 2] {
+            let expected: Vec<i64> = kth_neighbours(&ipix, layer, &ring, 0).concat();
+            assert_eq!(kth_neighbours_flat(&ipix, layer, &ring, 0), expected);
+
+            let expected: Vec<i64> = kth_neighbourhood(&ipix, layer, &ring, 0).concat();
+            assert_eq!(kth_neighbourhood_flat(&ipix, layer, &ring, 0), expected);
+        }
+    }
+}
@@ -1,10 +1,12 @@
 use cdshealpix as healpix;
+use num_traits::{AsPrimitive, Float};
 #[cfg(not(target_arch = "wasm32"))]
 use rayon::prelude::*;
 
 use crate::ellipsoid::Ellipsoid;
-use crate::maybe_parallelize;
 use crate::scalar::ring::coordinates as scalar;
+use crate::vectorized::execution::check_lengths;
+use crate::{maybe_parallelize, maybe_parallelize_into};
 
 pub fn healpix_to_lonlat(
     ipix: &[u64],
@@ -21,8 +23,8 @@ pub fn healpix_to_lonlat(
     result
 }
 
-pub fn lonlat_to_healpix(
-    coords: &[(f64, f64)],
+pub fn lonlat_to_healpix<T: Float + AsPrimitive<f64> + Sync>(
+    coords: &[(T, T)],
     nside: &u32,
     ellipsoid: &Ellipsoid,
     nthreads: usize,
@@ -36,6 +38,38 @@ pub fn lonlat_to_healpix(
     result
 }
 
+/// Cells containing the points, and the positions of the points inside the cells
+pub fn lonlat_to_healpix_with_offset<T: Float + AsPrimitive<f64> + Sync>(
+    coords: &[(T, T)],
+    nside: &u32,
+    ellipsoid: &Ellipsoid,
+    nthreads: usize,
+) -> Vec<(u64, f64, f64)> {
+    let mut result = Vec::<(u64, f64, f64)>::with_capacity(coords.len());
+
+    maybe_parallelize!(nthreads, coords, result, |(lon, lat)| {
+        scalar::lonlat_to_healpix_with_offset(lon, lat, nside, ellipsoid)
+    });
+
+    result
+}
+
+/// Inverse of `lonlat_to_healpix_with_offset`
+pub fn healpix_with_offset_to_lonlat(
+    cells: &[(u64, f64, f64)],
+    nside: &u32,
+    ellipsoid: &Ellipsoid,
+    nthreads: usize,
+) -> Vec<(f64, f64)> {
+    let mut result = Vec::<(f64, f64)>::with_capacity(cells.len());
+
+    maybe_parallelize!(nthreads, cells, result, |(hash, dx, dy)| {
+        scalar::healpix_with_offset_to_lonlat(hash, dx, dy, nside, ellipsoid)
+    });
+
+    result
+}
+
 pub fn vertices(
     ipix: &[u64],
     nside: &u32,
@@ -67,8 +101,25 @@ pub fn healpix_to_cartesian(
     result
 }
 
-pub fn cartesian_to_healpix(
-    coords: &[(f64, f64, f64)],
+/// Cartesian coordinates of the points `height` meters above the cell centers
+pub fn healpix_to_cartesian_at_height(
+    ipix: &[u64],
+    nside: &u32,
+    height: f64,
+    ellipsoid: &Ellipsoid,
+    nthreads: usize,
+) -> Vec<(f64, f64, f64)> {
+    let mut result = Vec::<(f64, f64, f64)>::with_capacity(ipix.len());
+
+    maybe_parallelize!(nthreads, ipix, result, |hash| {
+        scalar::healpix_to_cartesian_at_height(hash, nside, &height, ellipsoid)
+    });
+
+    result
+}
+
+pub fn cartesian_to_healpix<T: Float + AsPrimitive<f64> + Sync>(
+    coords: &[(T, T, T)],
     nside: &u32,
     ellipsoid: &Ellipsoid,
     nthreads: usize,
@@ -82,8 +133,80 @@ pub fn cartesian_to_healpix(
     result
 }
 
-pub fn bilinear_interpolation(
-    coords: &[(f64, f64)],
+/// Struct-of-arrays variant of `healpix_to_lonlat`, writing into `lon` and `lat`
+pub fn healpix_to_lonlat_into<T: Float + AsPrimitive<f64> + Send>(
+    ipix: &[u64],
+    nside: &u32,
+    ellipsoid: &Ellipsoid,
+    lon: &mut [T],
+    lat: &mut [T],
+    nthreads: usize,
+) where
+    f64: AsPrimitive<T>,
+{
+    check_lengths(ipix.len(), &[lon.len(), lat.len()]);
+
+    maybe_parallelize_into!(nthreads, (ipix), (lon, lat), |(hash, lon, lat)| {
+        let (lon_, lat_) = scalar::healpix_to_lonlat(hash, nside, ellipsoid);
+        (*lon, *lat) = (lon_.as_(), lat_.as_());
+    });
+}
+
+/// Struct-of-arrays variant of `lonlat_to_healpix`, writing into `ipix`
+pub fn lonlat_to_healpix_into<T: Float + AsPrimitive<f64> + Sync>(
+    lon: &[T],
+    lat: &[T],
+    nside: &u32,
+    ellipsoid: &Ellipsoid,
+    ipix: &mut [u64],
+    nthreads: usize,
+) {
+    check_lengths(lon.len(), &[lat.len(), ipix.len()]);
+
+    maybe_parallelize_into!(nthreads, (lon, lat), (ipix), |(lon, lat, hash)| {
+        *hash = scalar::lonlat_to_healpix(lon, lat, nside, ellipsoid);
+    });
+}
+
+/// Struct-of-arrays variant of `healpix_to_cartesian`, writing into `x`, `y` and `z`
+pub fn healpix_to_cartesian_into<T: Float + AsPrimitive<f64> + Send>(
+    ipix: &[u64],
+    nside: &u32,
+    ellipsoid: &Ellipsoid,
+    x: &mut [T],
+    y: &mut [T],
+    z: &mut [T],
+    nthreads: usize,
+) where
+    f64: AsPrimitive<T>,
+{
+    check_lengths(ipix.len(), &[x.len(), y.len(), z.len()]);
+
+    maybe_parallelize_into!(nthreads, (ipix), (x, y, z), |(hash, x, y, z)| {
+        let (x_, y_, z_) = scalar::healpix_to_cartesian(hash, nside, ellipsoid);
+        (*x, *y, *z) = (x_.as_(), y_.as_(), z_.as_());
+    });
+}
+
+/// Struct-of-arrays variant of `cartesian_to_healpix`, writing into `ipix`
+pub fn cartesian_to_healpix_into<T: Float + AsPrimitive<f64> + Sync>(
+    x: &[T],
+    y: &[T],
+    z: &[T],
+    nside: &u32,
+    ellipsoid: &Ellipsoid,
+    ipix: &mut [u64],
+    nthreads: usize,
+) {
+    check_lengths(x.len(), &[y.len(), z.len(), ipix.len()]);
+
+    maybe_parallelize_into!(nthreads, (x, y, z), (ipix), |(x, y, z, hash)| {
+        *hash = scalar::cartesian_to_healpix(x, y, z, nside, ellipsoid);
+    });
+}
+
+pub fn bilinear_interpolation<T: Float + AsPrimitive<f64> + Sync>(
+    coords: &[(T, T)],
     nside: &u32,
     ellipsoid: &Ellipsoid,
     nthreads: usize,
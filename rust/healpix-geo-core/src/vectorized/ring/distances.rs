@@ -1,4 +1,7 @@
+use crate::ellipsoid::Ellipsoid;
 use crate::maybe_parallelize;
+use crate::scalar::geometry::{geodesic, geodesic_distance};
+use crate::scalar::ring::coordinates::healpix_to_lonlat;
 use cdshealpix::sph_geom::coo3d::{UnitVec3, UnitVect3, vec3_of};
 
 #[cfg(not(target_arch = "wasm32"))]
@@ -30,3 +33,55 @@ pub fn angular_distances(
 
     result
 }
+
+/// Geodesic distances in meters between cell centers on the ellipsoid
+///
+/// Slower, but more accurate than `angular_distances`.
+pub fn geodesic_distances(
+    from: &[u64],
+    to: &[u64],
+    chunks: usize,
+    nside: &u32,
+    ellipsoid: &Ellipsoid,
+    nthreads: usize,
+) -> Vec<Vec<f64>> {
+    let geodesic = geodesic(ellipsoid);
+    let data: Vec<(&u64, &[u64])> = from.iter().zip(to.chunks(chunks)).collect();
+
+    let mut result = Vec::<Vec<f64>>::with_capacity(from.len());
+    maybe_parallelize!(nthreads, data, result, |(from_, to_)| {
+        let first = healpix_to_lonlat(from_, nside, ellipsoid);
+        to_.iter()
+            .map(|h| healpix_to_lonlat(h, nside, ellipsoid))
+            .map(|center| geodesic_distance(&first, &center, &geodesic))
+            .collect::<Vec<f64>>()
+    });
+
+    result
+}
+
+/// Geodesic distances in meters between points and cell centers
+///
+/// The points are given as longitude and latitude in degrees. Each point is
+/// compared to the corresponding row of `chunks` cells in `to`.
+pub fn point_geodesic_distances(
+    points: &[(f64, f64)],
+    to: &[u64],
+    chunks: usize,
+    nside: &u32,
+    ellipsoid: &Ellipsoid,
+    nthreads: usize,
+) -> Vec<Vec<f64>> {
+    let geodesic = geodesic(ellipsoid);
+    let data: Vec<(&(f64, f64), &[u64])> = points.iter().zip(to.chunks(chunks)).collect();
+
+    let mut result = Vec::<Vec<f64>>::with_capacity(points.len());
+    maybe_parallelize!(nthreads, data, result, |(point, to_)| {
+        to_.iter()
+            .map(|h| healpix_to_lonlat(h, nside, ellipsoid))
+            .map(|center| geodesic_distance(point, &center, &geodesic))
+            .collect::<Vec<f64>>()
+    });
+
+    result
+}
@@ -0,0 +1,57 @@
+use crate::ellipsoid::Ellipsoid;
+use crate::maybe_parallelize;
+use crate::scalar::geometry::{geodesic, geodesic_distance};
+use crate::scalar::zuniq::coordinates::healpix_to_lonlat;
+
+#[cfg(not(target_arch = "wasm32"))]
+use rayon::prelude::*;
+
+/// Geodesic distances in meters between cell centers on the ellipsoid
+///
+/// The cells may be at different depths.
+pub fn geodesic_distances(
+    from: &[u64],
+    to: &[u64],
+    chunks: usize,
+    ellipsoid: &Ellipsoid,
+    nthreads: usize,
+) -> Vec<Vec<f64>> {
+    let geodesic = geodesic(ellipsoid);
+    let data: Vec<(&u64, &[u64])> = from.iter().zip(to.chunks(chunks)).collect();
+
+    let mut result = Vec::<Vec<f64>>::with_capacity(from.len());
+    maybe_parallelize!(nthreads, data, result, |(from_, to_)| {
+        let first = healpix_to_lonlat(from_, ellipsoid);
+        to_.iter()
+            .map(|h| healpix_to_lonlat(h, ellipsoid))
+            .map(|center| geodesic_distance(&first, &center, &geodesic))
+            .collect::<Vec<f64>>()
+    });
+
+    result
+}
+
+/// Geodesic distances in meters between points and cell centers
+///
+/// The points are given as longitude and latitude in degrees. Each point is
+/// compared to the corresponding row of `chunks` cells in `to`.
+pub fn point_geodesic_distances(
+    points: &[(f64, f64)],
+    to: &[u64],
+    chunks: usize,
+    ellipsoid: &Ellipsoid,
+    nthreads: usize,
+) -> Vec<Vec<f64>> {
+    let geodesic = geodesic(ellipsoid);
+    let data: Vec<(&(f64, f64), &[u64])> = points.iter().zip(to.chunks(chunks)).collect();
+
+    let mut result = Vec::<Vec<f64>>::with_capacity(points.len());
+    maybe_parallelize!(nthreads, data, result, |(point, to_)| {
+        to_.iter()
+            .map(|h| healpix_to_lonlat(h, ellipsoid))
+            .map(|center| geodesic_distance(point, &center, &geodesic))
+            .collect::<Vec<f64>>()
+    });
+
+    result
+}
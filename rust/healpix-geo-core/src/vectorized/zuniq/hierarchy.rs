@@ -1,8 +1,9 @@
 #[cfg(not(target_arch = "wasm32"))]
 use rayon::prelude::*;
 
-use crate::maybe_parallelize;
+use crate::scalar::hierarchy::{n_neighbourhood, n_neighbours};
 use crate::scalar::zuniq::hierarchy as scalar;
+use crate::{maybe_parallelize, maybe_parallelize_rows};
 
 pub fn kth_neighbours(ipix: &[u64], ring: &u32, nthreads: usize) -> Vec<Vec<i64>> {
     let mut result = Vec::<Vec<i64>>::with_capacity(ipix.len());
@@ -23,3 +24,76 @@ pub fn kth_neighbourhood(ipix: &[u64], ring: &u32, nthreads: usize) -> Vec<Vec<i
 
     result
 }
+
+/// Like `kth_neighbours`, but returns a flat buffer with `8 * ring` values per cell
+///
+/// Missing neighbours are set to -1. The buffer can be viewed as a 2-D array
+/// of shape `(ipix.len(), 8 * ring)`.
+pub fn kth_neighbours_flat(ipix: &[u64], ring: &u32, nthreads: usize) -> Vec<i64> {
+    let stride = n_neighbours(ring);
+    let mut result = vec![-1i64; ipix.len() * stride];
+
+    maybe_parallelize_rows!(nthreads, ipix, result, stride, |(hash, row)| {
+        scalar::kth_neighbours_into(hash, ring, row)
+    });
+
+    result
+}
+
+/// Like `kth_neighbourhood`, but returns a flat buffer with `(2 * ring + 1)²` values per cell
+///
+/// Missing neighbours are set to -1. The buffer can be viewed as a 2-D array
+/// of shape `(ipix.len(), (2 * ring + 1)²)`.
+pub fn kth_neighbourhood_flat(ipix: &[u64], ring: &u32, nthreads: usize) -> Vec<i64> {
+    let stride = n_neighbourhood(ring);
+    let mut result = vec![-1i64; ipix.len() * stride];
+
+    maybe_parallelize_rows!(nthreads, ipix, result, stride, |(hash, row)| {
+        scalar::kth_neighbourhood_into(hash, ring, row)
+    });
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vectorized::nested::hierarchy as nested;
+
+    #[test]
+    fn test_flat_neighbours() {
+        let depth = 2;
+        let layer = cdshealpix::nested::get(depth);
+        // includes cells at the corners of base cells, with missing neighbours
+        let nested_ipix = vec![0, 5, 15, 16, 100];
+        let ipix: Vec<u64> = nested_ipix
+            .iter()
+            .map(|&h| cdshealpix::nested::to_zuniq(depth, h))
+            .collect();
+        let convert = |values: Vec<i64>| -> Vec<i64> {
+            values
+                .into_iter()
+                .map(|v| {
+                    if v == -1 {
+                        v
+                    } else {
+                        cdshealpix::nested::to_zuniq(depth, v as u64) as i64
+                    }
+                })
+                .collect()
+        };
+
+        for ring in [0, 1, 2] {
+            let expected = convert(nested::kth_neighbours_flat(&nested_ipix, layer, &ring, 0));
+            assert_eq!(kth_neighbours_flat(&ipix, &ring, 0), expected);
+
+            let expected = convert(nested::kth_neighbourhood_flat(
+                &nested_ipix,
+                layer,
+                &ring,
+                0,
+            ));
+            assert_eq!(kth_neighbourhood_flat(&ipix, &ring, 0), expected);
+        }
+    }
+}
@@ -1,4 +1,5 @@
 pub mod conversion;
 pub mod coordinates;
 pub mod coverage;
+pub mod distances;
 pub mod hierarchy;